@@ -1,6 +1,8 @@
 use std::str::FromStr;
 
 use clap::{command, Parser, Subcommand};
+use signature::suite::ed25519_2020::Ed25519Signature;
+use signature::suite::{DIDSigner, VerificationRelation};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -22,7 +24,7 @@ enum Command {
         #[arg(short, long)]
         input_file: String,
     },
-    #[cfg(feature = "v2_test")]
+    /// Sign the credential in input_file with the key pair in key_pair_file at the given time, then compare the proof with the one of the credential in comparison_file
     TestEd25519Signature2020 {
         #[arg(short, long)]
         input_file: String,
@@ -30,61 +32,116 @@ enum Command {
         key_pair_file: String,
         #[arg(short, long)]
         comparison_file: String,
+        #[arg(long, default_value = "2023-02-24T23:36:38Z")]
+        created: String,
+        #[arg(
+            long,
+            default_value = "https://vc.example/issuers/5678#z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2"
+        )]
+        verification_method: String,
     },
 }
 
-fn main() {
+// Signs with the key pair of the test vectors under their verification method, which is not derived from the key
+#[derive(Debug)]
+struct TestVectorSigner {
+    signer: signature::suite::ed25519_2020::Ed25519DidSigner,
+    verification_method: String,
+}
+
+impl DIDSigner<Ed25519Signature> for TestVectorSigner {
+    fn relational_sign(
+        &self,
+        msg: &[u8],
+        relation: VerificationRelation,
+    ) -> Result<Ed25519Signature, signature::suite::error::Error> {
+        self.signer.relational_sign(msg, relation)
+    }
+
+    fn try_sign(&self, msg: &[u8]) -> Result<Ed25519Signature, signature::suite::error::Error> {
+        self.signer.try_sign(msg)
+    }
+
+    fn get_proof_type(&self) -> String {
+        self.signer.get_proof_type()
+    }
+
+    fn get_verification_method(&self, _relation: VerificationRelation) -> String {
+        self.verification_method.clone()
+    }
+
+    fn encode(&self, sig: Ed25519Signature) -> String {
+        self.signer.encode(sig)
+    }
+}
+
+// Invalid documents are reported on stderr with a non-zero exit code, which the test suites rely on
+fn main() -> std::process::ExitCode {
     let args = CliArguments::parse();
-    match args.command {
+    match run(args.command) {
+        Ok(()) => std::process::ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(command: Command) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
         Command::TestIssueCredential {
             input_file: input_file_path,
         } => {
-            let input = std::fs::read_to_string(input_file_path).unwrap();
-            let vc = ssi_core::credential::VerifiableCredential::from_str(&input).unwrap();
+            let input = std::fs::read_to_string(input_file_path)?;
+            let vc = ssi_core::credential::VerifiableCredential::from_str(&input)?;
             println!("{}", vc);
         }
         Command::TestIssuePresentation {
             input_file: input_file_path,
         } => {
-            let input = std::fs::read_to_string(input_file_path).unwrap();
-            let vc = ssi_core::credential::VerifiablePresentation::from_str(&input).unwrap();
-            println!("{}", vc);
+            let input = std::fs::read_to_string(input_file_path)?;
+            let vp = ssi_core::credential::VerifiablePresentation::from_str(&input)?;
+            println!("{}", vp);
         }
-        #[cfg(feature = "v2_test")]
         Command::TestEd25519Signature2020 {
             input_file,
             key_pair_file,
             comparison_file,
+            created,
+            verification_method,
         } => {
-            let input = std::fs::read_to_string(input_file).unwrap();
-            let kp_file = std::fs::read_to_string(key_pair_file).unwrap();
-            let kp: serde_json::Value = serde_json::from_str(&kp_file).unwrap();
+            let input = std::fs::read_to_string(input_file)?;
+            let kp_file = std::fs::read_to_string(key_pair_file)?;
+            let kp: serde_json::Value = serde_json::from_str(&kp_file)?;
             let private_key = kp
                 .get("privateKeyMultibase")
-                .unwrap()
-                .as_str()
-                .unwrap()
+                .and_then(serde_json::Value::as_str)
+                .ok_or("privateKeyMultibase is missing from the key pair file")?
                 .to_string();
             let kp = signature::suite::ed25519_2020::Ed25519KeyPair::from_private_key(
                 "test".to_string(),
                 private_key,
-            )
-            .unwrap();
-            let signer: signature::suite::ed25519_2020::Ed25519DidSigner = kp.into();
-            let c = ssi_core::credential::Credential::from_str(&input).unwrap();
-            let vc = c
-                .try_into_verifiable_credential_for_test(
-                    &signer,
-                    "2023-02-24T23:36:38Z".to_string(),
-                    "https://vc.example/issuers/5678#z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2".to_string(),
-                )
-                .unwrap();
+            )?;
+            let signer = TestVectorSigner {
+                signer: kp.into(),
+                verification_method,
+            };
+            let clock = ssi_core::clock::FixedClock(created.parse()?);
+            let c = ssi_core::credential::Credential::from_str(&input)?;
+            let vc = c.try_into_verifiable_credential(
+                &signer,
+                VerificationRelation::AssertionMethod,
+                &clock,
+            )?;
 
-            let comparison_vc_str = std::fs::read_to_string(comparison_file).unwrap();
+            let comparison_vc_str = std::fs::read_to_string(comparison_file)?;
             let comparison_vc =
-                ssi_core::credential::VerifiableCredential::from_str(&comparison_vc_str).unwrap();
-            assert_eq!(vc.proof, comparison_vc.proof);
+                ssi_core::credential::VerifiableCredential::from_str(&comparison_vc_str)?;
+            if vc.proof != comparison_vc.proof {
+                return Err("Comparison VC does not match VC generated by CLI".into());
+            }
             println!("Comparison VC matches VC generated by CLI")
         }
     }
+    Ok(())
 }
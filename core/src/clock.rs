@@ -0,0 +1,32 @@
+/// Source of the current time of issuers and verifiers, so that it can be fixed in tests and test vectors.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> chrono::DateTime<chrono::Utc>;
+}
+
+/// The system time, used unless a clock is injected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+/// A clock stopped at the given time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock(pub chrono::DateTime<chrono::Utc>);
+
+/// Difference tolerated between the clock of a verifier and the clocks of issuers.
+pub const DEFAULT_CLOCK_SKEW_SECONDS: i64 = 300;
+
+impl Clock for SystemClock {
+    fn now(&self) -> chrono::DateTime<chrono::Utc> {
+        chrono::Utc::now()
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> chrono::DateTime<chrono::Utc> {
+        self.0
+    }
+}
+
+/// The default clock skew tolerance, see `DEFAULT_CLOCK_SKEW_SECONDS`.
+pub fn default_clock_skew() -> chrono::Duration {
+    chrono::Duration::seconds(DEFAULT_CLOCK_SKEW_SECONDS)
+}
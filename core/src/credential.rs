@@ -1,13 +1,20 @@
 // This module attempts to provide a relatively simple & high level way of interacting with Credentials, Verifiable Credentials, Presentations and Verifiable Presentations
 // Adheres to the https://www.w3.org/TR/vc-data-model/ spec.
 
-// cred_subject is a generic that implements trait X
-// trait X allows us to encode that object into JSON-LD
-// We provide types that implement trait X for the cred types that we support
-// Users can also user their own types that implement trait X if they need a different structure
+// cred_subject is a generic that implements `subject::TypedCredentialSubject`
+// The trait allows us to encode that object into JSON-LD
+// We provide types that implement the trait for the cred types that we support (`subject::BankAccount`, `subject::PermanentResident`)
+// Users can also implement the trait for their own types if they need a different structure
 // ---
 // Default context and Cred types are defaulted but can be redefined
+pub mod context_adherence;
+pub mod context_registry;
+pub mod preloaded_contexts;
+pub mod schema;
+pub mod status;
+pub mod subject;
 mod validation;
+pub mod validity;
 
 use serde_valid::json::{FromJsonStr, ToJsonString};
 use serde_valid::Validate;
@@ -22,20 +29,60 @@ pub enum ContextValue {
 
 pub type DocumentContext = Vec<ContextValue>;
 
-cfg_if::cfg_if! {
-    if #[cfg(feature = "v2_test")] {
-        pub const BASE_CREDENTIAL_CONTEXT: &str = "https://www.w3.org/ns/credentials/v2";
-        pub const EXAMPLE_CREDENTIAL_CONTEXT: &str = "https://www.w3.org/ns/credentials/examples/v2";
+pub const CREDENTIAL_V1_CONTEXT: &str = "https://www.w3.org/2018/credentials/v1";
+pub const CREDENTIAL_V2_CONTEXT: &str = "https://www.w3.org/ns/credentials/v2";
+pub const EXAMPLE_CREDENTIAL_V1_CONTEXT: &str = "https://www.w3.org/2018/credentials/examples/v1";
+pub const EXAMPLE_CREDENTIAL_V2_CONTEXT: &str = "https://www.w3.org/ns/credentials/examples/v2";
 
-    } else {
-        pub const BASE_CREDENTIAL_CONTEXT: &str = "https://www.w3.org/2018/credentials/v1";
-        pub const EXAMPLE_CREDENTIAL_CONTEXT: &str = "https://www.w3.org/2018/credentials/examples/v1";
-    }
-}
+// The context used for new documents unless a version is chosen explicitly
+pub const BASE_CREDENTIAL_CONTEXT: &str = CREDENTIAL_V1_CONTEXT;
+pub const EXAMPLE_CREDENTIAL_CONTEXT: &str = EXAMPLE_CREDENTIAL_V1_CONTEXT;
 
 pub const BANK_ACCOUNT_CREDENTIAL_CONTEXT: &str = "https://w3id.org/traceability/v1";
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+/// Version of the VC Data Model a document follows, detected from its first `@context` entry.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CredentialVersion {
+    /// https://www.w3.org/TR/vc-data-model/ (`issuanceDate`/`expirationDate`)
+    V1,
+    /// https://www.w3.org/TR/vc-data-model-2.0/ (`validFrom`/`validUntil`)
+    V2,
+}
+
+impl CredentialVersion {
+    pub fn from_context(context: &[ContextValue]) -> Option<Self> {
+        match context.first() {
+            Some(ContextValue::String(s)) if s == CREDENTIAL_V1_CONTEXT => Some(Self::V1),
+            Some(ContextValue::String(s)) if s == CREDENTIAL_V2_CONTEXT => Some(Self::V2),
+            _ => None,
+        }
+    }
+
+    /// Detect the version of a serialized document, e.g. before creating its proof.
+    pub fn from_document(doc: &serde_json::Value) -> Option<Self> {
+        let context: DocumentContext = serde_json::from_value(doc.get("@context")?.clone()).ok()?;
+        Self::from_context(&context)
+    }
+
+    pub fn base_context(&self) -> &'static str {
+        match self {
+            Self::V1 => CREDENTIAL_V1_CONTEXT,
+            Self::V2 => CREDENTIAL_V2_CONTEXT,
+        }
+    }
+
+    pub fn example_context(&self) -> &'static str {
+        match self {
+            Self::V1 => EXAMPLE_CREDENTIAL_V1_CONTEXT,
+            Self::V2 => EXAMPLE_CREDENTIAL_V2_CONTEXT,
+        }
+    }
+}
+
+// Types are (de)serialized as their name, any type without a variant of its own is kept in `Other`.
+// Parse names with `from_str` so that known types never end up in `Other`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+#[serde(from = "String", into = "String")]
 pub enum CredentialType {
     VerifiableCredential, // credential type common to all credentials
     PermanentResidentCard,
@@ -43,12 +90,91 @@ pub enum CredentialType {
     BankAccount,
     UniversityDegreeCredential,
     AlumniCredential,
+    BitstringStatusListCredential,
+    StatusList2021Credential,
+    Other(String),
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+#[serde(from = "String", into = "String")]
 pub enum PresentationType {
     VerifiablePresentation, // presentation type common to all presentations
     CredentialManagerPresentation,
+    Other(String),
+}
+
+// A `name` or `description` is either a plain string or one or more language-tagged values
+// https://www.w3.org/TR/vc-data-model-2.0/#names-and-descriptions
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum LanguageValue {
+    String(String),
+    Single(LanguageString),
+    Set(Vec<LanguageString>),
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LanguageString {
+    #[serde(rename = "@value")]
+    pub value: String,
+
+    #[serde(rename = "@language")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+
+    #[serde(rename = "@direction")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direction: Option<String>,
+}
+
+// The issuer is either its id or an object with the id and more information about the issuer
+// https://www.w3.org/TR/vc-data-model-2.0/#issuer
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Issuer {
+    Id(String),
+    Object(IssuerObject),
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct IssuerObject {
+    pub id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub name: Option<LanguageValue>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub image: Option<String>,
+
+    #[serde(flatten)]
+    pub property_set: std::collections::HashMap<String, serde_json::Value>,
+}
+
+impl Issuer {
+    pub fn id(&self) -> &str {
+        match self {
+            Issuer::Id(id) => id,
+            Issuer::Object(issuer) => &issuer.id,
+        }
+    }
+}
+
+impl From<String> for Issuer {
+    fn from(id: String) -> Self {
+        Issuer::Id(id)
+    }
+}
+
+impl From<&str> for Issuer {
+    fn from(id: &str) -> Self {
+        Issuer::Id(id.to_string())
+    }
+}
+
+impl From<IssuerObject> for Issuer {
+    fn from(issuer: IssuerObject) -> Self {
+        Issuer::Object(issuer)
+    }
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
@@ -59,6 +185,20 @@ pub enum CredentialSubject {
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Validate)]
+#[rule(validation::credential_version_validation(
+    context,
+    issuance_date,
+    expiration_date,
+    valid_from,
+    valid_until
+))]
+#[rule(validation::validity_period_validation(
+    issuance_date,
+    expiration_date,
+    valid_from,
+    valid_until
+))]
+#[rule(validation::optional_uri_validation(id))]
 pub struct Credential {
     #[validate(custom(validation::credential_context_validation))]
     #[serde(rename = "@context")]
@@ -67,22 +207,52 @@ pub struct Credential {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
 
+    #[validate(custom(validation::credential_type_validation))]
     #[serde(rename = "type")]
     pub cred_type: Vec<CredentialType>,
 
     #[serde(rename = "issuanceDate")]
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    #[serde(deserialize_with = "validation::rfc3339_date")]
     pub issuance_date: Option<chrono::DateTime<chrono::Utc>>, //chrono by default serializes to RFC3339
 
     #[serde(rename = "expirationDate")]
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    #[serde(deserialize_with = "validation::rfc3339_date")]
     pub expiration_date: Option<chrono::DateTime<chrono::Utc>>, //chrono by default serializes to RFC3339
 
-    pub issuer: String,
+    // `validFrom` and `validUntil` replace `issuanceDate` and `expirationDate` in VC Data Model 2.0
+    #[serde(rename = "validFrom")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    #[serde(deserialize_with = "validation::rfc3339_date")]
+    pub valid_from: Option<chrono::DateTime<chrono::Utc>>,
+
+    #[serde(rename = "validUntil")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    #[serde(deserialize_with = "validation::rfc3339_date")]
+    pub valid_until: Option<chrono::DateTime<chrono::Utc>>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub name: Option<LanguageValue>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub description: Option<LanguageValue>,
+
+    #[validate(custom(validation::issuer_validation))]
+    pub issuer: Issuer,
 
+    #[validate(custom(validation::credential_subject_validation))]
     #[serde(rename = "credentialSubject")]
     pub subject: CredentialSubject,
 
+    #[serde(rename = "credentialStatus")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub credential_status: Option<status::CredentialStatus>,
+
+    #[serde(rename = "credentialSchema")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub credential_schema: Option<schema::CredentialSchema>,
+
     #[serde(flatten)]
     pub property_set: std::collections::HashMap<String, serde_json::Value>,
 }
@@ -92,10 +262,13 @@ pub struct VerifiableCredential {
     #[serde(flatten)]
     #[validate]
     pub credential: Credential,
+    #[validate(custom(validation::proof_validation))]
     pub proof: crate::proof::CredentialProof,
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Validate)]
+#[rule(validation::optional_uri_validation(id))]
+#[rule(validation::optional_uri_validation(holder))]
 pub struct Presentation {
     #[serde(rename = "@context")]
     pub context: DocumentContext,
@@ -103,13 +276,59 @@ pub struct Presentation {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
 
+    #[validate(custom(validation::presentation_type_validation))]
     #[serde(rename = "type")]
     pub presentation_type: Vec<PresentationType>,
 
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub holder: Option<String>,
+
     #[serde(rename = "verifiableCredential")]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[validate]
-    pub verifiable_credential: Option<Vec<VerifiableCredential>>,
+    pub verifiable_credential: Option<Vec<PresentationCredential>>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
+pub enum EnvelopedCredentialType {
+    EnvelopedVerifiableCredential,
+}
+
+/// A credential secured by an enveloping proof (e.g. a JWT or SD-JWT), embedded in a VC Data Model 2.0
+/// presentation as a `data:` URL.
+/// https://www.w3.org/TR/vc-data-model-2.0/#enveloped-verifiable-credentials
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, Validate)]
+pub struct EnvelopedVerifiableCredential {
+    #[validate(custom(validation::enveloped_context_validation))]
+    #[serde(rename = "@context")]
+    pub context: DocumentContext,
+
+    #[validate(custom(validation::data_url_validation))]
+    pub id: String,
+
+    #[serde(rename = "type")]
+    pub envelope_type: EnvelopedCredentialType,
+}
+
+// Embedded credentials are the common case, so they are not boxed
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Validate)]
+#[serde(untagged)]
+pub enum PresentationCredential {
+    Embedded(#[validate] VerifiableCredential),
+    Enveloped(#[validate] EnvelopedVerifiableCredential),
+}
+
+impl From<VerifiableCredential> for PresentationCredential {
+    fn from(vc: VerifiableCredential) -> Self {
+        PresentationCredential::Embedded(vc)
+    }
+}
+
+impl From<EnvelopedVerifiableCredential> for PresentationCredential {
+    fn from(vc: EnvelopedVerifiableCredential) -> Self {
+        PresentationCredential::Enveloped(vc)
+    }
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Validate)]
@@ -118,24 +337,15 @@ pub struct VerifiablePresentation {
     #[validate]
     pub presentation: Presentation,
 
+    #[validate(custom(validation::proof_validation))]
     pub proof: crate::proof::CredentialProof,
 }
 
 impl FromStr for CredentialType {
-    type Err = super::error::Error;
+    type Err = std::convert::Infallible;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "VerifiableCredential" => Ok(CredentialType::VerifiableCredential),
-            "PermanentResidentCard" => Ok(CredentialType::PermanentResidentCard),
-            "BankCard" => Ok(CredentialType::BankCard),
-            "BankAccount" => Ok(CredentialType::BankAccount),
-            "UniversityDegreeCredential" => Ok(CredentialType::UniversityDegreeCredential),
-            "AlumniCredential" => Ok(CredentialType::AlumniCredential),
-            _ => Err(super::error::Error::Unknown(
-                "Unknown CredentialType".to_string(),
-            )),
-        }
+        Ok(CredentialType::from(s.to_string()))
     }
 }
 
@@ -148,10 +358,87 @@ impl CredentialType {
             CredentialType::BankAccount => "BankAccount",
             CredentialType::UniversityDegreeCredential => "UniversityDegreeCredential",
             CredentialType::AlumniCredential => "AlumniCredential",
+            CredentialType::BitstringStatusListCredential => "BitstringStatusListCredential",
+            CredentialType::StatusList2021Credential => "StatusList2021Credential",
+            CredentialType::Other(other) => other,
+        }
+    }
+}
+
+impl From<String> for CredentialType {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "VerifiableCredential" => CredentialType::VerifiableCredential,
+            "PermanentResidentCard" => CredentialType::PermanentResidentCard,
+            "BankCard" => CredentialType::BankCard,
+            "BankAccount" => CredentialType::BankAccount,
+            "UniversityDegreeCredential" => CredentialType::UniversityDegreeCredential,
+            "AlumniCredential" => CredentialType::AlumniCredential,
+            "BitstringStatusListCredential" => CredentialType::BitstringStatusListCredential,
+            "StatusList2021Credential" => CredentialType::StatusList2021Credential,
+            _ => CredentialType::Other(s),
+        }
+    }
+}
+
+impl From<CredentialType> for String {
+    fn from(cred_type: CredentialType) -> Self {
+        match cred_type {
+            CredentialType::Other(other) => other,
+            cred_type => cred_type.as_str().to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for CredentialType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for PresentationType {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(PresentationType::from(s.to_string()))
+    }
+}
+
+impl PresentationType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            PresentationType::VerifiablePresentation => "VerifiablePresentation",
+            PresentationType::CredentialManagerPresentation => "CredentialManagerPresentation",
+            PresentationType::Other(other) => other,
         }
     }
 }
 
+impl From<String> for PresentationType {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "VerifiablePresentation" => PresentationType::VerifiablePresentation,
+            "CredentialManagerPresentation" => PresentationType::CredentialManagerPresentation,
+            _ => PresentationType::Other(s),
+        }
+    }
+}
+
+impl From<PresentationType> for String {
+    fn from(presentation_type: PresentationType) -> Self {
+        match presentation_type {
+            PresentationType::Other(other) => other,
+            presentation_type => presentation_type.as_str().to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for PresentationType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 impl std::fmt::Display for Credential {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self.to_json_string() {
@@ -187,38 +474,77 @@ impl FromStr for VerifiableCredential {
 }
 
 impl Credential {
+    /// The VC Data Model version of the credential, detected from its `@context`.
+    pub fn version(&self) -> Option<CredentialVersion> {
+        CredentialVersion::from_context(&self.context)
+    }
+
+    /// Deserialize the subject of the credential into `T`, which must describe a subject of one of the credential types.
+    pub fn subject_as<T: subject::TypedCredentialSubject>(&self) -> Result<T, super::error::Error> {
+        let subject = match &self.subject {
+            CredentialSubject::Single(subject) => T::from_subject(subject)?,
+            CredentialSubject::Set(_) => {
+                return Err(super::error::Error::InvalidCredentialSubject(
+                    "Expected a single credential subject".to_string(),
+                ))
+            }
+        };
+        let cred_type = subject.credential_type();
+        match self.cred_type.contains(&cred_type) {
+            true => Ok(subject),
+            false => Err(super::error::Error::InvalidCredentialSubject(format!(
+                "The credential is not of type {cred_type}"
+            ))),
+        }
+    }
+
+    /// Sign the credential, the proof being created at the time of `clock`.
+    /// The credential is rejected if any of its terms is not defined by its contexts, as it would not be signed, see
+    /// `context_adherence::verify_context_adherence`.
     pub fn try_into_verifiable_credential<S: signature::suite::Signature>(
         self,
         issuer_signer: &impl signature::suite::DIDSigner<S>,
         relation: signature::suite::VerificationRelation,
+        clock: &(impl crate::clock::Clock + ?Sized),
     ) -> Result<VerifiableCredential, super::error::Error> {
-        let serialized_credential = serde_json::to_value(&self)?;
-        let proof = crate::proof::create_data_integrity_proof(
+        let options = crate::proof::SigningOptions {
+            strict_context_adherence: true,
+            ..Default::default()
+        };
+        self.try_into_verifiable_credential_with_options(issuer_signer, relation, clock, &options)
+    }
+
+    /// Sign the credential as `try_into_verifiable_credential` does, without checking that its terms are defined by
+    /// its contexts.
+    pub fn try_into_verifiable_credential_unchecked<S: signature::suite::Signature>(
+        self,
+        issuer_signer: &impl signature::suite::DIDSigner<S>,
+        relation: signature::suite::VerificationRelation,
+        clock: &(impl crate::clock::Clock + ?Sized),
+    ) -> Result<VerifiableCredential, super::error::Error> {
+        self.try_into_verifiable_credential_with_options(
             issuer_signer,
-            serialized_credential,
             relation,
-        )?;
-
-        Ok(VerifiableCredential {
-            credential: self,
-            proof,
-        })
+            clock,
+            &crate::proof::SigningOptions::default(),
+        )
     }
 
-    #[cfg(feature = "v2_test")]
-    pub fn try_into_verifiable_credential_for_test<S: signature::suite::Signature>(
+    /// Sign the credential with the given proof `options`, checking its context adherence only if they are strict.
+    pub fn try_into_verifiable_credential_with_options<S: signature::suite::Signature>(
         self,
         issuer_signer: &impl signature::suite::DIDSigner<S>,
-        proof_time: String,
-        verification_method: String,
+        relation: signature::suite::VerificationRelation,
+        clock: &(impl crate::clock::Clock + ?Sized),
+        options: &crate::proof::SigningOptions,
     ) -> Result<VerifiableCredential, super::error::Error> {
-        let proof_time = chrono::DateTime::parse_from_rfc3339(&proof_time).unwrap();
         let serialized_credential = serde_json::to_value(&self)?;
-        let proof = crate::proof::create_data_integrity_proof_for_test(
+        let proof = crate::proof::create_data_integrity_proof_with_options(
             issuer_signer,
             serialized_credential,
-            proof_time.into(),
-            verification_method,
+            relation,
+            clock,
+            options,
         )?;
 
         Ok(VerifiableCredential {
@@ -238,17 +564,48 @@ impl Credential {
     }
 }
 
+impl EnvelopedVerifiableCredential {
+    /// Envelope `data` of the given media type (e.g. `application/vc+jwt`) in a `data:` URL.
+    pub fn new(media_type: &str, data: &str) -> Self {
+        EnvelopedVerifiableCredential {
+            context: vec![ContextValue::String(CREDENTIAL_V2_CONTEXT.to_string())],
+            id: format!("data:{media_type},{data}"),
+            envelope_type: EnvelopedCredentialType::EnvelopedVerifiableCredential,
+        }
+    }
+
+    pub fn media_type(&self) -> Option<&str> {
+        self.id
+            .strip_prefix("data:")?
+            .split_once(',')
+            .map(|(m, _)| m)
+    }
+
+    pub fn data(&self) -> Option<&str> {
+        self.id.split_once(',').map(|(_, d)| d)
+    }
+}
+
 impl Presentation {
+    /// Sign the presentation, the holder defaulting to the DID of the signer's verification method.
+    /// The proof is created at the time of `clock`.
     pub fn try_into_verifiable_presentation<S: signature::suite::Signature>(
-        self,
+        mut self,
         issuer_signer: &impl signature::suite::DIDSigner<S>,
         relation: signature::suite::VerificationRelation,
+        clock: &(impl crate::clock::Clock + ?Sized),
     ) -> Result<VerifiablePresentation, super::error::Error> {
+        if self.holder.is_none() {
+            let verification_method =
+                signature::did::DidUrl::from_str(&issuer_signer.get_verification_method(relation))?;
+            self.holder = Some(verification_method.did().to_string());
+        }
         let serialized_presentation = serde_json::to_value(&self)?;
         let proof = crate::proof::create_data_integrity_proof(
             issuer_signer,
             serialized_presentation,
             relation,
+            clock,
         )?;
 
         Ok(VerifiablePresentation {
@@ -356,4 +713,355 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_create_v2_credential_from_string() {
+        let expect = json!({
+            "@context": ["https://www.w3.org/ns/credentials/v2", "https://www.w3.org/ns/credentials/examples/v2"],
+            "id": "http://university.example/credentials/3732",
+            "type": ["VerifiableCredential", "UniversityDegreeCredential"],
+            "issuer": "https://university.example/issuers/565049",
+            "name": "Example University Degree",
+            "description": [
+                {"@value": "Example University Degree", "@language": "en"},
+                {"@value": "Exemple de Diplôme Universitaire", "@language": "fr"}
+            ],
+            "validFrom": "2010-01-01T19:23:24Z",
+            "validUntil": "2020-01-01T19:23:24Z",
+            "credentialSubject": {
+                "id": "did:example:ebfeb1f712ebc6f1c276e12ec21",
+                "degree": {
+                    "type": "ExampleBachelorDegree",
+                    "name": "Bachelor of Science and Arts"
+                }
+            }
+        });
+
+        let credential = Credential::from_str(&expect.to_string()).unwrap();
+        assert_eq!(credential.version(), Some(CredentialVersion::V2));
+        assert_eq!(
+            credential.name,
+            Some(LanguageValue::String(
+                "Example University Degree".to_string()
+            ))
+        );
+        assert!(credential.property_set.is_empty());
+        assert_json_eq!(expect, serde_json::to_value(credential).unwrap());
+    }
+
+    #[rstest::rstest]
+    #[case::v1_with_valid_from(json!({
+        "@context": ["https://www.w3.org/2018/credentials/v1"],
+        "type": ["VerifiableCredential"],
+        "issuer": "did:example:28394728934792387",
+        "issuanceDate": "2019-12-03T12:19:52Z",
+        "validFrom": "2019-12-03T12:19:52Z",
+        "credentialSubject": {"id": "did:example:b34ca6cd37bbf23"}
+    }))]
+    #[case::v2_with_issuance_date(json!({
+        "@context": ["https://www.w3.org/ns/credentials/v2"],
+        "type": ["VerifiableCredential"],
+        "issuer": "did:example:28394728934792387",
+        "issuanceDate": "2019-12-03T12:19:52Z",
+        "credentialSubject": {"id": "did:example:b34ca6cd37bbf23"}
+    }))]
+    fn test_credential_version_mismatch(#[case] credential: serde_json::Value) {
+        assert!(Credential::from_str(&credential.to_string()).is_err());
+    }
+
+    fn create_verifiable_credential_json() -> serde_json::Value {
+        json!({
+            "@context": ["https://www.w3.org/2018/credentials/v1"],
+            "id": "http://example.edu/credentials/1872",
+            "type": ["VerifiableCredential", "AlumniCredential"],
+            "issuer": "https://example.edu/issuers/565049",
+            "issuanceDate": "2010-01-01T19:23:24Z",
+            "expirationDate": "2020-01-01T19:23:24Z",
+            "credentialSubject": {"id": "did:example:ebfeb1f712ebc6f1c276e12ec21", "alumniOf": "Example University"},
+            "proof": {
+                "type": "RsaSignature2018",
+                "created": "2017-06-18T21:19:10Z",
+                "proofPurpose": "assertionMethod",
+                "verificationMethod": "https://example.edu/issuers/keys/1",
+                "jws": "eyJhbGciOiJSUzI1NiIsImI2NCI6ZmFsc2UsImNyaXQiOlsiYjY0Il19..TCYt5X"
+            }
+        })
+    }
+
+    #[rstest::rstest]
+    #[case::valid("id", json!("urn:uuid:3978344f-8596-4c3a-a978-8fcaba3903c5"), true)]
+    #[case::issuer_object("issuer", json!({"id": "did:example:76e12ec712ebc6f1c221ebfeb1f", "name": "Example University"}), true)]
+    #[case::missing_type_entry("type", json!(["AlumniCredential"]), false)]
+    #[case::id_not_uri("id", json!("credentials 1872"), false)]
+    #[case::id_not_single("id", json!(["http://example.edu/credentials/1872"]), false)]
+    #[case::issuer_not_uri("issuer", json!("example.edu"), false)]
+    #[case::issuer_object_without_id("issuer", json!({"name": "Example University"}), false)]
+    #[case::issuance_date_not_rfc3339("issuanceDate", json!("01/01/2010"), false)]
+    #[case::issuance_date_offset_without_colon("issuanceDate", json!("2010-01-01T19:23:24+0000"), false)]
+    #[case::expiration_before_issuance("expirationDate", json!("2009-01-01T19:23:24Z"), false)]
+    #[case::missing_subject("credentialSubject", serde_json::Value::Null, false)]
+    #[case::empty_subject("credentialSubject", json!({}), false)]
+    #[case::empty_subject_set("credentialSubject", json!([]), false)]
+    #[case::subject_id_not_uri("credentialSubject", json!({"id": "ebfeb1f712ebc6f1c276e12ec21"}), false)]
+    #[case::missing_proof("proof", serde_json::Value::Null, false)]
+    #[case::proof_without_type("proof", json!({"verificationMethod": "https://example.edu/issuers/keys/1", "jws": "eyJhbGciOiJSUzI1NiJ9..TCYt5X"}), false)]
+    #[case::proof_method_not_uri("proof", json!({"type": "RsaSignature2018", "verificationMethod": "keys-1", "proofPurpose": "assertionMethod", "jws": "eyJhbGciOiJSUzI1NiJ9..TCYt5X"}), false)]
+    #[case::empty_proof_set("proof", json!([]), false)]
+    fn test_verifiable_credential_structure(
+        #[case] property: &str,
+        #[case] value: serde_json::Value,
+        #[case] valid: bool,
+    ) {
+        let mut vc = create_verifiable_credential_json();
+        match value {
+            serde_json::Value::Null => vc.as_object_mut().unwrap().remove(property),
+            value => vc
+                .as_object_mut()
+                .unwrap()
+                .insert(property.to_string(), value),
+        };
+
+        assert_eq!(
+            VerifiableCredential::from_str(&vc.to_string()).is_ok(),
+            valid
+        );
+    }
+
+    #[rstest::rstest]
+    #[case::valid(json!({"type": ["VerifiablePresentation"], "holder": "did:example:ebfeb1f712ebc6f1c276e12ec21"}), true)]
+    #[case::missing_type_entry(json!({"type": ["CredentialManagerPresentation"]}), false)]
+    #[case::holder_not_uri(json!({"type": ["VerifiablePresentation"], "holder": "ebfeb1f712ebc6f1c276e12ec21"}), false)]
+    #[case::invalid_credential(json!({"type": ["VerifiablePresentation"], "verifiableCredential": [{"type": ["VerifiableCredential"]}]}), false)]
+    fn test_verifiable_presentation_structure(
+        #[case] presentation: serde_json::Value,
+        #[case] valid: bool,
+    ) {
+        let mut vp = json!({
+            "@context": ["https://www.w3.org/2018/credentials/v1"],
+            "id": "urn:uuid:3978344f-8596-4c3a-a978-8fcaba3903c5",
+            "verifiableCredential": [create_verifiable_credential_json()],
+            "proof": create_verifiable_credential_json()["proof"]
+        });
+        for (property, value) in presentation.as_object().unwrap() {
+            vp[property] = value.clone();
+        }
+
+        assert_eq!(
+            VerifiablePresentation::from_str(&vp.to_string()).is_ok(),
+            valid
+        );
+    }
+
+    #[test]
+    fn test_create_presentation_with_enveloped_credential() {
+        let expect = json!({
+            "@context": ["https://www.w3.org/ns/credentials/v2"],
+            "type": ["VerifiablePresentation"],
+            "verifiableCredential": [{
+                "@context": ["https://www.w3.org/ns/credentials/v2"],
+                "id": "data:application/vc+sd-jwt,eyJhbGciOiJFUzM4NCIsImtpZCI6IkdOV2FBTDJQVlVVMkpJVDg5bTBxMzBNY3VrM1FKVEQ0OHpBMzEwX3F6OVkifQ",
+                "type": "EnvelopedVerifiableCredential"
+            }],
+        });
+
+        let presentation = Presentation::from_str(&expect.to_string()).unwrap();
+        match presentation.verifiable_credential.as_deref() {
+            Some([PresentationCredential::Enveloped(vc)]) => {
+                assert_eq!(vc.media_type(), Some("application/vc+sd-jwt"));
+                assert_eq!(
+                    vc,
+                    &EnvelopedVerifiableCredential::new(
+                        "application/vc+sd-jwt",
+                        vc.data().unwrap()
+                    )
+                );
+            }
+            vc => panic!("unexpected credentials {vc:?}"),
+        }
+        assert_json_eq!(expect, serde_json::to_value(presentation).unwrap());
+
+        let mut v1_envelope = expect;
+        v1_envelope["verifiableCredential"][0]["@context"] =
+            json!(["https://www.w3.org/2018/credentials/v1"]);
+        assert!(Presentation::from_str(&v1_envelope.to_string()).is_err());
+    }
+
+    #[rstest::rstest]
+    #[case::known("BankAccount", CredentialType::BankAccount)]
+    #[case::other("DriverLicense", CredentialType::Other("DriverLicense".to_string()))]
+    fn test_credential_type(#[case] name: &str, #[case] expected: CredentialType) {
+        assert_eq!(CredentialType::from_str(name).unwrap(), expected);
+        assert_eq!(
+            serde_json::from_value::<CredentialType>(json!(name)).unwrap(),
+            expected
+        );
+        assert_eq!(serde_json::to_value(&expected).unwrap(), json!(name));
+        assert_eq!(expected.as_str(), name);
+    }
+
+    #[rstest::rstest]
+    #[case::known("VerifiablePresentation", PresentationType::VerifiablePresentation)]
+    #[case::other("LoginPresentation", PresentationType::Other("LoginPresentation".to_string()))]
+    fn test_presentation_type(#[case] name: &str, #[case] expected: PresentationType) {
+        assert_eq!(PresentationType::from_str(name).unwrap(), expected);
+        assert_eq!(
+            serde_json::from_value::<PresentationType>(json!(name)).unwrap(),
+            expected
+        );
+        assert_eq!(serde_json::to_value(&expected).unwrap(), json!(name));
+    }
+
+    #[test]
+    fn test_create_credential_of_other_type_from_string() {
+        let expect = json!({
+            "@context": ["https://www.w3.org/2018/credentials/v1", "https://example.com/driver-license/v1"],
+            "type": ["VerifiableCredential", "DriverLicense"],
+            "issuer": "did:example:28394728934792387",
+            "issuanceDate": "2019-12-03T12:19:52Z",
+            "credentialSubject": {"id": "did:example:b34ca6cd37bbf23", "licenseClass": "B"}
+        });
+
+        let credential = Credential::from_str(&expect.to_string()).unwrap();
+        assert_eq!(
+            credential.cred_type,
+            vec![
+                CredentialType::VerifiableCredential,
+                CredentialType::Other("DriverLicense".to_string())
+            ]
+        );
+        assert_json_eq!(expect, serde_json::to_value(credential).unwrap());
+    }
+
+    #[rstest::rstest]
+    #[case::id(json!("did:example:76e12ec712ebc6f1c221ebfeb1f"), None)]
+    #[case::object(
+        json!({
+            "id": "did:example:76e12ec712ebc6f1c221ebfeb1f",
+            "name": "Example University",
+            "image": "data:image/png;base64,iVBORw0KGgo...YII="
+        }),
+        Some(LanguageValue::String("Example University".to_string()))
+    )]
+    fn test_credential_issuer(
+        #[case] issuer: serde_json::Value,
+        #[case] name: Option<LanguageValue>,
+    ) {
+        let expect = json!({
+            "@context": ["https://www.w3.org/2018/credentials/v1"],
+            "type": ["VerifiableCredential"],
+            "issuer": issuer,
+            "issuanceDate": "2019-12-03T12:19:52Z",
+            "credentialSubject": {"id": "did:example:b34ca6cd37bbf23"}
+        });
+
+        let credential = Credential::from_str(&expect.to_string()).unwrap();
+        assert_eq!(
+            credential.issuer.id(),
+            "did:example:76e12ec712ebc6f1c221ebfeb1f"
+        );
+        match &credential.issuer {
+            Issuer::Id(_) => assert!(name.is_none()),
+            Issuer::Object(issuer) => assert_eq!(issuer.name, name),
+        }
+        assert_json_eq!(expect, serde_json::to_value(credential).unwrap());
+    }
+
+    #[rstest::rstest]
+    #[case::from_signer(None, "did:knox:")]
+    #[case::explicit(
+        Some("did:example:ebfeb1f712ebc6f1c276e12ec21"),
+        "did:example:ebfeb1f712ebc6f1c276e12ec21"
+    )]
+    fn test_presentation_holder(
+        #[case] holder: Option<&str>,
+        #[case] expected_holder_prefix: &str,
+    ) {
+        let signer: signature::suite::ed25519_2020::Ed25519DidSigner =
+            signature::suite::ed25519_2020::Ed25519KeyPair::new("knox".to_string(), None)
+                .unwrap()
+                .into();
+        let presentation = Presentation {
+            context: vec![ContextValue::String(BASE_CREDENTIAL_CONTEXT.to_string())],
+            id: None,
+            presentation_type: vec![PresentationType::VerifiablePresentation],
+            holder: holder.map(str::to_string),
+            verifiable_credential: None,
+        };
+
+        let vp = presentation
+            .try_into_verifiable_presentation(
+                &signer,
+                signature::suite::VerificationRelation::Authentication,
+                &crate::clock::SystemClock,
+            )
+            .unwrap();
+        let vp_holder = vp.presentation.holder.unwrap();
+        assert!(vp_holder.starts_with(expected_holder_prefix));
+        assert!(!vp_holder.contains('#'));
+    }
+
+    // A signer whose verification method is not a DID URL
+    #[derive(Debug)]
+    struct MalformedSigner(signature::suite::ed25519_2020::Ed25519DidSigner);
+
+    impl signature::suite::DIDSigner<signature::suite::ed25519_2020::Ed25519Signature>
+        for MalformedSigner
+    {
+        fn relational_sign(
+            &self,
+            msg: &[u8],
+            relation: signature::suite::VerificationRelation,
+        ) -> Result<signature::suite::ed25519_2020::Ed25519Signature, signature::suite::error::Error>
+        {
+            self.0.relational_sign(msg, relation)
+        }
+
+        fn try_sign(
+            &self,
+            msg: &[u8],
+        ) -> Result<signature::suite::ed25519_2020::Ed25519Signature, signature::suite::error::Error>
+        {
+            self.0.try_sign(msg)
+        }
+
+        fn get_proof_type(&self) -> String {
+            self.0.get_proof_type()
+        }
+
+        fn get_verification_method(
+            &self,
+            relation: signature::suite::VerificationRelation,
+        ) -> String {
+            self.0
+                .get_verification_method(relation)
+                .replacen("did:knox:", "did:Knox:", 1)
+        }
+
+        fn encode(&self, sig: signature::suite::ed25519_2020::Ed25519Signature) -> String {
+            self.0.encode(sig)
+        }
+    }
+
+    #[test]
+    fn test_presentation_holder_invalid_verification_method() {
+        let signer = MalformedSigner(
+            signature::suite::ed25519_2020::Ed25519KeyPair::new("knox".to_string(), None)
+                .unwrap()
+                .into(),
+        );
+        let presentation = Presentation {
+            context: vec![ContextValue::String(BASE_CREDENTIAL_CONTEXT.to_string())],
+            id: None,
+            presentation_type: vec![PresentationType::VerifiablePresentation],
+            holder: None,
+            verifiable_credential: None,
+        };
+
+        let res = presentation.try_into_verifiable_presentation(
+            &signer,
+            signature::suite::VerificationRelation::Authentication,
+            &crate::clock::SystemClock,
+        );
+        assert!(matches!(res, Err(crate::error::Error::InvalidDid(_))));
+    }
 }
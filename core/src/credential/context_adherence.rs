@@ -0,0 +1,94 @@
+// Strict JSON-LD context adherence: every property and type of a document must be defined by its contexts.
+// JSON-LD expansion silently drops the terms that do not expand to an absolute IRI, so they would not be covered by
+// the signature of the normalized RDF dataset.
+
+// The vocabulary mapping given to the terms that the contexts of a document do not define, see `with_undefined_terms_vocab`
+const UNDEFINED_TERMS_VOCAB: &str = "urn:ssi:undefined-term:";
+
+/// Expand the JSON-LD document as it is expanded for signing, and reject it if any of its properties or types does
+/// not expand to an absolute IRI. The error lists the undefined terms, e.g. `nickname`.
+/// Signing with `proof::SigningOptions::strict_context_adherence` runs the same check on the expansion it signs.
+pub fn verify_context_adherence(doc: &serde_json::Value) -> Result<(), crate::error::Error> {
+    crate::proof::normalization::create_adherent_dataset(doc).map(|_| ())
+}
+
+// Reject the IRIs of a document expanded with `with_undefined_terms_vocab` that come from the undefined terms vocabulary
+pub(crate) fn verify_defined_terms(
+    iris: &std::collections::BTreeSet<String>,
+) -> Result<(), crate::error::Error> {
+    let undefined: Vec<String> = iris
+        .iter()
+        .filter_map(|iri| iri.strip_prefix(UNDEFINED_TERMS_VOCAB))
+        .map(str::to_string)
+        .collect();
+
+    match undefined.is_empty() {
+        true => Ok(()),
+        false => Err(crate::error::Error::UndefinedTerms(undefined)),
+    }
+}
+
+// A vocabulary mapping ahead of the contexts of the document gives the terms they do not define an IRI, instead of
+// dropping them, so that they can be found in the expanded document. Contexts with their own `@vocab` (e.g. the VC 2.0
+// context) still take precedence, the terms expanded with it are covered by the signature.
+pub(crate) fn with_undefined_terms_vocab(doc: &serde_json::Value) -> serde_json::Value {
+    let mut doc = doc.clone();
+    if let Some(doc) = doc.as_object_mut() {
+        let vocab = serde_json::json!({ "@vocab": UNDEFINED_TERMS_VOCAB });
+        let contexts = match doc.remove("@context") {
+            Some(serde_json::Value::Array(contexts)) => contexts,
+            Some(context) => vec![context],
+            None => vec![],
+        };
+        doc.insert(
+            "@context".to_string(),
+            std::iter::once(vocab).chain(contexts).collect(),
+        );
+    }
+    doc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{verify_defined_terms, with_undefined_terms_vocab, UNDEFINED_TERMS_VOCAB};
+    use serde_json::json;
+
+    #[rstest::rstest]
+    #[case::contexts(
+        json!({"@context": ["https://www.w3.org/2018/credentials/v1", {"nickname": "https://schema.org/alternateName"}]}),
+        json!([{"@vocab": UNDEFINED_TERMS_VOCAB}, "https://www.w3.org/2018/credentials/v1", {"nickname": "https://schema.org/alternateName"}])
+    )]
+    #[case::single_context(
+        json!({"@context": "https://www.w3.org/2018/credentials/v1"}),
+        json!([{"@vocab": UNDEFINED_TERMS_VOCAB}, "https://www.w3.org/2018/credentials/v1"])
+    )]
+    #[case::without_context(json!({}), json!([{"@vocab": UNDEFINED_TERMS_VOCAB}]))]
+    fn test_with_undefined_terms_vocab(
+        #[case] doc: serde_json::Value,
+        #[case] expected: serde_json::Value,
+    ) {
+        let mut doc = doc;
+        doc["nickname"] = json!("Al");
+
+        let res = with_undefined_terms_vocab(&doc);
+        assert_eq!(res["@context"], expected);
+        assert_eq!(res["nickname"], doc["nickname"]);
+    }
+
+    #[rstest::rstest]
+    #[case::defined(&["https://schema.org/name"], None)]
+    #[case::undefined(
+        &["https://schema.org/name", "urn:ssi:undefined-term:nickname"],
+        Some(vec!["nickname".to_string()])
+    )]
+    fn test_verify_defined_terms(#[case] iris: &[&str], #[case] expected: Option<Vec<String>>) {
+        let iris = iris.iter().map(|iri| iri.to_string()).collect();
+        match (verify_defined_terms(&iris), expected) {
+            (Ok(()), None) => {}
+            (Err(crate::error::Error::UndefinedTerms(terms)), Some(expected)) => {
+                assert_eq!(terms, expected)
+            }
+            (res, expected) => panic!("unexpected result {res:?}, expected {expected:?}"),
+        }
+    }
+}
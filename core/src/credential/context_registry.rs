@@ -0,0 +1,129 @@
+use super::{ContextValue, CredentialVersion, DocumentContext};
+
+/// Maps credential and presentation type names to the JSON-LD contexts defining their terms.
+/// Documents always start with the base context of their VC Data Model version, followed by the contexts registered
+/// for their types. Types without registered contexts only get the base context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextRegistry {
+    contexts: std::collections::HashMap<String, Vec<String>>,
+}
+
+impl Default for ContextRegistry {
+    fn default() -> Self {
+        let mut registry = ContextRegistry::new();
+        registry.register(
+            super::CredentialType::BankAccount.as_str(),
+            &[super::BANK_ACCOUNT_CREDENTIAL_CONTEXT],
+        );
+        registry.register(
+            super::CredentialType::StatusList2021Credential.as_str(),
+            &[super::status::STATUS_LIST_2021_CONTEXT],
+        );
+        registry
+    }
+}
+
+impl ContextRegistry {
+    /// Create a registry without any type, see `ContextRegistry::default` for the types supported out of the box.
+    pub fn new() -> Self {
+        ContextRegistry {
+            contexts: std::collections::HashMap::new(),
+        }
+    }
+
+    /// Register the contexts required by `type_name`, replacing any previous registration of the type.
+    pub fn register(&mut self, type_name: &str, contexts: &[&str]) -> &mut Self {
+        self.contexts.insert(
+            type_name.to_string(),
+            contexts.iter().map(|c| c.to_string()).collect(),
+        );
+        self
+    }
+
+    pub fn get(&self, type_name: &str) -> &[String] {
+        self.contexts.get(type_name).map_or(&[], Vec::as_slice)
+    }
+
+    /// The `@context` of a document of the given types: the base context of `version` followed by the contexts of
+    /// each type, without duplicates.
+    pub fn get_contexts(&self, version: CredentialVersion, type_names: &[&str]) -> DocumentContext {
+        let mut contexts = vec![version.base_context()];
+        for context in type_names.iter().flat_map(|t| self.get(t)) {
+            if !contexts.contains(&context.as_str()) {
+                contexts.push(context);
+            }
+        }
+        contexts
+            .into_iter()
+            .map(|c| ContextValue::String(c.to_string()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        BANK_ACCOUNT_CREDENTIAL_CONTEXT, BASE_CREDENTIAL_CONTEXT, CREDENTIAL_V2_CONTEXT,
+    };
+    use super::{ContextRegistry, ContextValue, CredentialVersion};
+
+    const DRIVER_LICENSE_CONTEXT: &str = "https://example.com/driver-license/v1";
+
+    fn contexts(uris: &[&str]) -> Vec<ContextValue> {
+        uris.iter()
+            .map(|uri| ContextValue::String(uri.to_string()))
+            .collect()
+    }
+
+    #[rstest::rstest]
+    #[case::bank_account(
+        CredentialVersion::V1,
+        &["VerifiableCredential", "BankAccount"],
+        contexts(&[BASE_CREDENTIAL_CONTEXT, BANK_ACCOUNT_CREDENTIAL_CONTEXT])
+    )]
+    #[case::unregistered(
+        CredentialVersion::V1,
+        &["PermanentResidentCard"],
+        contexts(&[BASE_CREDENTIAL_CONTEXT])
+    )]
+    #[case::custom(
+        CredentialVersion::V1,
+        &["DriverLicense"],
+        contexts(&[BASE_CREDENTIAL_CONTEXT, DRIVER_LICENSE_CONTEXT])
+    )]
+    #[case::no_duplicates(
+        CredentialVersion::V1,
+        &["BankAccount", "DriverLicense", "SavingsAccount"],
+        contexts(&[BASE_CREDENTIAL_CONTEXT, BANK_ACCOUNT_CREDENTIAL_CONTEXT, DRIVER_LICENSE_CONTEXT])
+    )]
+    #[case::v2(
+        CredentialVersion::V2,
+        &["VerifiableCredential", "DriverLicense"],
+        contexts(&[CREDENTIAL_V2_CONTEXT, DRIVER_LICENSE_CONTEXT])
+    )]
+    fn test_get_contexts(
+        #[case] version: CredentialVersion,
+        #[case] type_names: &[&str],
+        #[case] expected: Vec<ContextValue>,
+    ) {
+        let mut registry = ContextRegistry::default();
+        registry
+            .register("DriverLicense", &[DRIVER_LICENSE_CONTEXT])
+            .register(
+                "SavingsAccount",
+                &[BANK_ACCOUNT_CREDENTIAL_CONTEXT, BASE_CREDENTIAL_CONTEXT],
+            );
+
+        assert_eq!(registry.get_contexts(version, type_names), expected);
+    }
+
+    #[test]
+    fn test_register_replaces_contexts() {
+        let mut registry = ContextRegistry::new();
+        registry.register("BankAccount", &[DRIVER_LICENSE_CONTEXT]);
+        assert_eq!(registry.get("BankAccount"), &[DRIVER_LICENSE_CONTEXT]);
+
+        registry.register("BankAccount", &[]);
+        assert!(registry.get("BankAccount").is_empty());
+    }
+}
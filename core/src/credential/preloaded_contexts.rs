@@ -0,0 +1,166 @@
+// JSON-LD contexts preloaded by IRI, used in place of loading them over HTTP when documents are expanded for signing,
+// e.g. to sign offline or to pin the contexts of an issuer. The references to preloaded contexts are replaced by the
+// contexts themselves before expansion, so the documents must be exact copies of the published contexts: signatures
+// created with other definitions would not verify against the published ones.
+
+/// Context documents (`{"@context": ...}`) by the IRI they are published at.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PreloadedContexts {
+    contexts: std::collections::HashMap<String, serde_json::Value>,
+}
+
+impl PreloadedContexts {
+    pub fn new() -> Self {
+        PreloadedContexts::default()
+    }
+
+    /// Preload the context `document` published at `iri`, replacing any previously preloaded document.
+    pub fn insert(&mut self, iri: &str, document: serde_json::Value) -> &mut Self {
+        self.contexts.insert(iri.to_string(), document);
+        self
+    }
+
+    /// Replace the references to preloaded contexts in every `@context` of the document, including the scoped
+    /// contexts of term definitions, by the preloaded contexts. Other references are left to the document loader.
+    pub fn inline(&self, doc: &serde_json::Value) -> serde_json::Value {
+        self.inline_value(doc.clone(), &mut vec![])
+    }
+
+    fn inline_value(
+        &self,
+        value: serde_json::Value,
+        inlining: &mut Vec<String>,
+    ) -> serde_json::Value {
+        match value {
+            serde_json::Value::Array(values) => values
+                .into_iter()
+                .map(|value| self.inline_value(value, inlining))
+                .collect(),
+            serde_json::Value::Object(object) => object
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = match key.as_str() {
+                        "@context" => self.inline_context(value, inlining),
+                        _ => self.inline_value(value, inlining),
+                    };
+                    (key, value)
+                })
+                .collect(),
+            value => value,
+        }
+    }
+
+    // A preloaded context that is itself an array of contexts is spliced in, `@context` arrays can not be nested.
+    // `inlining` holds the contexts being inlined, a context referencing itself is left as a reference.
+    fn inline_context(
+        &self,
+        context: serde_json::Value,
+        inlining: &mut Vec<String>,
+    ) -> serde_json::Value {
+        let (contexts, is_array) = match context {
+            serde_json::Value::Array(contexts) => (contexts, true),
+            context => (vec![context], false),
+        };
+
+        let mut inlined = Vec::with_capacity(contexts.len());
+        for context in contexts {
+            let preloaded = match &context {
+                serde_json::Value::String(iri) if !inlining.contains(iri) => self
+                    .contexts
+                    .get(iri)
+                    .and_then(|document| document.get("@context"))
+                    .map(|preloaded| (iri.clone(), preloaded.clone())),
+                _ => None,
+            };
+            let Some((iri, preloaded)) = preloaded else {
+                inlined.push(self.inline_value(context, inlining));
+                continue;
+            };
+
+            inlining.push(iri);
+            match self.inline_context(preloaded, inlining) {
+                serde_json::Value::Array(contexts) => inlined.extend(contexts),
+                context => inlined.push(context),
+            }
+            inlining.pop();
+        }
+
+        match inlined.len() {
+            1 if !is_array => inlined.remove(0),
+            _ => serde_json::Value::Array(inlined),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PreloadedContexts;
+    use serde_json::json;
+
+    const CONTEXT: &str = "https://example.com/context/v1";
+    const OTHER_CONTEXT: &str = "https://example.com/other/v1";
+
+    fn create_preloaded_contexts() -> PreloadedContexts {
+        let mut preloaded = PreloadedContexts::new();
+        preloaded
+            .insert(
+                CONTEXT,
+                json!({"@context": {"name": "https://schema.org/name"}}),
+            )
+            .insert(
+                OTHER_CONTEXT,
+                json!({"@context": [CONTEXT, {"nickname": "https://schema.org/alternateName"}]}),
+            );
+        preloaded
+    }
+
+    #[rstest::rstest]
+    #[case::single(
+        json!({"@context": CONTEXT, "name": "Alice"}),
+        json!({"@context": {"name": "https://schema.org/name"}, "name": "Alice"})
+    )]
+    #[case::not_preloaded(
+        json!({"@context": ["https://www.w3.org/2018/credentials/v1", CONTEXT]}),
+        json!({"@context": ["https://www.w3.org/2018/credentials/v1", {"name": "https://schema.org/name"}]})
+    )]
+    #[case::array(
+        json!({"@context": [CONTEXT]}),
+        json!({"@context": [{"name": "https://schema.org/name"}]})
+    )]
+    #[case::spliced(
+        json!({"@context": [OTHER_CONTEXT, {"age": "https://schema.org/age"}]}),
+        json!({"@context": [
+            {"name": "https://schema.org/name"},
+            {"nickname": "https://schema.org/alternateName"},
+            {"age": "https://schema.org/age"}
+        ]})
+    )]
+    #[case::scoped(
+        json!({"@context": {"Person": {"@id": "https://schema.org/Person", "@context": CONTEXT}}}),
+        json!({"@context": {"Person": {
+            "@id": "https://schema.org/Person",
+            "@context": {"name": "https://schema.org/name"}
+        }}})
+    )]
+    #[case::nested_node(
+        json!({"credentialSubject": {"@context": CONTEXT, "name": "Alice"}}),
+        json!({"credentialSubject": {"@context": {"name": "https://schema.org/name"}, "name": "Alice"}})
+    )]
+    fn test_inline(#[case] doc: serde_json::Value, #[case] expected: serde_json::Value) {
+        assert_eq!(create_preloaded_contexts().inline(&doc), expected);
+    }
+
+    #[test]
+    fn test_inline_self_reference() {
+        let mut preloaded = PreloadedContexts::new();
+        preloaded.insert(
+            CONTEXT,
+            json!({"@context": [CONTEXT, {"name": "https://schema.org/name"}]}),
+        );
+
+        assert_eq!(
+            preloaded.inline(&json!({"@context": CONTEXT})),
+            json!({"@context": [CONTEXT, {"name": "https://schema.org/name"}]})
+        );
+    }
+}
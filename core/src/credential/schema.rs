@@ -0,0 +1,479 @@
+// Validation of the credential subject against the JSON Schemas declared in `credentialSchema`
+// https://www.w3.org/TR/vc-json-schema/
+
+use std::str::FromStr;
+
+// A schema is either published as is (`JsonSchema`) or as the `jsonSchema` of the subject of a credential
+// (`JsonSchemaCredential`), any other schema type is kept in `Other`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(from = "String", into = "String")]
+pub enum SchemaType {
+    JsonSchema,
+    JsonSchemaCredential,
+    Other(String),
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SchemaEntry {
+    pub id: String,
+
+    #[serde(rename = "type")]
+    pub schema_type: SchemaType,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum CredentialSchema {
+    Single(SchemaEntry),
+    Set(Vec<SchemaEntry>),
+}
+
+/// A credential subject property that does not conform to the schema, `pointer` being the JSON pointer of the property
+/// in the credential.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaViolation {
+    pub pointer: String,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaViolations(pub Vec<SchemaViolation>);
+
+/// Load the document identified by the `id` of a schema entry: the JSON Schema itself or the credential holding it.
+#[mockall::automock]
+#[async_trait::async_trait]
+pub trait SchemaLoader: Send + Sync {
+    async fn load_schema(&self, id: &str)
+        -> Result<serde_json::Value, crate::error::ResolverError>;
+}
+
+// Schemas known in advance, e.g. in tests, are loaded from a map of schema id to document.
+#[async_trait::async_trait]
+impl SchemaLoader for std::collections::HashMap<String, serde_json::Value> {
+    async fn load_schema(
+        &self,
+        id: &str,
+    ) -> Result<serde_json::Value, crate::error::ResolverError> {
+        self.get(id)
+            .cloned()
+            .ok_or_else(|| crate::error::ResolverError::DocumentNotFound(id.to_string()))
+    }
+}
+
+impl FromStr for SchemaType {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(SchemaType::from(s.to_string()))
+    }
+}
+
+impl SchemaType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SchemaType::JsonSchema => "JsonSchema",
+            SchemaType::JsonSchemaCredential => "JsonSchemaCredential",
+            SchemaType::Other(other) => other,
+        }
+    }
+}
+
+impl From<String> for SchemaType {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "JsonSchema" => SchemaType::JsonSchema,
+            "JsonSchemaCredential" => SchemaType::JsonSchemaCredential,
+            _ => SchemaType::Other(s),
+        }
+    }
+}
+
+impl From<SchemaType> for String {
+    fn from(schema_type: SchemaType) -> Self {
+        match schema_type {
+            SchemaType::Other(other) => other,
+            schema_type => schema_type.as_str().to_string(),
+        }
+    }
+}
+
+impl CredentialSchema {
+    pub fn entries(&self) -> Vec<&SchemaEntry> {
+        match self {
+            CredentialSchema::Single(entry) => vec![entry],
+            CredentialSchema::Set(entries) => entries.iter().collect(),
+        }
+    }
+}
+
+impl From<SchemaEntry> for CredentialSchema {
+    fn from(entry: SchemaEntry) -> Self {
+        CredentialSchema::Single(entry)
+    }
+}
+
+impl std::fmt::Display for SchemaViolations {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let violations: Vec<String> = self
+            .0
+            .iter()
+            .map(|v| format!("{}: {}", v.pointer, v.message))
+            .collect();
+        write!(f, "{}", violations.join("; "))
+    }
+}
+
+/// Validate the `credentialSubject` of the credential against `json_schema`. Violations are reported with the JSON
+/// pointer of the offending property in the credential, e.g. `/credentialSubject/birthDate`.
+pub fn validate_credential_subject(
+    credential: &super::Credential,
+    json_schema: &serde_json::Value,
+) -> Result<(), crate::error::Error> {
+    let schema = jsonschema::JSONSchema::compile(json_schema)
+        .map_err(|e| crate::error::Error::InvalidCredentialSchema(e.to_string()))?;
+    let subjects = match &credential.subject {
+        super::CredentialSubject::Single(subject) => {
+            vec![("/credentialSubject".to_string(), subject)]
+        }
+        super::CredentialSubject::Set(subjects) => subjects
+            .iter()
+            .enumerate()
+            .map(|(i, subject)| (format!("/credentialSubject/{i}"), subject))
+            .collect(),
+    };
+
+    let mut violations = vec![];
+    for (pointer, subject) in subjects {
+        let subject = serde_json::to_value(subject)?;
+        if let Err(errors) = schema.validate(&subject) {
+            violations.extend(errors.map(|e| SchemaViolation {
+                pointer: format!("{pointer}{}", e.instance_path),
+                message: e.to_string(),
+            }));
+        };
+    }
+    match violations.is_empty() {
+        true => Ok(()),
+        false => Err(crate::error::Error::SchemaValidation(SchemaViolations(
+            violations,
+        ))),
+    }
+}
+
+/// Load every JSON Schema declared in the `credentialSchema` of the credential with `loader` and validate the
+/// `credentialSubject` against it. Schemas of unsupported types are ignored.
+/// The proofs of `JsonSchemaCredential` schemas are verified with the keys of their issuers, resolved through
+/// `did_resolver`.
+pub async fn verify_credential_schema(
+    credential: &super::Credential,
+    loader: &(impl SchemaLoader + ?Sized),
+    did_resolver: &(impl crate::DIDResolver + ?Sized),
+) -> Result<(), crate::error::Error> {
+    let Some(credential_schema) = &credential.credential_schema else {
+        return Ok(());
+    };
+
+    for entry in credential_schema.entries() {
+        if let SchemaType::Other(schema_type) = &entry.schema_type {
+            tracing::warn!(schema_type, "Unsupported credential schema ignored");
+            continue;
+        }
+        let document = loader
+            .load_schema(&entry.id)
+            .await
+            .map_err(|e| crate::error::Error::InvalidCredentialSchema(e.to_string()))?;
+        let json_schema = get_json_schema(entry, document, did_resolver).await?;
+        validate_credential_subject(credential, &json_schema)?;
+    }
+    Ok(())
+}
+
+// The schema of a `JsonSchemaCredential` is the `jsonSchema` of its subject, which is only trusted once the proof of
+// its issuer is verified
+async fn get_json_schema(
+    entry: &SchemaEntry,
+    document: serde_json::Value,
+    did_resolver: &(impl crate::DIDResolver + ?Sized),
+) -> Result<serde_json::Value, crate::error::Error> {
+    if entry.schema_type != SchemaType::JsonSchemaCredential {
+        return Ok(document);
+    }
+
+    let json_schema = document
+        .get("credentialSubject")
+        .and_then(|subject| subject.get("jsonSchema"))
+        .cloned()
+        .ok_or_else(|| {
+            crate::error::Error::InvalidCredentialSchema(format!(
+                "{} has no credentialSubject.jsonSchema",
+                entry.id
+            ))
+        })?;
+    let issuer = document
+        .get("issuer")
+        .cloned()
+        .map(serde_json::from_value::<super::Issuer>)
+        .transpose()?
+        .ok_or_else(|| {
+            crate::error::Error::InvalidCredentialSchema(format!("{} has no issuer", entry.id))
+        })?;
+    crate::verify_data_integrity_proof(
+        document,
+        issuer.id(),
+        signature::suite::VerificationRelation::AssertionMethod,
+        did_resolver,
+    )
+    .await
+    .map_err(|e| {
+        crate::error::Error::InvalidCredentialSchema(format!(
+            "The proof of {} is invalid: {e}",
+            entry.id
+        ))
+    })?;
+    Ok(json_schema)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        verify_credential_schema, CredentialSchema, MockSchemaLoader, SchemaEntry, SchemaType,
+        SchemaViolation, SchemaViolations,
+    };
+    use serde_json::json;
+
+    macro_rules! aw {
+        ($e:expr) => {
+            tokio_test::block_on($e)
+        };
+    }
+
+    const SCHEMA_ID: &str = "https://example.com/schemas/email.json";
+
+    fn create_json_schema() -> serde_json::Value {
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "emailAddress": {"type": "string", "format": "email"},
+                "age": {"type": "integer", "minimum": 18}
+            },
+            "required": ["emailAddress"]
+        })
+    }
+
+    fn create_credential(
+        subject: serde_json::Value,
+        schema_type: SchemaType,
+    ) -> crate::credential::Credential {
+        let mut credential: crate::credential::Credential = serde_json::from_value(json!({
+            "@context": ["https://www.w3.org/ns/credentials/v2"],
+            "id": "https://example.com/credentials/3732",
+            "type": ["VerifiableCredential"],
+            "issuer": "did:example:28394728934792387",
+            "credentialSubject": subject,
+        }))
+        .unwrap();
+        credential.credential_schema = Some(CredentialSchema::Single(SchemaEntry {
+            id: SCHEMA_ID.to_string(),
+            schema_type,
+        }));
+        credential
+    }
+
+    #[rstest::rstest]
+    #[case::valid(json!({"emailAddress": "alice@example.com", "age": 21}), None)]
+    #[case::missing_property(
+        json!({"age": 21}),
+        Some(SchemaViolations(vec![SchemaViolation {
+            pointer: "/credentialSubject".to_string(),
+            message: "\"emailAddress\" is a required property".to_string(),
+        }]))
+    )]
+    #[case::invalid_property(
+        json!({"emailAddress": "alice@example.com", "age": 17}),
+        Some(SchemaViolations(vec![SchemaViolation {
+            pointer: "/credentialSubject/age".to_string(),
+            message: "17 is less than the minimum of 18".to_string(),
+        }]))
+    )]
+    fn test_validate_credential_subject(
+        #[case] subject: serde_json::Value,
+        #[case] expected: Option<SchemaViolations>,
+    ) {
+        let credential = create_credential(subject, SchemaType::JsonSchema);
+
+        match super::validate_credential_subject(&credential, &create_json_schema()) {
+            Ok(()) => assert!(expected.is_none()),
+            Err(crate::error::Error::SchemaValidation(violations)) => {
+                assert_eq!(Some(violations), expected)
+            }
+            Err(e) => panic!("unexpected error {e}"),
+        }
+    }
+
+    #[test]
+    fn test_validate_credential_subject_set() {
+        let credential = create_credential(
+            json!([{"emailAddress": "alice@example.com"}, {"age": 30}]),
+            SchemaType::JsonSchema,
+        );
+
+        let res = super::validate_credential_subject(&credential, &create_json_schema());
+        assert_eq!(
+            res.err().map(|e| e.to_string()).as_deref(),
+            Some(
+                "Schema Validation Error: /credentialSubject/1: \"emailAddress\" is a required property"
+            )
+        );
+    }
+
+    fn create_issuer() -> (
+        String,
+        signature::suite::ed25519_2020::Ed25519DidSigner,
+        crate::MockDIDResolver,
+    ) {
+        let kp =
+            signature::suite::ed25519_2020::Ed25519KeyPair::new("knox".to_string(), None).unwrap();
+        let issuer = signature::suite::KeyPair::get_did(&kp);
+        let did_document = aw!(crate::identity::create_identity(
+            signature::suite::ed25519_2020::Ed25519DidVerifier::from(kp.clone()),
+            vec![]
+        ))
+        .unwrap();
+        let mut did_resolver = crate::MockDIDResolver::default();
+        did_resolver.expect_resolve().returning(move |_| {
+            Ok(crate::ResolveResponse {
+                did_document: serde_json::to_value(&did_document).unwrap(),
+                did_document_metadata: Default::default(),
+                did_resolution_metadata: Default::default(),
+            })
+        });
+        (issuer, kp.into(), did_resolver)
+    }
+
+    fn create_json_schema_credential(
+        issuer: &str,
+        signer: &signature::suite::ed25519_2020::Ed25519DidSigner,
+    ) -> serde_json::Value {
+        let mut credential = json!({
+            "@context": ["https://www.w3.org/ns/credentials/v2"],
+            "id": SCHEMA_ID,
+            "type": ["VerifiableCredential", "JsonSchemaCredential"],
+            "issuer": issuer,
+            "credentialSubject": {
+                "id": SCHEMA_ID,
+                "type": "JsonSchema",
+                "jsonSchema": create_json_schema()
+            }
+        });
+        let proof = crate::proof::create_data_integrity_proof(
+            signer,
+            credential.clone(),
+            signature::suite::VerificationRelation::AssertionMethod,
+            &crate::clock::SystemClock,
+        )
+        .unwrap();
+        credential["proof"] = serde_json::to_value(proof).unwrap();
+        credential
+    }
+
+    #[rstest::rstest]
+    #[case::json_schema(SchemaType::JsonSchema)]
+    #[case::json_schema_credential(SchemaType::JsonSchemaCredential)]
+    fn test_verify_credential_schema(#[case] schema_type: SchemaType) {
+        let (issuer, signer, did_resolver) = create_issuer();
+        let document = match schema_type {
+            SchemaType::JsonSchemaCredential => create_json_schema_credential(&issuer, &signer),
+            _ => create_json_schema(),
+        };
+        let loader = std::collections::HashMap::from([(SCHEMA_ID.to_string(), document)]);
+
+        let valid = create_credential(
+            json!({"emailAddress": "alice@example.com"}),
+            schema_type.clone(),
+        );
+        assert!(aw!(verify_credential_schema(&valid, &loader, &did_resolver)).is_ok());
+
+        let invalid = create_credential(json!({"emailAddress": 42}), schema_type);
+        assert!(matches!(
+            aw!(verify_credential_schema(&invalid, &loader, &did_resolver)),
+            Err(crate::error::Error::SchemaValidation(SchemaViolations(violations)))
+                if violations[0].pointer == "/credentialSubject/emailAddress"
+        ));
+    }
+
+    // A schema credential whose proof does not verify can not relax the schema of the credential
+    #[rstest::rstest]
+    #[case::tampered("tampered")]
+    #[case::unsigned("unsigned")]
+    fn test_verify_untrusted_json_schema_credential(#[case] untrusted: &str) {
+        let (issuer, signer, did_resolver) = create_issuer();
+        let mut document = create_json_schema_credential(&issuer, &signer);
+        match untrusted {
+            "tampered" => document["credentialSubject"]["jsonSchema"] = json!({}),
+            _ => {
+                document.as_object_mut().unwrap().remove("proof");
+            }
+        }
+        let loader = std::collections::HashMap::from([(SCHEMA_ID.to_string(), document)]);
+        let credential = create_credential(json!({"age": 12}), SchemaType::JsonSchemaCredential);
+
+        assert!(matches!(
+            aw!(verify_credential_schema(&credential, &loader, &did_resolver)),
+            Err(crate::error::Error::InvalidCredentialSchema(e))
+                if e.starts_with(&format!("The proof of {SCHEMA_ID} is invalid"))
+        ));
+    }
+
+    #[rstest::rstest]
+    #[case::not_found(
+        Err(crate::error::ResolverError::DocumentNotFound(SCHEMA_ID.to_string())),
+        SchemaType::JsonSchema,
+        "Invalid Credential Schema: Document not found: https://example.com/schemas/email.json"
+    )]
+    #[case::no_json_schema(
+        Ok(json!({"credentialSubject": {}})),
+        SchemaType::JsonSchemaCredential,
+        "Invalid Credential Schema: https://example.com/schemas/email.json has no credentialSubject.jsonSchema"
+    )]
+    #[case::invalid_schema(
+        Ok(json!({"type": 12})),
+        SchemaType::JsonSchema,
+        "Invalid Credential Schema: 12 is not valid under any of the schemas listed in the 'anyOf' keyword"
+    )]
+    fn test_verify_credential_schema_error(
+        #[case] document: Result<serde_json::Value, crate::error::ResolverError>,
+        #[case] schema_type: SchemaType,
+        #[case] expected: &str,
+    ) {
+        let mut loader = MockSchemaLoader::default();
+        loader
+            .expect_load_schema()
+            .with(mockall::predicate::eq(SCHEMA_ID))
+            .return_once(move |_| document);
+        let credential =
+            create_credential(json!({"emailAddress": "alice@example.com"}), schema_type);
+
+        let res = aw!(verify_credential_schema(
+            &credential,
+            &loader,
+            &crate::MockDIDResolver::default()
+        ));
+        assert_eq!(res.err().map(|e| e.to_string()).as_deref(), Some(expected));
+    }
+
+    #[test]
+    fn test_unsupported_schema_type_ignored() {
+        let credential = create_credential(
+            json!({"age": 12}),
+            SchemaType::from("JsonSchemaValidator2018".to_string()),
+        );
+
+        let res = aw!(verify_credential_schema(
+            &credential,
+            &MockSchemaLoader::default(),
+            &crate::MockDIDResolver::default()
+        ));
+        assert!(res.is_ok());
+    }
+}
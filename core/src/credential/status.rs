@@ -0,0 +1,826 @@
+// Credential status follows https://www.w3.org/TR/vc-bitstring-status-list/ and its predecessor
+// https://www.w3.org/TR/2023/WD-vc-status-list-20230427/ (StatusList2021)
+use std::io::{Read, Write};
+
+// The minimum length of a status list recommended for herd privacy, see https://www.w3.org/TR/vc-bitstring-status-list/
+pub const MIN_STATUS_LIST_LENGTH: usize = 131_072;
+// The maximum size of a decompressed status list (over 130 million statuses), guarding against GZIP bombs in lists
+// fetched from remote.
+pub const MAX_STATUS_LIST_BYTES: usize = 16 * 1024 * 1024;
+
+// The BitstringStatusList terms are defined by the VC Data Model 2.0 context.
+pub const BITSTRING_STATUS_LIST_CONTEXT: &str = "https://www.w3.org/ns/credentials/v2";
+pub const STATUS_LIST_2021_CONTEXT: &str = "https://w3id.org/vc/status-list/2021/v1";
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusPurpose {
+    #[serde(rename = "revocation")]
+    Revocation,
+    #[serde(rename = "suspension")]
+    Suspension,
+    #[serde(rename = "refresh")]
+    Refresh,
+    #[serde(rename = "message")]
+    Message,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusListEntryType {
+    BitstringStatusListEntry,
+    StatusList2021Entry,
+}
+
+impl StatusListEntryType {
+    fn contexts(&self) -> super::DocumentContext {
+        match self {
+            StatusListEntryType::BitstringStatusListEntry => vec![super::ContextValue::String(
+                BITSTRING_STATUS_LIST_CONTEXT.to_string(),
+            )],
+            StatusListEntryType::StatusList2021Entry => vec![
+                super::ContextValue::String(super::BASE_CREDENTIAL_CONTEXT.to_string()),
+                super::ContextValue::String(STATUS_LIST_2021_CONTEXT.to_string()),
+            ],
+        }
+    }
+
+    fn credential_type(&self) -> super::CredentialType {
+        match self {
+            StatusListEntryType::BitstringStatusListEntry => {
+                super::CredentialType::BitstringStatusListCredential
+            }
+            StatusListEntryType::StatusList2021Entry => {
+                super::CredentialType::StatusList2021Credential
+            }
+        }
+    }
+
+    fn subject_type(&self) -> &'static str {
+        match self {
+            StatusListEntryType::BitstringStatusListEntry => "BitstringStatusList",
+            StatusListEntryType::StatusList2021Entry => "StatusList2021",
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StatusListEntry {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    #[serde(rename = "type")]
+    pub entry_type: StatusListEntryType,
+
+    #[serde(rename = "statusPurpose")]
+    pub status_purpose: StatusPurpose,
+
+    // Serialized as a string as required by both specifications, numbers are accepted as well.
+    #[serde(rename = "statusListIndex", with = "status_list_index")]
+    pub status_list_index: usize,
+
+    #[serde(rename = "statusListCredential")]
+    pub status_list_credential: String,
+}
+
+/// An entry of the `credentialStatus` property. Entries of other status methods are kept as is and ignored by
+/// `verify_credential_status`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum StatusEntry {
+    StatusList(StatusListEntry),
+    Other(std::collections::HashMap<String, serde_json::Value>),
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum CredentialStatus {
+    Single(StatusEntry),
+    Set(Vec<StatusEntry>),
+}
+
+impl CredentialStatus {
+    pub fn entries(&self) -> &[StatusEntry] {
+        match self {
+            CredentialStatus::Single(entry) => std::slice::from_ref(entry),
+            CredentialStatus::Set(entries) => entries,
+        }
+    }
+}
+
+impl From<StatusListEntry> for CredentialStatus {
+    fn from(entry: StatusListEntry) -> Self {
+        CredentialStatus::Single(StatusEntry::StatusList(entry))
+    }
+}
+
+mod status_list_index {
+    pub fn serialize<S: serde::Serializer>(
+        index: &usize,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&index.to_string())
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<usize, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Index {
+            String(String),
+            Number(usize),
+        }
+
+        match serde::Deserialize::deserialize(deserializer)? {
+            Index::String(index) => index.parse().map_err(serde::de::Error::custom),
+            Index::Number(index) => Ok(index),
+        }
+    }
+}
+
+/// Source of the status list credentials referenced by `statusListCredential`, e.g. the registry or an HTTP client.
+#[mockall::automock]
+#[async_trait::async_trait]
+pub trait StatusListResolver: Send + Sync {
+    async fn resolve_status_list_credential(
+        &self,
+        status_list_credential: &str,
+    ) -> Result<serde_json::Value, crate::error::ResolverError>;
+}
+
+/// A bitstring holding the status of every credential referencing the list, the bit at `statusListIndex` being set
+/// when the credential is revoked or suspended (depending on the purpose of the list).
+/// Index 0 is the left-most bit of the first byte.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusList {
+    bits: Vec<u8>,
+}
+
+impl Default for StatusList {
+    fn default() -> Self {
+        StatusList::new(MIN_STATUS_LIST_LENGTH)
+    }
+}
+
+impl StatusList {
+    /// Create a list of at least `len` statuses, all unset.
+    pub fn new(len: usize) -> Self {
+        StatusList {
+            bits: vec![0; len.div_ceil(8)],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.len() * 8
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Result<bool, crate::error::Error> {
+        let byte = self
+            .bits
+            .get(index / 8)
+            .ok_or_else(|| self.out_of_range(index))?;
+        Ok(byte & (0x80 >> (index % 8)) != 0)
+    }
+
+    pub fn set(&mut self, index: usize, status: bool) -> Result<(), crate::error::Error> {
+        if index >= self.len() {
+            return Err(self.out_of_range(index));
+        }
+        match status {
+            true => self.bits[index / 8] |= 0x80 >> (index % 8),
+            false => self.bits[index / 8] &= !(0x80 >> (index % 8)),
+        }
+        Ok(())
+    }
+
+    /// GZIP-compress the bitstring and encode it with base64url without padding, as expected in the `encodedList`
+    /// property of status list credentials.
+    pub fn encode(&self) -> Result<String, crate::error::Error> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder
+            .write_all(&self.bits)
+            .and_then(|_| encoder.finish())
+            .map(|compressed| base64::encode_config(compressed, base64::URL_SAFE_NO_PAD))
+            .map_err(|e| crate::error::Error::InvalidStatusList(e.to_string()))
+    }
+
+    // Bitstring Status List prefixes the list with the base64url multibase header `u`, StatusList2021 does not. A GZIP
+    // stream encoded with base64url always starts with `H` so the prefix is unambiguous.
+    pub fn decode(encoded_list: &str) -> Result<Self, crate::error::Error> {
+        let encoded_list = encoded_list.strip_prefix('u').unwrap_or(encoded_list);
+        let compressed = base64::decode_config(encoded_list, base64::URL_SAFE_NO_PAD)
+            .map_err(|e| crate::error::Error::InvalidStatusList(e.to_string()))?;
+
+        let mut bits = Vec::new();
+        flate2::read::GzDecoder::new(compressed.as_slice())
+            .take(MAX_STATUS_LIST_BYTES as u64 + 1)
+            .read_to_end(&mut bits)
+            .map_err(|e| crate::error::Error::InvalidStatusList(e.to_string()))?;
+        if bits.len() > MAX_STATUS_LIST_BYTES {
+            return Err(crate::error::Error::InvalidStatusList(format!(
+                "The status list exceeds {MAX_STATUS_LIST_BYTES} bytes"
+            )));
+        }
+        Ok(StatusList { bits })
+    }
+
+    /// Read the list from the `encodedList` property of the subject of a status list credential.
+    pub fn from_credential(credential: &serde_json::Value) -> Result<Self, crate::error::Error> {
+        let encoded_list = credential
+            .get("credentialSubject")
+            .and_then(|subject| subject.get("encodedList"))
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| {
+                crate::error::Error::InvalidStatusList(
+                    "The credential subject has no encodedList".to_string(),
+                )
+            })?;
+        StatusList::decode(encoded_list)
+    }
+
+    fn out_of_range(&self, index: usize) -> crate::error::Error {
+        crate::error::Error::InvalidStatusList(format!(
+            "Index {index} is out of range of a list of {} statuses",
+            self.len()
+        ))
+    }
+}
+
+/// Create the unsigned status list credential `id` publishing `status_list`, issued at the time of `clock`. It must
+/// be signed by `issuer` before being published.
+pub fn create_status_list_credential(
+    entry_type: StatusListEntryType,
+    id: &str,
+    issuer: String,
+    status_purpose: StatusPurpose,
+    status_list: &StatusList,
+    clock: &(impl crate::clock::Clock + ?Sized),
+) -> Result<super::Credential, crate::error::Error> {
+    let encoded_list = match entry_type {
+        // Multibase base64url header
+        StatusListEntryType::BitstringStatusListEntry => format!("u{}", status_list.encode()?),
+        StatusListEntryType::StatusList2021Entry => status_list.encode()?,
+    };
+    let subject = std::collections::HashMap::from([
+        ("id".to_string(), serde_json::json!(format!("{id}#list"))),
+        (
+            "type".to_string(),
+            serde_json::json!(entry_type.subject_type()),
+        ),
+        (
+            "statusPurpose".to_string(),
+            serde_json::to_value(status_purpose)?,
+        ),
+        ("encodedList".to_string(), serde_json::json!(encoded_list)),
+    ]);
+
+    let now = clock.now();
+    // `issuanceDate` is replaced by `validFrom` in the VC Data Model 2.0 used by Bitstring Status List
+    let (issuance_date, valid_from) = match entry_type {
+        StatusListEntryType::BitstringStatusListEntry => (None, Some(now)),
+        StatusListEntryType::StatusList2021Entry => (Some(now), None),
+    };
+
+    Ok(super::Credential {
+        context: entry_type.contexts(),
+        id: Some(id.to_string()),
+        cred_type: vec![
+            super::CredentialType::VerifiableCredential,
+            entry_type.credential_type(),
+        ],
+        issuance_date,
+        expiration_date: None,
+        valid_from,
+        valid_until: None,
+        name: None,
+        description: None,
+        issuer: issuer.into(),
+        subject: super::CredentialSubject::Single(subject),
+        credential_status: None,
+        credential_schema: None,
+        property_set: Default::default(),
+    })
+}
+
+/// Verify that `status_list_credential` is issued by `issuer`, the issuer of the credentials referencing the list,
+/// and that its proof is created by an `assertionMethod` key of the issuer, resolved through `did_resolver`. Anyone
+/// serving the list could otherwise clear the bits of revoked credentials.
+pub async fn verify_status_list_credential(
+    status_list_credential: &serde_json::Value,
+    issuer: &str,
+    did_resolver: &(impl crate::DIDResolver + ?Sized),
+) -> Result<(), crate::error::Error> {
+    let id = status_list_credential
+        .get("id")
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or("The status list credential");
+    let list_issuer = status_list_credential
+        .get("issuer")
+        .cloned()
+        .map(serde_json::from_value::<super::Issuer>)
+        .transpose()?;
+    if list_issuer.as_ref().map(super::Issuer::id) != Some(issuer) {
+        return Err(crate::error::Error::InvalidStatusList(format!(
+            "{id} is not issued by {issuer}"
+        )));
+    }
+
+    crate::verify_data_integrity_proof(
+        status_list_credential.clone(),
+        issuer,
+        signature::suite::VerificationRelation::AssertionMethod,
+        did_resolver,
+    )
+    .await
+    .map_err(|e| {
+        crate::error::Error::InvalidStatusList(format!("The proof of {id} is invalid: {e}"))
+    })
+}
+
+/// Read the bit of `entry` in `status_list_credential`, which must be the status list credential referenced by the
+/// entry, have the same purpose, and be issued by `issuer`, see `verify_status_list_credential`.
+pub async fn get_status(
+    entry: &StatusListEntry,
+    issuer: &str,
+    status_list_credential: &serde_json::Value,
+    did_resolver: &(impl crate::DIDResolver + ?Sized),
+) -> Result<bool, crate::error::Error> {
+    if let Some(id) = status_list_credential
+        .get("id")
+        .and_then(serde_json::Value::as_str)
+    {
+        if id != entry.status_list_credential {
+            return Err(crate::error::Error::InvalidStatusList(format!(
+                "Expected status list credential {}, got {id}",
+                entry.status_list_credential
+            )));
+        }
+    }
+    let status_purpose = status_list_credential
+        .get("credentialSubject")
+        .and_then(|subject| subject.get("statusPurpose"))
+        .cloned()
+        .map(serde_json::from_value::<StatusPurpose>)
+        .transpose()?;
+    if status_purpose.is_some_and(|status_purpose| status_purpose != entry.status_purpose) {
+        return Err(crate::error::Error::InvalidStatusList(format!(
+            "The purpose of status list credential {} does not match the entry",
+            entry.status_list_credential
+        )));
+    }
+
+    verify_status_list_credential(status_list_credential, issuer, did_resolver).await?;
+    StatusList::from_credential(status_list_credential)?.get(entry.status_list_index)
+}
+
+/// Check every revocation and suspension entry of the credential status, failing with `CredentialRevoked` or
+/// `CredentialSuspended` when the bit of the credential is set in its status list.
+/// The status lists must be issued by the issuer of the credential, whose keys are resolved through `did_resolver`.
+pub async fn verify_credential_status(
+    credential: &super::Credential,
+    resolver: &(impl StatusListResolver + ?Sized),
+    did_resolver: &(impl crate::DIDResolver + ?Sized),
+) -> Result<(), crate::error::Error> {
+    let Some(credential_status) = &credential.credential_status else {
+        return Ok(());
+    };
+
+    for entry in credential_status.entries() {
+        let entry = match entry {
+            StatusEntry::StatusList(entry) => entry,
+            StatusEntry::Other(entry) => {
+                tracing::warn!(entry_type = ?entry.get("type"), "Unsupported credential status ignored");
+                continue;
+            }
+        };
+        if !matches!(
+            entry.status_purpose,
+            StatusPurpose::Revocation | StatusPurpose::Suspension
+        ) {
+            continue;
+        }
+
+        let status_list_credential = resolver
+            .resolve_status_list_credential(&entry.status_list_credential)
+            .await
+            .map_err(|e| crate::error::Error::InvalidStatusList(e.to_string()))?;
+        let issuer = credential.issuer.id();
+        if !get_status(entry, issuer, &status_list_credential, did_resolver).await? {
+            continue;
+        }
+
+        let id = credential.id.as_deref().unwrap_or_default();
+        return Err(match entry.status_purpose {
+            StatusPurpose::Suspension => crate::error::Error::CredentialSuspended(id.to_string()),
+            _ => crate::error::Error::CredentialRevoked(id.to_string()),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        create_status_list_credential, get_status, verify_credential_status, CredentialStatus,
+        MockStatusListResolver, StatusEntry, StatusList, StatusListEntry, StatusListEntryType,
+        StatusPurpose, MAX_STATUS_LIST_BYTES, MIN_STATUS_LIST_LENGTH,
+    };
+
+    #[test]
+    fn test_encode_decode() {
+        let mut status_list = StatusList::default();
+        assert_eq!(status_list.len(), MIN_STATUS_LIST_LENGTH);
+        for index in [0, 7, 8, 94567, MIN_STATUS_LIST_LENGTH - 1] {
+            status_list.set(index, true).unwrap();
+        }
+        status_list.set(8, false).unwrap();
+
+        let encoded = status_list.encode().unwrap();
+        assert!(encoded.starts_with("H4sI"));
+        let decoded = StatusList::decode(&encoded).unwrap();
+        assert_eq!(decoded, status_list);
+        // Multibase encoded lists of Bitstring Status List
+        assert_eq!(
+            StatusList::decode(&format!("u{encoded}")).unwrap(),
+            status_list
+        );
+
+        for (index, expected) in [(0, true), (1, false), (7, true), (8, false), (94567, true)] {
+            assert_eq!(decoded.get(index).unwrap(), expected);
+        }
+        assert!(decoded.get(MIN_STATUS_LIST_LENGTH).is_err());
+    }
+
+    #[rstest::rstest]
+    // Example list of 100,000 statuses of the StatusList2021 specification, all unset
+    #[case::status_list_2021(
+        "H4sIAAAAAAAAA-3BMQEAAADCoPVPbQsvoAAAAAAAAAAAAAAAAP4GcwM92tQwAAA",
+        None
+    )]
+    #[case::invalid_base64("H4sI!", Some("Invalid Status List: Invalid byte 33, offset 4."))]
+    #[case::not_gzip(
+        "bm90IGEgZ3ppcCBzdHJlYW0",
+        Some("Invalid Status List: invalid gzip header")
+    )]
+    fn test_decode(#[case] encoded_list: &str, #[case] expected_error: Option<&str>) {
+        match (StatusList::decode(encoded_list), expected_error) {
+            (Ok(status_list), None) => {
+                assert_eq!(status_list.len(), 100_000);
+                assert!(!status_list.get(0).unwrap());
+            }
+            (Err(e), Some(expected)) => assert_eq!(e.to_string(), expected),
+            (res, expected) => panic!("unexpected result {res:?}, expected {expected:?}"),
+        }
+    }
+
+    #[test]
+    fn test_decode_too_large() {
+        let at_cap = StatusList {
+            bits: vec![0; MAX_STATUS_LIST_BYTES],
+        };
+        assert_eq!(
+            StatusList::decode(&at_cap.encode().unwrap()).unwrap(),
+            at_cap
+        );
+
+        let too_large = StatusList {
+            bits: vec![0; MAX_STATUS_LIST_BYTES + 1],
+        };
+        assert_eq!(
+            StatusList::decode(&too_large.encode().unwrap())
+                .unwrap_err()
+                .to_string(),
+            format!("Invalid Status List: The status list exceeds {MAX_STATUS_LIST_BYTES} bytes")
+        );
+    }
+
+    #[test]
+    fn test_from_credential() {
+        let mut status_list = StatusList::new(16);
+        status_list.set(3, true).unwrap();
+        let credential = serde_json::json!({
+            "credentialSubject": {
+                "type": "BitstringStatusList",
+                "statusPurpose": "revocation",
+                "encodedList": status_list.encode().unwrap(),
+            }
+        });
+
+        let decoded = StatusList::from_credential(&credential).unwrap();
+        assert!(decoded.get(3).unwrap());
+        assert!(StatusList::from_credential(&serde_json::json!({})).is_err());
+    }
+
+    const STATUS_LIST_CREDENTIAL_ID: &str = "https://example.com/credentials/status/3";
+
+    fn create_entry(
+        entry_type: StatusListEntryType,
+        status_purpose: StatusPurpose,
+    ) -> StatusListEntry {
+        StatusListEntry {
+            id: Some(format!("{STATUS_LIST_CREDENTIAL_ID}#94567")),
+            entry_type,
+            status_purpose,
+            status_list_index: 94567,
+            status_list_credential: STATUS_LIST_CREDENTIAL_ID.to_string(),
+        }
+    }
+
+    #[rstest::rstest]
+    #[case::bitstring(
+        serde_json::json!({
+            "id": "https://example.com/credentials/status/3#94567",
+            "type": "BitstringStatusListEntry",
+            "statusPurpose": "revocation",
+            "statusListIndex": "94567",
+            "statusListCredential": "https://example.com/credentials/status/3"
+        }),
+        CredentialStatus::from(create_entry(StatusListEntryType::BitstringStatusListEntry, StatusPurpose::Revocation))
+    )]
+    #[case::status_list_2021_set(
+        serde_json::json!([{
+            "id": "https://example.com/credentials/status/3#94567",
+            "type": "StatusList2021Entry",
+            "statusPurpose": "suspension",
+            "statusListIndex": "94567",
+            "statusListCredential": "https://example.com/credentials/status/3"
+        }]),
+        CredentialStatus::Set(vec![StatusEntry::StatusList(create_entry(
+            StatusListEntryType::StatusList2021Entry,
+            StatusPurpose::Suspension
+        ))])
+    )]
+    #[case::other(
+        serde_json::json!({
+            "id": "https://example.com/status/24",
+            "type": "CredentialStatusList2017"
+        }),
+        CredentialStatus::Single(StatusEntry::Other(std::collections::HashMap::from([
+            ("id".to_string(), serde_json::json!("https://example.com/status/24")),
+            ("type".to_string(), serde_json::json!("CredentialStatusList2017")),
+        ])))
+    )]
+    fn test_credential_status_serialization(
+        #[case] json: serde_json::Value,
+        #[case] expected: CredentialStatus,
+    ) {
+        let credential_status: CredentialStatus = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(credential_status, expected);
+        assert_eq!(serde_json::to_value(&credential_status).unwrap(), json);
+    }
+
+    #[test]
+    fn test_deserialize_numeric_index() {
+        let entry: StatusListEntry = serde_json::from_value(serde_json::json!({
+            "type": "StatusList2021Entry",
+            "statusPurpose": "revocation",
+            "statusListIndex": 94567,
+            "statusListCredential": STATUS_LIST_CREDENTIAL_ID
+        }))
+        .unwrap();
+        assert_eq!(entry.status_list_index, 94567);
+    }
+
+    macro_rules! aw {
+        ($e:expr) => {
+            tokio_test::block_on($e)
+        };
+    }
+
+    // The signer of the status list credentials and a DID resolver of its DID Document
+    fn create_issuer() -> (
+        String,
+        signature::suite::ed25519_2020::Ed25519DidSigner,
+        crate::MockDIDResolver,
+    ) {
+        let kp =
+            signature::suite::ed25519_2020::Ed25519KeyPair::new("knox".to_string(), None).unwrap();
+        let issuer = signature::suite::KeyPair::get_did(&kp);
+        let did_document = aw!(crate::identity::create_identity(
+            signature::suite::ed25519_2020::Ed25519DidVerifier::from(kp.clone()),
+            vec![]
+        ))
+        .unwrap();
+        let mut did_resolver = crate::MockDIDResolver::default();
+        did_resolver.expect_resolve().returning(move |_| {
+            Ok(crate::ResolveResponse {
+                did_document: serde_json::to_value(&did_document).unwrap(),
+                did_document_metadata: Default::default(),
+                did_resolution_metadata: Default::default(),
+            })
+        });
+        (issuer, kp.into(), did_resolver)
+    }
+
+    fn create_signed_status_list_credential(
+        entry_type: StatusListEntryType,
+        status_purpose: StatusPurpose,
+        status_list: &StatusList,
+        issuer: &str,
+        signer: &signature::suite::ed25519_2020::Ed25519DidSigner,
+    ) -> serde_json::Value {
+        let vc = create_status_list_credential(
+            entry_type,
+            STATUS_LIST_CREDENTIAL_ID,
+            issuer.to_string(),
+            status_purpose,
+            status_list,
+            &crate::clock::SystemClock,
+        )
+        .unwrap()
+        .try_into_verifiable_credential(
+            signer,
+            signature::suite::VerificationRelation::AssertionMethod,
+            &crate::clock::SystemClock,
+        )
+        .unwrap();
+        serde_json::to_value(vc).unwrap()
+    }
+
+    #[rstest::rstest]
+    #[case::bitstring(
+        StatusListEntryType::BitstringStatusListEntry,
+        "BitstringStatusListCredential",
+        "u"
+    )]
+    #[case::status_list_2021(
+        StatusListEntryType::StatusList2021Entry,
+        "StatusList2021Credential",
+        "H4sI"
+    )]
+    fn test_create_status_list_credential(
+        #[case] entry_type: StatusListEntryType,
+        #[case] expected_type: &str,
+        #[case] expected_prefix: &str,
+    ) {
+        let (issuer, signer, did_resolver) = create_issuer();
+        let mut status_list = StatusList::default();
+        status_list.set(94567, true).unwrap();
+
+        let credential = create_signed_status_list_credential(
+            entry_type,
+            StatusPurpose::Revocation,
+            &status_list,
+            &issuer,
+            &signer,
+        );
+        assert_eq!(credential["type"][1], expected_type);
+        assert!(credential["credentialSubject"]["encodedList"]
+            .as_str()
+            .unwrap()
+            .starts_with(expected_prefix));
+
+        assert_eq!(
+            StatusList::from_credential(&credential).unwrap(),
+            status_list
+        );
+        let entry = create_entry(entry_type, StatusPurpose::Revocation);
+        assert!(aw!(get_status(&entry, &issuer, &credential, &did_resolver)).unwrap());
+        let entry = StatusListEntry {
+            status_list_index: 94566,
+            ..entry
+        };
+        assert!(!aw!(get_status(&entry, &issuer, &credential, &did_resolver)).unwrap());
+
+        // The entry must reference the status list credential with the same purpose
+        let entry = StatusListEntry {
+            status_purpose: StatusPurpose::Suspension,
+            ..entry
+        };
+        assert!(aw!(get_status(&entry, &issuer, &credential, &did_resolver)).is_err());
+        let entry = StatusListEntry {
+            status_purpose: StatusPurpose::Revocation,
+            status_list_credential: "https://example.com/credentials/status/4".to_string(),
+            ..entry
+        };
+        assert!(aw!(get_status(&entry, &issuer, &credential, &did_resolver)).is_err());
+    }
+
+    #[rstest::rstest]
+    #[case::valid(StatusPurpose::Revocation, &[], None)]
+    #[case::revoked(
+        StatusPurpose::Revocation,
+        &[94567],
+        Some("Credential revoked: https://example.com/credentials/1872")
+    )]
+    #[case::suspended(
+        StatusPurpose::Suspension,
+        &[94567],
+        Some("Credential suspended: https://example.com/credentials/1872")
+    )]
+    fn test_verify_credential_status(
+        #[case] status_purpose: StatusPurpose,
+        #[case] set_indexes: &[usize],
+        #[case] expected_error: Option<&str>,
+    ) {
+        let (issuer, signer, did_resolver) = create_issuer();
+        let mut status_list = StatusList::default();
+        for index in set_indexes {
+            status_list.set(*index, true).unwrap();
+        }
+        let status_list_credential = create_signed_status_list_credential(
+            StatusListEntryType::BitstringStatusListEntry,
+            status_purpose,
+            &status_list,
+            &issuer,
+            &signer,
+        );
+        let mut resolver = MockStatusListResolver::default();
+        resolver
+            .expect_resolve_status_list_credential()
+            .with(mockall::predicate::eq(STATUS_LIST_CREDENTIAL_ID))
+            .return_once(move |_| Ok(status_list_credential));
+
+        let credential = create_credential(&issuer, status_purpose);
+        let res = aw!(verify_credential_status(
+            &credential,
+            &resolver,
+            &did_resolver
+        ));
+        assert_eq!(res.err().map(|e| e.to_string()).as_deref(), expected_error);
+    }
+
+    // Status lists that are not signed by the issuer of the credential can not un-revoke it
+    #[rstest::rstest]
+    #[case::other_issuer("other_issuer")]
+    #[case::tampered("tampered")]
+    #[case::unsigned("unsigned")]
+    fn test_verify_credential_status_untrusted_list(#[case] untrusted: &str) {
+        let (issuer, signer, did_resolver) = create_issuer();
+        let mut status_list = StatusList::default();
+        status_list.set(94567, true).unwrap();
+        let create_list = |status_list: &StatusList, issuer: &str, signer| {
+            create_signed_status_list_credential(
+                StatusListEntryType::BitstringStatusListEntry,
+                StatusPurpose::Revocation,
+                status_list,
+                issuer,
+                signer,
+            )
+        };
+
+        let status_list_credential = match untrusted {
+            "other_issuer" => {
+                let (other_issuer, other_signer, _) = create_issuer();
+                create_list(&StatusList::default(), &other_issuer, &other_signer)
+            }
+            "tampered" => {
+                let mut credential = create_list(&status_list, &issuer, &signer);
+                credential["credentialSubject"]["encodedList"] =
+                    serde_json::json!(format!("u{}", StatusList::default().encode().unwrap()));
+                credential
+            }
+            _ => {
+                let mut credential = create_list(&StatusList::default(), &issuer, &signer);
+                credential.as_object_mut().unwrap().remove("proof");
+                credential
+            }
+        };
+        let mut resolver = MockStatusListResolver::default();
+        resolver
+            .expect_resolve_status_list_credential()
+            .return_once(move |_| Ok(status_list_credential));
+
+        let credential = create_credential(&issuer, StatusPurpose::Revocation);
+        let res = aw!(verify_credential_status(
+            &credential,
+            &resolver,
+            &did_resolver
+        ));
+        assert!(matches!(
+            res,
+            Err(crate::error::Error::InvalidStatusList(_))
+        ));
+    }
+
+    fn create_credential(
+        issuer: &str,
+        status_purpose: StatusPurpose,
+    ) -> crate::credential::Credential {
+        crate::credential::Credential {
+            context: vec![],
+            id: Some("https://example.com/credentials/1872".to_string()),
+            cred_type: vec![crate::credential::CredentialType::VerifiableCredential],
+            issuance_date: None,
+            expiration_date: None,
+            valid_from: None,
+            valid_until: None,
+            name: None,
+            description: None,
+            issuer: issuer.into(),
+            subject: crate::credential::CredentialSubject::Single(Default::default()),
+            credential_status: Some(CredentialStatus::Set(vec![
+                StatusEntry::Other(Default::default()),
+                StatusEntry::StatusList(create_entry(
+                    StatusListEntryType::BitstringStatusListEntry,
+                    status_purpose,
+                )),
+            ])),
+            credential_schema: None,
+            property_set: Default::default(),
+        }
+    }
+}
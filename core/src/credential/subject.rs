@@ -0,0 +1,348 @@
+use super::CredentialType;
+
+pub const PERMANENT_RESIDENT_CREDENTIAL_CONTEXT: &str = "https://w3id.org/citizenship/v1";
+
+/// A credential subject modelled as a Rust type.
+/// Implementors give the type of credential issued for the subject, the JSON-LD contexts defining its terms and the
+/// JSON-LD types of the subject. The subject properties are (de)serialized with serde.
+pub trait TypedCredentialSubject: serde::Serialize + serde::de::DeserializeOwned {
+    fn credential_type(&self) -> CredentialType;
+
+    /// Contexts needed by the subject on top of the base credential context.
+    fn contexts(&self) -> Vec<super::ContextValue> {
+        vec![]
+    }
+
+    /// The JSON-LD `type` of the subject, added to the subject properties when not serialized by the type itself.
+    fn subject_types(&self) -> Vec<&str> {
+        vec![]
+    }
+
+    fn to_subject(
+        &self,
+    ) -> Result<std::collections::HashMap<String, serde_json::Value>, crate::error::Error> {
+        let mut subject: std::collections::HashMap<String, serde_json::Value> =
+            serde_json::from_value(serde_json::to_value(self)?)?;
+        let subject_types = self.subject_types();
+        if !subject_types.is_empty() {
+            subject
+                .entry("type".to_string())
+                .or_insert_with(|| serde_json::json!(subject_types));
+        }
+        Ok(subject)
+    }
+
+    fn from_subject(
+        subject: &std::collections::HashMap<String, serde_json::Value>,
+    ) -> Result<Self, crate::error::Error> {
+        serde_json::from_value(serde_json::to_value(subject)?)
+            .map_err(|e| crate::error::Error::InvalidCredentialSubject(e.to_string()))
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PostalAddress {
+    #[serde(rename = "streetAddress")]
+    pub street_address: String,
+
+    #[serde(rename = "addressLocality")]
+    pub address_locality: String,
+
+    #[serde(rename = "addressRegion")]
+    pub address_region: String,
+
+    #[serde(rename = "addressCountry")]
+    pub address_country: String,
+
+    #[serde(rename = "postalCode")]
+    pub postal_code: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RoutingInfo {
+    pub code: String,
+    pub value: String,
+}
+
+// Subject of a `BankAccount` credential, with the terms of https://w3id.org/traceability/v1
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BankAccount {
+    pub id: String,
+
+    #[serde(rename = "accountId")]
+    pub account_id: String,
+
+    #[serde(rename = "givenName")]
+    pub given_name: String,
+
+    #[serde(rename = "familyName")]
+    pub family_name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub address: Option<PostalAddress>,
+
+    #[serde(rename = "routingInfo")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub routing_info: Option<RoutingInfo>,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub iban: Option<String>,
+
+    #[serde(rename = "BIC11")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub bic11: Option<String>,
+}
+
+impl TypedCredentialSubject for BankAccount {
+    fn credential_type(&self) -> CredentialType {
+        CredentialType::BankAccount
+    }
+
+    // Not all the terms of the subject are defined by the traceability context, they are defined by the context
+    // following it so that none drops out of the signed data
+    fn contexts(&self) -> Vec<super::ContextValue> {
+        vec![
+            super::ContextValue::String(super::BANK_ACCOUNT_CREDENTIAL_CONTEXT.to_string()),
+            bank_account_terms(),
+        ]
+    }
+
+    fn subject_types(&self) -> Vec<&str> {
+        vec!["BankAccount"]
+    }
+
+    // The nested objects are typed nodes as well
+    fn to_subject(
+        &self,
+    ) -> Result<std::collections::HashMap<String, serde_json::Value>, crate::error::Error> {
+        let mut subject: std::collections::HashMap<String, serde_json::Value> =
+            serde_json::from_value(serde_json::to_value(self)?)?;
+        subject.insert("type".to_string(), serde_json::json!(self.subject_types()));
+        for (property, node_type) in [("address", "PostalAddress"), ("routingInfo", "RoutingInfo")]
+        {
+            if let Some(serde_json::Value::Object(node)) = subject.get_mut(property) {
+                node.insert("type".to_string(), serde_json::json!([node_type]));
+            }
+        }
+        Ok(subject)
+    }
+}
+
+// The terms of the bank account subject, from the schema.org vocabulary and the traceability vocabulary
+fn bank_account_terms() -> super::ContextValue {
+    let schema_org_terms = [
+        "BankAccount",
+        "PostalAddress",
+        "accountId",
+        "givenName",
+        "familyName",
+        "address",
+        "streetAddress",
+        "addressLocality",
+        "addressRegion",
+        "addressCountry",
+        "postalCode",
+    ];
+    let traceability_terms = [
+        "RoutingInfo",
+        "routingInfo",
+        "code",
+        "value",
+        "iban",
+        "BIC11",
+    ];
+
+    let terms = schema_org_terms
+        .into_iter()
+        .map(|term| (term, format!("https://schema.org/{term}")))
+        .chain(
+            traceability_terms
+                .into_iter()
+                .map(|term| (term, format!("https://w3id.org/traceability#{term}"))),
+        )
+        .map(|(term, iri)| (term.to_string(), serde_json::Value::String(iri)))
+        .collect();
+    super::ContextValue::Object(terms)
+}
+
+// Subject of a `PermanentResidentCard` credential, with the terms of https://w3id.org/citizenship/v1
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PermanentResident {
+    pub id: String,
+
+    #[serde(rename = "givenName")]
+    pub given_name: String,
+
+    #[serde(rename = "familyName")]
+    pub family_name: String,
+
+    pub gender: String,
+
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub image: Option<String>,
+
+    #[serde(rename = "residentSince")]
+    pub resident_since: chrono::NaiveDate,
+
+    #[serde(rename = "lprCategory")]
+    pub lpr_category: String,
+
+    #[serde(rename = "lprNumber")]
+    pub lpr_number: String,
+
+    #[serde(rename = "commuterClassification")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub commuter_classification: Option<String>,
+
+    #[serde(rename = "birthCountry")]
+    pub birth_country: String,
+
+    #[serde(rename = "birthDate")]
+    pub birth_date: chrono::NaiveDate,
+}
+
+impl TypedCredentialSubject for PermanentResident {
+    fn credential_type(&self) -> CredentialType {
+        CredentialType::PermanentResidentCard
+    }
+
+    fn contexts(&self) -> Vec<super::ContextValue> {
+        vec![super::ContextValue::String(
+            PERMANENT_RESIDENT_CREDENTIAL_CONTEXT.to_string(),
+        )]
+    }
+
+    fn subject_types(&self) -> Vec<&str> {
+        vec!["PermanentResident", "Person"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BankAccount, PermanentResident, PostalAddress, RoutingInfo, TypedCredentialSubject,
+    };
+    use serde_json::json;
+
+    fn create_bank_account() -> BankAccount {
+        BankAccount {
+            id: "did:knox:z6MkoBjc4GfEWrdAXAchrDrjc7LBuTVNXySswadG3apCKy9P".to_string(),
+            account_id: "1111111".to_string(),
+            given_name: "Alice".to_string(),
+            family_name: "Smith".to_string(),
+            address: Some(PostalAddress {
+                street_address: "19 Knox St".to_string(),
+                address_locality: "Toronto".to_string(),
+                address_region: "ON".to_string(),
+                address_country: "Canada".to_string(),
+                postal_code: "M3B 1A2".to_string(),
+            }),
+            routing_info: Some(RoutingInfo {
+                code: "GBDSC".to_string(),
+                value: "042962".to_string(),
+            }),
+            iban: Some("GB74GSLD04296280001319".to_string()),
+            bic11: Some("TDOMCATTTOR".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_bank_account_subject() {
+        let bank_account = create_bank_account();
+        let subject = bank_account.to_subject().unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&subject).unwrap(),
+            json!({
+                "id": "did:knox:z6MkoBjc4GfEWrdAXAchrDrjc7LBuTVNXySswadG3apCKy9P",
+                "type": ["BankAccount"],
+                "accountId": "1111111",
+                "givenName": "Alice",
+                "familyName": "Smith",
+                "address": {
+                    "type": ["PostalAddress"],
+                    "streetAddress": "19 Knox St",
+                    "addressLocality": "Toronto",
+                    "addressRegion": "ON",
+                    "addressCountry": "Canada",
+                    "postalCode": "M3B 1A2"
+                },
+                "routingInfo": {
+                    "type": ["RoutingInfo"],
+                    "code": "GBDSC",
+                    "value": "042962"
+                },
+                "iban": "GB74GSLD04296280001319",
+                "BIC11": "TDOMCATTTOR"
+            })
+        );
+        assert_eq!(BankAccount::from_subject(&subject).unwrap(), bank_account);
+    }
+
+    // Every property and type of the subject is defined by the context of the bank account terms
+    #[test]
+    fn test_bank_account_terms() {
+        let Some(crate::credential::ContextValue::Object(terms)) =
+            create_bank_account().contexts().pop()
+        else {
+            panic!("the bank account terms are not the last context");
+        };
+        let subject = serde_json::to_value(create_bank_account().to_subject().unwrap()).unwrap();
+
+        let mut nodes = vec![&subject];
+        while let Some(serde_json::Value::Object(node)) = nodes.pop() {
+            for (property, value) in node {
+                if property != "id" && property != "type" {
+                    assert!(terms.contains_key(property), "{property} is not defined");
+                }
+                match value {
+                    serde_json::Value::Object(_) => nodes.push(value),
+                    serde_json::Value::Array(types) if property == "type" => {
+                        for node_type in types {
+                            let node_type = node_type.as_str().unwrap();
+                            assert!(terms.contains_key(node_type), "{node_type} is not defined");
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_permanent_resident_subject() {
+        let subject = serde_json::from_value(json!({
+            "id": "did:example:b34ca6cd37bbf23",
+            "type": ["PermanentResident", "Person"],
+            "givenName": "JOHN",
+            "familyName": "SMITH",
+            "gender": "Male",
+            "residentSince": "2015-01-01",
+            "lprCategory": "C09",
+            "lprNumber": "999-999-999",
+            "birthCountry": "Bahamas",
+            "birthDate": "1958-07-17"
+        }))
+        .unwrap();
+
+        let permanent_resident = PermanentResident::from_subject(&subject).unwrap();
+        assert_eq!(permanent_resident.given_name, "JOHN");
+        assert_eq!(
+            permanent_resident.birth_date,
+            chrono::NaiveDate::from_ymd_opt(1958, 7, 17).unwrap()
+        );
+        assert_eq!(permanent_resident.to_subject().unwrap(), subject);
+    }
+
+    #[test]
+    fn test_subject_missing_property() {
+        let subject = serde_json::from_value(json!({
+            "id": "did:example:b34ca6cd37bbf23",
+            "type": ["BankAccount"],
+            "givenName": "Alice"
+        }))
+        .unwrap();
+
+        assert!(BankAccount::from_subject(&subject).is_err());
+    }
+}
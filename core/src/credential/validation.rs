@@ -1,7 +1,12 @@
-use super::ContextValue;
+use super::{ContextValue, CredentialSubject, CredentialType, CredentialVersion, PresentationType};
+use crate::identity::validation::is_uri;
+use crate::proof::{CredentialProof, ProofType};
+
+type Date = chrono::DateTime<chrono::Utc>;
 
 // Context must contain at least one URI
-// The first URI must be https://www.w3.org/2018/credentials/v1 (use BASE_CREDENDIAL_CONTEXT)
+// The first URI must be the base context of a supported VC Data Model version:
+// https://www.w3.org/2018/credentials/v1 or https://www.w3.org/ns/credentials/v2
 pub fn credential_context_validation(
     val: &[ContextValue],
 ) -> Result<(), serde_valid::validation::Error> {
@@ -12,10 +17,11 @@ pub fn credential_context_validation(
                 "Context must contain at least one URI".to_string(),
             ))
         }
-        Some(ContextValue::String(ref s)) if s != super::BASE_CREDENTIAL_CONTEXT => {
+        Some(ContextValue::String(ref s)) if CredentialVersion::from_context(val).is_none() => {
             Err(serde_valid::validation::Error::Custom(format!(
-                "The first URI must be {}, instead found {}",
-                super::BASE_CREDENTIAL_CONTEXT,
+                "The first URI must be {} or {}, instead found {}",
+                super::CREDENTIAL_V1_CONTEXT,
+                super::CREDENTIAL_V2_CONTEXT,
                 s
             )))
         }
@@ -26,9 +32,237 @@ pub fn credential_context_validation(
     }
 }
 
+// The validity properties depend on the version of the data model:
+// V1 requires `issuanceDate` and may have `expirationDate`, V2 uses the optional `validFrom` and `validUntil` instead
+pub fn credential_version_validation(
+    context: &[ContextValue],
+    issuance_date: &Option<Date>,
+    expiration_date: &Option<Date>,
+    valid_from: &Option<Date>,
+    valid_until: &Option<Date>,
+) -> Result<(), serde_valid::validation::Error> {
+    let unexpected = match CredentialVersion::from_context(context) {
+        // Reported by credential_context_validation
+        None => return Ok(()),
+        Some(CredentialVersion::V1) if issuance_date.is_none() => {
+            return Err(serde_valid::validation::Error::Custom(
+                "issuanceDate is required by VC Data Model v1".to_string(),
+            ))
+        }
+        Some(CredentialVersion::V1) => [
+            ("validFrom", valid_from.is_some()),
+            ("validUntil", valid_until.is_some()),
+        ],
+        Some(CredentialVersion::V2) => [
+            ("issuanceDate", issuance_date.is_some()),
+            ("expirationDate", expiration_date.is_some()),
+        ],
+    };
+
+    match unexpected.iter().find(|(_, present)| *present) {
+        Some((property, _)) => Err(serde_valid::validation::Error::Custom(format!(
+            "{property} is not defined by the VC Data Model version of {}",
+            context_uri(context)
+        ))),
+        None => Ok(()),
+    }
+}
+
+// Dates must be RFC 3339 date-times, chrono alone also accepts some relaxed forms (e.g. `+0000` or `UTC` offsets)
+pub fn rfc3339_date<'de, D>(deserializer: D) -> Result<Option<Date>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let val: Option<String> = serde::Deserialize::deserialize(deserializer)?;
+    val.map(|date| {
+        chrono::DateTime::parse_from_rfc3339(&date)
+            .map(|date| date.with_timezone(&chrono::Utc))
+            .map_err(|e| serde::de::Error::custom(format!("{date} is not an RFC 3339 date: {e}")))
+    })
+    .transpose()
+}
+
+// A credential is only valid between its issuance (validFrom) and expiration (validUntil) dates
+pub fn validity_period_validation(
+    issuance_date: &Option<Date>,
+    expiration_date: &Option<Date>,
+    valid_from: &Option<Date>,
+    valid_until: &Option<Date>,
+) -> Result<(), serde_valid::validation::Error> {
+    for (start, end, start_name, end_name) in [
+        (
+            issuance_date,
+            expiration_date,
+            "issuanceDate",
+            "expirationDate",
+        ),
+        (valid_from, valid_until, "validFrom", "validUntil"),
+    ] {
+        if let (Some(start), Some(end)) = (start, end) {
+            if end <= start {
+                return Err(serde_valid::validation::Error::Custom(format!(
+                    "{end_name} {end} must be after {start_name} {start}"
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+// The type of a credential must contain VerifiableCredential
+pub fn credential_type_validation(
+    val: &[CredentialType],
+) -> Result<(), serde_valid::validation::Error> {
+    match val.contains(&CredentialType::VerifiableCredential) {
+        true => Ok(()),
+        false => Err(serde_valid::validation::Error::Custom(
+            "The type must contain VerifiableCredential".to_string(),
+        )),
+    }
+}
+
+// The type of a presentation must contain VerifiablePresentation
+pub fn presentation_type_validation(
+    val: &[PresentationType],
+) -> Result<(), serde_valid::validation::Error> {
+    match val.contains(&PresentationType::VerifiablePresentation) {
+        true => Ok(()),
+        false => Err(serde_valid::validation::Error::Custom(
+            "The type must contain VerifiablePresentation".to_string(),
+        )),
+    }
+}
+
+// Used for the optional `id` of credentials and presentations and the `holder` of presentations
+pub fn optional_uri_validation(val: &Option<String>) -> Result<(), serde_valid::validation::Error> {
+    match val {
+        Some(uri) => uri_validation(uri),
+        None => Ok(()),
+    }
+}
+
+pub fn issuer_validation(val: &super::Issuer) -> Result<(), serde_valid::validation::Error> {
+    uri_validation(val.id()).map_err(|_| {
+        serde_valid::validation::Error::Custom(format!("Issuer {} is not a valid URI", val.id()))
+    })
+}
+
+// There must be at least one subject, each with at least one claim, and subject ids must be URIs
+pub fn credential_subject_validation(
+    val: &CredentialSubject,
+) -> Result<(), serde_valid::validation::Error> {
+    let subjects = match val {
+        CredentialSubject::Single(subject) => vec![subject],
+        CredentialSubject::Set(subjects) => subjects.iter().collect(),
+    };
+    if subjects.is_empty() || subjects.iter().any(|subject| subject.is_empty()) {
+        return Err(serde_valid::validation::Error::Custom(
+            "The credentialSubject must not be empty".to_string(),
+        ));
+    }
+
+    subjects
+        .iter()
+        .filter_map(|subject| subject.get("id"))
+        .try_for_each(|id| match id.as_str() {
+            Some(id) if is_uri(id) => Ok(()),
+            _ => Err(serde_valid::validation::Error::Custom(format!(
+                "The credentialSubject id {id} is not a valid URI"
+            ))),
+        })
+}
+
+// Every proof must have a type, a verification method URI and a value
+pub fn proof_validation(val: &CredentialProof) -> Result<(), serde_valid::validation::Error> {
+    let proofs = match val {
+        CredentialProof::Single(proof) => vec![proof],
+        CredentialProof::Set(proofs) => proofs.iter().collect(),
+    };
+    if proofs.is_empty() {
+        return Err(serde_valid::validation::Error::Custom(
+            "The proof must not be empty".to_string(),
+        ));
+    }
+
+    proofs.into_iter().try_for_each(|proof| {
+        let (proof_type, verification_method, value) = match proof {
+            ProofType::Ed25519Signature2020(proof) => (
+                &proof.proof_type,
+                &proof.verification_method,
+                &proof.proof_value,
+            ),
+            ProofType::RsaSignature2018(proof) => {
+                if let Some(created) = &proof.created {
+                    chrono::DateTime::parse_from_rfc3339(created).map_err(|_| {
+                        serde_valid::validation::Error::Custom(format!(
+                            "The proof creation date {created} is not an RFC 3339 date"
+                        ))
+                    })?;
+                }
+                (&proof.proof_type, &proof.verification_method, &proof.jws)
+            }
+        };
+        if proof_type.is_empty() {
+            return Err(serde_valid::validation::Error::Custom(
+                "The proof type must not be empty".to_string(),
+            ));
+        }
+        if !is_uri(verification_method) {
+            return Err(serde_valid::validation::Error::Custom(format!(
+                "The proof verificationMethod {verification_method} is not a valid URI"
+            )));
+        }
+        match value.is_empty() {
+            true => Err(serde_valid::validation::Error::Custom(format!(
+                "The {proof_type} proof has no value"
+            ))),
+            false => Ok(()),
+        }
+    })
+}
+
+fn uri_validation(val: &str) -> Result<(), serde_valid::validation::Error> {
+    match is_uri(val) {
+        true => Ok(()),
+        false => Err(serde_valid::validation::Error::Custom(format!(
+            "{val} is not a valid URI"
+        ))),
+    }
+}
+
+// An enveloped credential only exists in VC Data Model v2
+pub fn enveloped_context_validation(
+    val: &[ContextValue],
+) -> Result<(), serde_valid::validation::Error> {
+    match CredentialVersion::from_context(val) {
+        Some(CredentialVersion::V2) => Ok(()),
+        _ => Err(serde_valid::validation::Error::Custom(format!(
+            "The first URI of an enveloped credential must be {}",
+            super::CREDENTIAL_V2_CONTEXT
+        ))),
+    }
+}
+
+// The id of an enveloped credential is a data URL holding the secured credential: data:<media type>,<data>
+pub fn data_url_validation(val: &str) -> Result<(), serde_valid::validation::Error> {
+    match val.strip_prefix("data:").and_then(|v| v.split_once(',')) {
+        Some((_, data)) if !data.is_empty() => Ok(()),
+        _ => Err(serde_valid::validation::Error::Custom(format!(
+            "{val} is not a data URL"
+        ))),
+    }
+}
+
+fn context_uri(context: &[ContextValue]) -> &str {
+    match context.first() {
+        Some(ContextValue::String(s)) => s,
+        _ => "",
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::super::BASE_CREDENTIAL_CONTEXT;
+    use super::super::{BASE_CREDENTIAL_CONTEXT, CREDENTIAL_V1_CONTEXT, CREDENTIAL_V2_CONTEXT};
     use super::ContextValue;
 
     #[rstest::rstest]
@@ -41,7 +275,7 @@ mod tests {
     #[case::first_uri_not_base(
         vec![super::ContextValue::String("https://www.w3.org/2018/credentials/v2".to_string())],
         Err(serde_valid::validation::Error::Custom(
-            "The first URI must be https://www.w3.org/2018/credentials/v1, instead found https://www.w3.org/2018/credentials/v2".to_string()
+            "The first URI must be https://www.w3.org/2018/credentials/v1 or https://www.w3.org/ns/credentials/v2, instead found https://www.w3.org/2018/credentials/v2".to_string()
         ))
     )]
     #[case::first_uri_not_string(
@@ -54,6 +288,10 @@ mod tests {
         vec![super::ContextValue::String(BASE_CREDENTIAL_CONTEXT.to_string())],
         Ok(())
     )]
+    #[case::valid_v2_context(
+        vec![super::ContextValue::String(CREDENTIAL_V2_CONTEXT.to_string())],
+        Ok(())
+    )]
     fn test_validate_credential_context(
         #[case] context: Vec<super::ContextValue>,
         #[case] expected: Result<(), serde_valid::validation::Error>,
@@ -63,4 +301,159 @@ mod tests {
             Err(e) => assert_eq!(e.to_string(), expected.unwrap_err().to_string()),
         }
     }
+
+    fn date() -> Option<super::Date> {
+        Some("2023-01-01T00:00:00Z".parse().unwrap())
+    }
+
+    #[rstest::rstest]
+    #[case::v1(CREDENTIAL_V1_CONTEXT, date(), date(), None, None, Ok(()))]
+    #[case::v1_missing_issuance_date(
+        CREDENTIAL_V1_CONTEXT,
+        None,
+        None,
+        None,
+        None,
+        Err("issuanceDate is required by VC Data Model v1".to_string())
+    )]
+    #[case::v1_valid_from(
+        CREDENTIAL_V1_CONTEXT,
+        date(),
+        None,
+        date(),
+        None,
+        Err("validFrom is not defined by the VC Data Model version of https://www.w3.org/2018/credentials/v1".to_string())
+    )]
+    #[case::v2(CREDENTIAL_V2_CONTEXT, None, None, date(), date(), Ok(()))]
+    #[case::v2_without_dates(CREDENTIAL_V2_CONTEXT, None, None, None, None, Ok(()))]
+    #[case::v2_expiration_date(
+        CREDENTIAL_V2_CONTEXT,
+        None,
+        date(),
+        date(),
+        None,
+        Err("expirationDate is not defined by the VC Data Model version of https://www.w3.org/ns/credentials/v2".to_string())
+    )]
+    fn test_validate_credential_version(
+        #[case] context: &str,
+        #[case] issuance_date: Option<super::Date>,
+        #[case] expiration_date: Option<super::Date>,
+        #[case] valid_from: Option<super::Date>,
+        #[case] valid_until: Option<super::Date>,
+        #[case] expected: Result<(), String>,
+    ) {
+        let res = super::credential_version_validation(
+            &[ContextValue::String(context.to_string())],
+            &issuance_date,
+            &expiration_date,
+            &valid_from,
+            &valid_until,
+        );
+        assert_eq!(res.map_err(|e| e.to_string()), expected);
+    }
+
+    fn later_date() -> Option<super::Date> {
+        Some("2024-01-01T00:00:00Z".parse().unwrap())
+    }
+
+    #[rstest::rstest]
+    #[case::v1(date(), later_date(), None, None, Ok(()))]
+    #[case::v2(None, None, date(), later_date(), Ok(()))]
+    #[case::open_ended(date(), None, None, None, Ok(()))]
+    #[case::expired_at_issuance(
+        date(),
+        date(),
+        None,
+        None,
+        Err("expirationDate 2023-01-01 00:00:00 UTC must be after issuanceDate 2023-01-01 00:00:00 UTC".to_string())
+    )]
+    #[case::valid_until_before_valid_from(
+        None,
+        None,
+        later_date(),
+        date(),
+        Err("validUntil 2023-01-01 00:00:00 UTC must be after validFrom 2024-01-01 00:00:00 UTC".to_string())
+    )]
+    fn test_validate_validity_period(
+        #[case] issuance_date: Option<super::Date>,
+        #[case] expiration_date: Option<super::Date>,
+        #[case] valid_from: Option<super::Date>,
+        #[case] valid_until: Option<super::Date>,
+        #[case] expected: Result<(), String>,
+    ) {
+        let res = super::validity_period_validation(
+            &issuance_date,
+            &expiration_date,
+            &valid_from,
+            &valid_until,
+        );
+        assert_eq!(res.map_err(|e| e.to_string()), expected);
+    }
+
+    #[rstest::rstest]
+    #[case::single(serde_json::json!({"id": "did:example:123", "name": "Alice"}), Ok(()))]
+    #[case::without_id(serde_json::json!([{"name": "Alice"}, {"name": "Bob"}]), Ok(()))]
+    #[case::empty_set(
+        serde_json::json!([]),
+        Err("The credentialSubject must not be empty".to_string())
+    )]
+    #[case::empty_subject_in_set(
+        serde_json::json!([{"id": "did:example:123"}, {}]),
+        Err("The credentialSubject must not be empty".to_string())
+    )]
+    #[case::id_not_string(
+        serde_json::json!({"id": 123}),
+        Err("The credentialSubject id 123 is not a valid URI".to_string())
+    )]
+    fn test_validate_credential_subject(
+        #[case] subject: serde_json::Value,
+        #[case] expected: Result<(), String>,
+    ) {
+        let subject = serde_json::from_value(subject).unwrap();
+        let res = super::credential_subject_validation(&subject);
+        assert_eq!(res.map_err(|e| e.to_string()), expected);
+    }
+
+    #[rstest::rstest]
+    #[case::data_integrity(
+        serde_json::json!({
+            "type": "Ed25519Signature2020",
+            "verificationMethod": "did:example:123#key-1",
+            "proofPurpose": "assertionMethod",
+            "proofValue": "z58DAdFfa9SkqZMVPxAQp"
+        }),
+        Ok(())
+    )]
+    #[case::no_value(
+        serde_json::json!({
+            "type": "Ed25519Signature2020",
+            "verificationMethod": "did:example:123#key-1",
+            "proofPurpose": "assertionMethod",
+            "proofValue": ""
+        }),
+        Err("The Ed25519Signature2020 proof has no value".to_string())
+    )]
+    #[case::created_not_rfc3339(
+        serde_json::json!([{
+            "type": "RsaSignature2018",
+            "created": "2017-06-18",
+            "verificationMethod": "https://example.edu/issuers/keys/1",
+            "proofPurpose": "assertionMethod",
+            "jws": "eyJhbGciOiJSUzI1NiJ9..TCYt5X"
+        }]),
+        Err("The proof creation date 2017-06-18 is not an RFC 3339 date".to_string())
+    )]
+    fn test_validate_proof(#[case] proof: serde_json::Value, #[case] expected: Result<(), String>) {
+        let proof = serde_json::from_value(proof).unwrap();
+        let res = super::proof_validation(&proof);
+        assert_eq!(res.map_err(|e| e.to_string()), expected);
+    }
+
+    #[rstest::rstest]
+    #[case::jwt("data:application/vc+jwt,eyJhbGciOiJFUzI1NiJ9.e30.c2ln", true)]
+    #[case::no_data("data:application/vc+jwt,", false)]
+    #[case::not_data_url("https://example.com/credentials/1", false)]
+    fn test_validate_data_url(#[case] url: &str, #[case] valid: bool) {
+        assert_eq!(super::data_url_validation(url).is_ok(), valid);
+    }
 }
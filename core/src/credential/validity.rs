@@ -0,0 +1,203 @@
+// Verification of the validity period of credentials and of the creation time of their proofs against a clock.
+// Each comparison tolerates `skew` of difference between the clock of the verifier and the clock of the issuer.
+use crate::clock::Clock;
+
+/// Verify that the credential is valid at the time of `clock`: it must be issued (`issuanceDate` / `validFrom`), not
+/// expired (`expirationDate` / `validUntil`), and its proofs must neither be created in the future nor be expired.
+pub fn verify_credential_validity(
+    vc: &super::VerifiableCredential,
+    clock: &(impl Clock + ?Sized),
+    skew: chrono::Duration,
+) -> Result<(), crate::error::Error> {
+    let now = clock.now();
+    let credential = &vc.credential;
+    let id = credential.id.as_deref().unwrap_or("The credential");
+
+    let valid_from = [credential.issuance_date, credential.valid_from];
+    if let Some(valid_from) = valid_from.into_iter().flatten().find(|d| now + skew < *d) {
+        return Err(crate::error::Error::CredentialNotYetValid(format!(
+            "{id} is not valid before {valid_from}"
+        )));
+    }
+    let valid_until = [credential.expiration_date, credential.valid_until];
+    if let Some(valid_until) = valid_until.into_iter().flatten().find(|d| now - skew >= *d) {
+        return Err(crate::error::Error::CredentialExpired(format!(
+            "{id} expired at {valid_until}"
+        )));
+    }
+
+    verify_proof_validity(&vc.proof, clock, skew)
+}
+
+/// Verify the proofs of the presentation and the validity of the credentials it embeds at the time of `clock`.
+/// Enveloped credentials are secured by their own envelope and are not checked.
+pub fn verify_presentation_validity(
+    vp: &super::VerifiablePresentation,
+    clock: &(impl Clock + ?Sized),
+    skew: chrono::Duration,
+) -> Result<(), crate::error::Error> {
+    verify_proof_validity(&vp.proof, clock, skew)?;
+    vp.presentation
+        .verifiable_credential
+        .iter()
+        .flatten()
+        .try_for_each(|credential| match credential {
+            super::PresentationCredential::Embedded(vc) => {
+                verify_credential_validity(vc, clock, skew)
+            }
+            super::PresentationCredential::Enveloped(_) => Ok(()),
+        })
+}
+
+/// Reject proofs created after the time of `clock` or expired before it.
+pub fn verify_proof_validity(
+    proof: &crate::proof::CredentialProof,
+    clock: &(impl Clock + ?Sized),
+    skew: chrono::Duration,
+) -> Result<(), crate::error::Error> {
+    let now = clock.now();
+    let proofs = match proof {
+        crate::proof::CredentialProof::Single(proof) => vec![proof],
+        crate::proof::CredentialProof::Set(proofs) => proofs.iter().collect(),
+    };
+
+    proofs.into_iter().try_for_each(|proof| {
+        let (created, expires) = match proof {
+            crate::proof::ProofType::Ed25519Signature2020(proof) => (proof.created, proof.expires),
+            crate::proof::ProofType::RsaSignature2018(proof) => (
+                proof
+                    .created
+                    .as_deref()
+                    .and_then(|created| chrono::DateTime::parse_from_rfc3339(created).ok())
+                    .map(|created| created.with_timezone(&chrono::Utc)),
+                None,
+            ),
+        };
+        match (created, expires) {
+            (Some(created), _) if now + skew < created => Err(
+                crate::error::Error::ProofNotYetValid(format!("The proof is created at {created}")),
+            ),
+            (_, Some(expires)) if now - skew >= expires => Err(crate::error::Error::ProofExpired(
+                format!("The proof expired at {expires}"),
+            )),
+            _ => Ok(()),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{verify_credential_validity, verify_presentation_validity};
+    use crate::clock::FixedClock;
+    use serde_json::json;
+
+    fn clock(now: &str) -> FixedClock {
+        FixedClock(now.parse().unwrap())
+    }
+
+    fn skew() -> chrono::Duration {
+        chrono::Duration::minutes(5)
+    }
+
+    fn create_verifiable_credential(
+        dates: serde_json::Value,
+        proof_dates: serde_json::Value,
+    ) -> crate::credential::VerifiableCredential {
+        let mut vc = json!({
+            "@context": ["https://www.w3.org/ns/credentials/v2"],
+            "id": "https://example.com/credentials/3732",
+            "type": ["VerifiableCredential"],
+            "issuer": "did:example:28394728934792387",
+            "credentialSubject": {"id": "did:example:b34ca6cd37bbf23"},
+            "proof": {
+                "type": "Ed25519Signature2020",
+                "verificationMethod": "did:example:28394728934792387#key-1",
+                "proofPurpose": "assertionMethod",
+                "proofValue": "z58DAdFfa9SkqZMVPxAQp"
+            }
+        });
+        for (property, date) in dates.as_object().unwrap() {
+            vc[property] = date.clone();
+        }
+        for (property, date) in proof_dates.as_object().unwrap() {
+            vc["proof"][property] = date.clone();
+        }
+        serde_json::from_value(vc).unwrap()
+    }
+
+    #[rstest::rstest]
+    #[case::valid(
+        json!({"validFrom": "2024-01-01T00:00:00Z", "validUntil": "2025-01-01T00:00:00Z"}),
+        json!({"created": "2024-01-01T00:00:00Z", "expires": "2025-01-01T00:00:00Z"}),
+        "2024-06-01T00:00:00Z",
+        None
+    )]
+    #[case::without_dates(json!({}), json!({}), "2024-06-01T00:00:00Z", None)]
+    #[case::issued_within_skew(
+        json!({"validFrom": "2024-01-01T00:04:00Z"}),
+        json!({"created": "2024-01-01T00:04:00Z"}),
+        "2024-01-01T00:00:00Z",
+        None
+    )]
+    #[case::not_yet_valid(
+        json!({"validFrom": "2024-01-01T00:06:00Z"}),
+        json!({}),
+        "2024-01-01T00:00:00Z",
+        Some("Credential not yet valid: https://example.com/credentials/3732 is not valid before 2024-01-01 00:06:00 UTC")
+    )]
+    #[case::expired(
+        json!({"validUntil": "2024-01-01T00:00:00Z"}),
+        json!({}),
+        "2024-01-01T00:05:00Z",
+        Some("Credential expired: https://example.com/credentials/3732 expired at 2024-01-01 00:00:00 UTC")
+    )]
+    #[case::proof_created_in_future(
+        json!({}),
+        json!({"created": "2024-01-01T00:10:00Z"}),
+        "2024-01-01T00:00:00Z",
+        Some("Proof not yet valid: The proof is created at 2024-01-01 00:10:00 UTC")
+    )]
+    #[case::proof_expired(
+        json!({}),
+        json!({"expires": "2024-01-01T00:00:00Z"}),
+        "2024-01-02T00:00:00Z",
+        Some("Proof expired: The proof expired at 2024-01-01 00:00:00 UTC")
+    )]
+    fn test_verify_credential_validity(
+        #[case] dates: serde_json::Value,
+        #[case] proof_dates: serde_json::Value,
+        #[case] now: &str,
+        #[case] expected_error: Option<&str>,
+    ) {
+        let vc = create_verifiable_credential(dates, proof_dates);
+
+        let res = verify_credential_validity(&vc, &clock(now), skew());
+        assert_eq!(res.err().map(|e| e.to_string()).as_deref(), expected_error);
+    }
+
+    #[test]
+    fn test_verify_presentation_validity() {
+        let vc =
+            create_verifiable_credential(json!({"validUntil": "2024-01-01T00:00:00Z"}), json!({}));
+        let vp = crate::credential::VerifiablePresentation {
+            presentation: crate::credential::Presentation {
+                context: vec![crate::credential::ContextValue::String(
+                    crate::credential::CREDENTIAL_V2_CONTEXT.to_string(),
+                )],
+                id: None,
+                presentation_type: vec![
+                    crate::credential::PresentationType::VerifiablePresentation,
+                ],
+                holder: None,
+                verifiable_credential: Some(vec![vc.clone().into()]),
+            },
+            proof: vc.proof,
+        };
+
+        assert!(verify_presentation_validity(&vp, &clock("2023-12-01T00:00:00Z"), skew()).is_ok());
+        assert!(matches!(
+            verify_presentation_validity(&vp, &clock("2024-02-01T00:00:00Z"), skew()),
+            Err(crate::error::Error::CredentialExpired(_))
+        ));
+    }
+}
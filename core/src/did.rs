@@ -0,0 +1,113 @@
+// This module provides the dereferencing of DID URLs into the resources of a resolved DID Document.
+// The DID syntax is implemented by the signature crate, so that signers can build their DIDs from it.
+pub use signature::did::{Did, DidUrl};
+
+/// The resource a DID URL points to inside of a DID Document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DereferencedResource {
+    Document(Box<crate::identity::DidDocument>),
+    VerificationMethod(crate::identity::KeyMaterial),
+    Service(crate::identity::Service),
+}
+
+/// Given a DID URL, resolve its DID Document through the `resolver` and return the resource the DID URL points to.
+/// A DID URL without a fragment dereferences to the DID Document itself.
+/// The `service` DID parameter or the fragment select a service, the fragment can also select a verification method.
+pub async fn dereference(
+    resolver: &(impl crate::DIDResolver + ?Sized),
+    did_url: &DidUrl,
+) -> Result<DereferencedResource, crate::error::ResolverError> {
+    let rsp = crate::identity::resolve(resolver, &did_url.did().to_string()).await?;
+
+    rsp.did_document.dereference(did_url).ok_or_else(|| {
+        crate::error::ResolverError::DocumentNotFound(format!(
+            "No resource found in DID Document for {did_url}"
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockDIDResolver;
+    use std::str::FromStr;
+
+    macro_rules! aw {
+        ($e:expr) => {
+            tokio_test::block_on($e)
+        };
+    }
+
+    fn get_resolve_response() -> crate::ResolveResponse {
+        crate::ResolveResponse {
+            did_document: serde_json::json!({
+                "@context": ["https://www.w3.org/ns/did/v1"],
+                "id": "did:example:123",
+                "verificationMethod": [{
+                    "id": "did:example:123#key-1",
+                    "type": "Ed25519VerificationKey2020",
+                    "controller": "did:example:123",
+                    "publicKeyMultibase": "z6MkmgYPyjwqrMyHYBFfEcetAAoW7A9njsC4ToZ1WnjAgRL1"
+                }],
+                "keyAgreement": [{
+                    "id": "#key-agreement-1",
+                    "type": "X25519KeyAgreementKey2020",
+                    "controller": "did:example:123",
+                    "publicKeyMultibase": "z6LSbysY2xFMRpGMhb7tFTLMpeuPRaqaWM1yECx2AtzE3KCc"
+                }],
+                "authentication": ["#key-1"],
+                "service": [{
+                    "id": "#agent",
+                    "type": "DIDCommMessaging",
+                    "serviceEndpoint": "https://agent.example.com"
+                }]
+            }),
+            did_document_metadata: crate::DidDocumentMetadata {
+                created: chrono::DateTime::parse_from_rfc3339("2021-04-28T20:00:00.000Z")
+                    .unwrap()
+                    .into(),
+                updated: chrono::DateTime::parse_from_rfc3339("2021-04-28T20:00:00.000Z")
+                    .unwrap()
+                    .into(),
+                ..Default::default()
+            },
+            did_resolution_metadata: crate::ResolutionMetadata {
+                content_type: None,
+                duration: None,
+                did_url: None,
+                error: None,
+            },
+        }
+    }
+
+    #[rstest::rstest]
+    #[case::document("did:example:123", Some("did:example:123"))]
+    #[case::verification_method("did:example:123#key-1", Some("did:example:123#key-1"))]
+    #[case::embedded_verification_method(
+        "did:example:123#key-agreement-1",
+        Some("#key-agreement-1")
+    )]
+    #[case::service_fragment("did:example:123#agent", Some("#agent"))]
+    #[case::service_parameter("did:example:123?service=agent", Some("#agent"))]
+    #[case::unknown_fragment("did:example:123#key-2", None)]
+    fn test_dereference(#[case] did_url: &str, #[case] expected_id: Option<&str>) {
+        let mut resolver_mock = MockDIDResolver::default();
+        resolver_mock
+            .expect_resolve()
+            .with(mockall::predicate::eq("did:example:123".to_string()))
+            .return_once(|_| Ok(get_resolve_response()));
+
+        let did_url = DidUrl::from_str(did_url).unwrap();
+        let res = aw!(dereference(&resolver_mock, &did_url));
+
+        match (res, expected_id) {
+            (Ok(DereferencedResource::Document(doc)), Some(id)) => assert_eq!(doc.id, id),
+            (Ok(DereferencedResource::VerificationMethod(method)), Some(id)) => {
+                assert_eq!(method.id, id)
+            }
+            (Ok(DereferencedResource::Service(service)), Some(id)) => assert_eq!(service.id, id),
+            (Err(crate::error::ResolverError::DocumentNotFound(_)), None) => {}
+            (res, expected) => panic!("unexpected result {res:?}, expected {expected:?}"),
+        }
+    }
+}
@@ -9,8 +9,50 @@ pub enum Error {
     #[error("Serde Valid Error: {0}")]
     SerdeValid(#[from] serde_valid::Error<serde_json::Error>),
 
+    #[error("Invalid DID: {0}")]
+    InvalidDid(String),
+
     #[error("Signature Error: {0}")]
     Signature(#[from] signature::suite::error::Error),
+
+    #[error("Resolver Error: {0}")]
+    Resolver(#[from] ResolverError),
+
+    #[error("Invalid Proof: {0}")]
+    InvalidProof(String),
+
+    #[error("Invalid Status List: {0}")]
+    InvalidStatusList(String),
+
+    #[error("Credential revoked: {0}")]
+    CredentialRevoked(String),
+
+    #[error("Credential suspended: {0}")]
+    CredentialSuspended(String),
+
+    #[error("Credential expired: {0}")]
+    CredentialExpired(String),
+
+    #[error("Credential not yet valid: {0}")]
+    CredentialNotYetValid(String),
+
+    #[error("Proof expired: {0}")]
+    ProofExpired(String),
+
+    #[error("Proof not yet valid: {0}")]
+    ProofNotYetValid(String),
+
+    #[error("Invalid Credential Subject: {0}")]
+    InvalidCredentialSubject(String),
+
+    #[error("Invalid Credential Schema: {0}")]
+    InvalidCredentialSchema(String),
+
+    #[error("Schema Validation Error: {0}")]
+    SchemaValidation(crate::credential::schema::SchemaViolations),
+
+    #[error("Terms not defined by the context: {}", .0.join(", "))]
+    UndefinedTerms(Vec<String>),
 }
 
 #[derive(Debug, thiserror::Error, PartialEq, Eq)]
@@ -26,4 +68,47 @@ pub enum ResolverError {
 
     #[error("Network Failure: {0}")]
     NetworkFailure(String),
+
+    #[error("Invalid DID: {0}")]
+    InvalidDid(String),
+
+    #[error("Representation not supported: {0}")]
+    RepresentationNotSupported(String),
+
+    #[error("Method not supported: {0}")]
+    MethodNotSupported(String),
+
+    #[error("DID deactivated: {0}")]
+    Deactivated(String),
+
+    #[error("Timeout: {0}")]
+    Timeout(String),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+}
+
+impl ResolverError {
+    // Transient errors are expected to go away by themselves, so the request can be retried.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            ResolverError::NetworkFailure(_) | ResolverError::Timeout(_)
+        )
+    }
+}
+
+// The parse error already reads "Invalid DID: ...", keep only its reason
+impl From<signature::did::Error> for Error {
+    fn from(e: signature::did::Error) -> Self {
+        let signature::did::Error::InvalidDid(reason) = e;
+        Error::InvalidDid(reason)
+    }
+}
+
+impl From<signature::did::Error> for ResolverError {
+    fn from(e: signature::did::Error) -> Self {
+        let signature::did::Error::InvalidDid(reason) = e;
+        ResolverError::InvalidDid(reason)
+    }
 }
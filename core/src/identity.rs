@@ -1,121 +1,642 @@
+pub(crate) mod validation;
+
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
+use serde_valid::json::{FromJsonStr, FromJsonValue};
+use serde_valid::Validate;
 
 pub async fn recover<S>(
     resolver: impl crate::DIDResolver,
     verifier: impl signature::suite::DIDVerifier<S>,
-) -> Result<serde_json::Value, crate::error::ResolverError>
+) -> Result<DidDocument, crate::error::ResolverError>
 where
     S: signature::suite::Signature,
 {
-    let rsp = resolver.resolve(verifier.get_did()).await?;
+    let rsp = resolve(&resolver, &verifier.get_did()).await?;
     Ok(rsp.did_document)
 }
 
+/// Given a `did`, resolve its DID Document through the `resolver` and parse it into a DidDocument.
+/// Returns `ResolverError::InvalidData` when the resolved document does not follow the DID Core data model.
+pub async fn resolve(
+    resolver: &(impl crate::DIDResolver + ?Sized),
+    did: &str,
+) -> Result<crate::TypedResolveResponse, crate::error::ResolverError> {
+    resolver.resolve(did.to_string()).await?.try_into()
+}
+
 pub async fn create_identity<S>(
     verifier: impl signature::suite::DIDVerifier<S>,
+    services: Vec<Service>,
 ) -> Result<DidDocument, crate::error::Error>
 where
     S: signature::suite::Signature,
 {
-    let did_doc = create_did_document(verifier);
+    let did_doc = DidDocument {
+        service: services,
+        ..create_did_document(verifier)
+    };
+    did_doc
+        .validate()
+        .map_err(serde_valid::Error::ValidationError)?;
 
     Ok(did_doc)
 }
 
 pub async fn register_identity(
-    resolver: &impl super::DIDResolver,
+    resolver: &(impl super::DIDResolver + ?Sized),
     did_doc: DidDocument,
 ) -> Result<DidDocument, crate::error::Error> {
     let encoded_did_doc = serde_json::to_value(did_doc.clone())?;
+    resolver.create(did_doc.id.clone(), encoded_did_doc).await?;
+
+    Ok(did_doc)
+}
+
+/// Sign `did_doc` with the `capabilityInvocation` key of the `signer` and replace the registered DID Document with it.
+/// The registry authorizes the update when the key is a `capabilityInvocation` key of the current document.
+pub async fn update_identity<S>(
+    resolver: &(impl super::DIDResolver + ?Sized),
+    did_doc: DidDocument,
+    signer: &impl signature::suite::DIDSigner<S>,
+) -> Result<DidDocument, crate::error::Error>
+where
+    S: signature::suite::Signature,
+{
+    did_doc
+        .validate()
+        .map_err(serde_valid::Error::ValidationError)?;
+    let signed_did_doc = did_doc.clone().try_into_signed_document(signer)?;
+
     resolver
-        .create(did_doc.id.clone(), encoded_did_doc)
-        .await
-        .map_err(|e| crate::error::Error::Unknown(e.to_string()))?;
+        .update(did_doc.id.clone(), serde_json::to_value(signed_did_doc)?)
+        .await?;
 
     Ok(did_doc)
 }
 
+/// Sign the current `did_doc` with the `capabilityInvocation` key of the `signer` as a proof of ownership and
+/// deactivate its DID.
+pub async fn deactivate_identity<S>(
+    resolver: &(impl super::DIDResolver + ?Sized),
+    did_doc: DidDocument,
+    signer: &impl signature::suite::DIDSigner<S>,
+) -> Result<(), crate::error::Error>
+where
+    S: signature::suite::Signature,
+{
+    let did = did_doc.id.clone();
+    let signed_did_doc = did_doc.try_into_signed_document(signer)?;
+
+    Ok(resolver
+        .deactivate(did, serde_json::to_value(signed_did_doc)?)
+        .await?)
+}
+
 fn create_did_document<S>(verifier: impl signature::suite::DIDVerifier<S>) -> DidDocument
 where
     S: signature::suite::Signature,
 {
     DidDocument {
         id: verifier.get_did(),
-        context: vec![
-            "https://www.w3.org/ns/did/v1".to_string(),
-            "https://w3id.org/security/suites/ed25519-2020/v1".to_string(),
-        ],
-        authentication: vec![KeyMaterial {
-            id: verifier
-                .get_verification_method(signature::suite::VerificationRelation::Authentication),
-            proof_type: verifier.get_key_material_type(),
-            controller: verifier.get_did(),
-            public_key_multibase: verifier.get_encoded_public_key_by_relation(
-                signature::suite::VerificationRelation::Authentication,
-            ),
-        }],
-        capability_invocation: vec![KeyMaterial {
-            id: verifier.get_verification_method(
-                signature::suite::VerificationRelation::CapabilityInvocation,
-            ),
-            proof_type: verifier.get_key_material_type(),
-            controller: verifier.get_did(),
-            public_key_multibase: verifier.get_encoded_public_key_by_relation(
-                signature::suite::VerificationRelation::CapabilityInvocation,
-            ),
-        }],
-        capability_delegation: vec![KeyMaterial {
-            id: verifier.get_verification_method(
-                signature::suite::VerificationRelation::CapabilityDelegation,
-            ),
-            proof_type: verifier.get_key_material_type(),
-            controller: verifier.get_did(),
-            public_key_multibase: verifier.get_encoded_public_key_by_relation(
-                signature::suite::VerificationRelation::CapabilityDelegation,
-            ),
-        }],
-        assertion_method: vec![KeyMaterial {
-            id: verifier
-                .get_verification_method(signature::suite::VerificationRelation::AssertionMethod),
-            proof_type: verifier.get_key_material_type(),
-            controller: verifier.get_did(),
-            public_key_multibase: verifier.get_encoded_public_key_by_relation(
-                signature::suite::VerificationRelation::AssertionMethod,
-            ),
-        }],
+        context: DidContext::Set(vec![
+            crate::credential::ContextValue::String(DID_CONTEXT.to_string()),
+            crate::credential::ContextValue::String(ED25519_2020_CONTEXT.to_string()),
+        ]),
+        controller: None,
+        also_known_as: vec![],
+        verification_method: vec![],
+        authentication: vec![create_key_material(
+            &verifier,
+            signature::suite::VerificationRelation::Authentication,
+        )
+        .into()],
+        capability_invocation: vec![create_key_material(
+            &verifier,
+            signature::suite::VerificationRelation::CapabilityInvocation,
+        )
+        .into()],
+        capability_delegation: vec![create_key_material(
+            &verifier,
+            signature::suite::VerificationRelation::CapabilityDelegation,
+        )
+        .into()],
+        assertion_method: vec![create_key_material(
+            &verifier,
+            signature::suite::VerificationRelation::AssertionMethod,
+        )
+        .into()],
+        key_agreement: vec![],
+        service: vec![],
+        property_set: std::collections::HashMap::new(),
     }
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+fn create_key_material<S>(
+    verifier: &impl signature::suite::DIDVerifier<S>,
+    relation: signature::suite::VerificationRelation,
+) -> KeyMaterial
+where
+    S: signature::suite::Signature,
+{
+    KeyMaterial {
+        id: verifier.get_verification_method(relation),
+        proof_type: verifier.get_key_material_type(),
+        controller: verifier.get_did(),
+        public_key_multibase: Some(verifier.get_encoded_public_key_by_relation(relation)),
+        public_key_jwk: None,
+        property_set: std::collections::HashMap::new(),
+    }
+}
+
+pub const DID_CONTEXT: &str = "https://www.w3.org/ns/did/v1";
+pub const ED25519_2020_CONTEXT: &str = "https://w3id.org/security/suites/ed25519-2020/v1";
+pub const X25519_2020_CONTEXT: &str = "https://w3id.org/security/suites/x25519-2020/v1";
+pub const JWS_2020_CONTEXT: &str = "https://w3id.org/security/suites/jws-2020/v1";
+
+pub const LINKED_DOMAINS_SERVICE_TYPE: &str = "LinkedDomains";
+pub const DIDCOMM_MESSAGING_SERVICE_TYPE: &str = "DIDCommMessaging";
+
+// A verification method as defined in https://www.w3.org/TR/did-core/#verification-methods
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct KeyMaterial {
     pub id: String,
     #[serde(rename = "type")]
     pub proof_type: String,
     pub controller: String,
     #[serde(rename = "publicKeyMultibase")]
-    pub public_key_multibase: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_key_multibase: Option<String>,
+    #[serde(rename = "publicKeyJwk")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_key_jwk: Option<std::collections::HashMap<String, serde_json::Value>>,
+
+    #[serde(flatten)]
+    pub property_set: std::collections::HashMap<String, serde_json::Value>,
+}
+
+// Verification relationships either embed a verification method or reference one by its DID URL.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum VerificationMethodEntry {
+    Reference(String),
+    Embedded(KeyMaterial),
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum DidContext {
+    Single(crate::credential::ContextValue),
+    Set(crate::credential::DocumentContext),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Controller {
+    Single(String),
+    Set(Vec<String>),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ServiceType {
+    Single(String),
+    Set(Vec<String>),
+}
+
+// The value of `serviceEndpoint` is either a URI, a map or a set composed of URIs and/or maps.
+// https://www.w3.org/TR/did-core/#dfn-serviceendpoint
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ServiceEndpoint {
+    Uri(String),
+    Map(std::collections::HashMap<String, serde_json::Value>),
+    Set(Vec<ServiceEndpointValue>),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ServiceEndpointValue {
+    Uri(String),
+    Map(std::collections::HashMap<String, serde_json::Value>),
+}
+
+// A service as defined in https://www.w3.org/TR/did-core/#services
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Service {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub service_type: ServiceType,
+    #[serde(rename = "serviceEndpoint")]
+    pub service_endpoint: ServiceEndpoint,
+
+    #[serde(flatten)]
+    pub property_set: std::collections::HashMap<String, serde_json::Value>,
+}
+
+// A DID Document secured with a data integrity proof created by a `capabilityInvocation` key of the DID controller,
+// authorizing the document to replace the registered one.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SignedDidDocument {
+    #[serde(flatten)]
+    pub document: DidDocument,
+    pub proof: crate::proof::CredentialProof,
+}
+
+// Follows the data model defined in https://www.w3.org/TR/did-core/#core-properties
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, serde_valid::Validate)]
+#[rule(validation::controller_validation(controller))]
+#[rule(validation::unique_id_validation(
+    id,
+    verification_method,
+    authentication,
+    assertion_method,
+    key_agreement,
+    capability_invocation,
+    capability_delegation,
+    service
+))]
 pub struct DidDocument {
-    #[serde(rename = "@context")]
-    pub context: Vec<String>,
+    #[validate(custom(validation::did_context_validation))]
+    #[serde(
+        rename = "@context",
+        default,
+        skip_serializing_if = "DidContext::is_empty"
+    )]
+    pub context: DidContext,
 
+    #[validate(custom(validation::did_validation))]
     #[serde(rename = "id")]
     pub id: String,
-    pub authentication: Vec<KeyMaterial>,
-    #[serde(rename = "capabilityInvocation")]
-    pub capability_invocation: Vec<KeyMaterial>,
-    #[serde(rename = "capabilityDelegation")]
-    pub capability_delegation: Vec<KeyMaterial>,
-    #[serde(rename = "assertionMethod")]
-    pub assertion_method: Vec<KeyMaterial>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub controller: Option<Controller>,
+
+    #[validate(custom(validation::also_known_as_validation))]
+    #[serde(rename = "alsoKnownAs", default, skip_serializing_if = "Vec::is_empty")]
+    pub also_known_as: Vec<String>,
+
+    #[validate(custom(validation::verification_method_validation))]
+    #[serde(
+        rename = "verificationMethod",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub verification_method: Vec<KeyMaterial>,
+
+    #[validate(custom(validation::verification_relationship_validation))]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authentication: Vec<VerificationMethodEntry>,
+
+    #[validate(custom(validation::verification_relationship_validation))]
+    #[serde(
+        rename = "assertionMethod",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub assertion_method: Vec<VerificationMethodEntry>,
+
+    #[validate(custom(validation::verification_relationship_validation))]
+    #[serde(
+        rename = "keyAgreement",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub key_agreement: Vec<VerificationMethodEntry>,
+
+    #[validate(custom(validation::verification_relationship_validation))]
+    #[serde(
+        rename = "capabilityInvocation",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub capability_invocation: Vec<VerificationMethodEntry>,
+
+    #[validate(custom(validation::verification_relationship_validation))]
+    #[serde(
+        rename = "capabilityDelegation",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub capability_delegation: Vec<VerificationMethodEntry>,
+
+    #[validate(custom(validation::service_validation))]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub service: Vec<Service>,
+
+    #[serde(flatten)]
+    pub property_set: std::collections::HashMap<String, serde_json::Value>,
+}
+
+impl DidContext {
+    pub fn is_empty(&self) -> bool {
+        matches!(self, DidContext::Set(set) if set.is_empty())
+    }
+
+    pub fn first(&self) -> Option<&crate::credential::ContextValue> {
+        match self {
+            DidContext::Single(value) => Some(value),
+            DidContext::Set(set) => set.first(),
+        }
+    }
+}
+
+impl Default for DidContext {
+    fn default() -> Self {
+        DidContext::Set(vec![])
+    }
+}
+
+impl Controller {
+    pub fn as_vec(&self) -> Vec<&str> {
+        match self {
+            Controller::Single(controller) => vec![controller.as_str()],
+            Controller::Set(controllers) => controllers.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+impl ServiceType {
+    pub fn contains(&self, service_type: &str) -> bool {
+        match self {
+            ServiceType::Single(t) => t == service_type,
+            ServiceType::Set(types) => types.iter().any(|t| t == service_type),
+        }
+    }
+}
+
+impl ServiceEndpoint {
+    /// Returns every URI of the endpoint. Map endpoints (e.g. `DIDCommMessaging`) contribute their `uri` property.
+    pub fn uris(&self) -> Vec<&str> {
+        match self {
+            ServiceEndpoint::Uri(uri) => vec![uri.as_str()],
+            ServiceEndpoint::Map(map) => map_uri(map).into_iter().collect(),
+            ServiceEndpoint::Set(set) => set
+                .iter()
+                .filter_map(|value| match value {
+                    ServiceEndpointValue::Uri(uri) => Some(uri.as_str()),
+                    ServiceEndpointValue::Map(map) => map_uri(map),
+                })
+                .collect(),
+        }
+    }
+}
+
+fn map_uri(map: &std::collections::HashMap<String, serde_json::Value>) -> Option<&str> {
+    map.get("uri").and_then(serde_json::Value::as_str)
+}
+
+impl Service {
+    pub fn new(
+        id: impl Into<String>,
+        service_type: impl Into<String>,
+        endpoint: ServiceEndpoint,
+    ) -> Self {
+        Service {
+            id: id.into(),
+            service_type: ServiceType::Single(service_type.into()),
+            service_endpoint: endpoint,
+            property_set: std::collections::HashMap::new(),
+        }
+    }
+}
+
+impl VerificationMethodEntry {
+    pub fn id(&self) -> &str {
+        match self {
+            VerificationMethodEntry::Reference(id) => id,
+            VerificationMethodEntry::Embedded(method) => &method.id,
+        }
+    }
+}
+
+impl From<KeyMaterial> for VerificationMethodEntry {
+    fn from(method: KeyMaterial) -> Self {
+        VerificationMethodEntry::Embedded(method)
+    }
 }
 
 impl DidDocument {
     pub fn to_json(&self) -> Result<String, crate::error::Error> {
         Ok(serde_json::to_string(self)?)
     }
+
+    /// Given a service `id`, either absolute or relative to the document (`#service-1`), return the matching service.
+    pub fn get_service(&self, id: &str) -> Option<&Service> {
+        self.service
+            .iter()
+            .find(|service| self.is_same_id(&service.id, id))
+    }
+
+    /// Returns every service that lists `service_type` among its types.
+    pub fn get_services_by_type(&self, service_type: &str) -> Vec<&Service> {
+        self.service
+            .iter()
+            .filter(|service| service.service_type.contains(service_type))
+            .collect()
+    }
+
+    /// Returns the endpoint of the first service that lists `service_type` among its types.
+    pub fn get_service_endpoint_by_type(&self, service_type: &str) -> Option<&ServiceEndpoint> {
+        self.get_services_by_type(service_type)
+            .first()
+            .map(|service| &service.service_endpoint)
+    }
+
+    /// Given a verification method `id`, either absolute or relative to the document (`#key-1`), return the matching
+    /// method from `verificationMethod` or embedded in one of the verification relationships.
+    pub fn get_verification_method(&self, id: &str) -> Option<&KeyMaterial> {
+        let embedded = [
+            &self.authentication,
+            &self.assertion_method,
+            &self.key_agreement,
+            &self.capability_invocation,
+            &self.capability_delegation,
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| match entry {
+            VerificationMethodEntry::Embedded(method) => Some(method),
+            VerificationMethodEntry::Reference(_) => None,
+        });
+
+        self.verification_method
+            .iter()
+            .chain(embedded)
+            .find(|method| self.is_same_id(&method.id, id))
+    }
+
+    /// Same as `get_verification_method`, only returning the method when it is authorized for the verification
+    /// `relation` of the document.
+    pub fn get_verification_method_by_relation(
+        &self,
+        id: &str,
+        relation: signature::suite::VerificationRelation,
+    ) -> Option<&KeyMaterial> {
+        let entries = match relation {
+            signature::suite::VerificationRelation::AssertionMethod => &self.assertion_method,
+            signature::suite::VerificationRelation::Authentication => &self.authentication,
+            signature::suite::VerificationRelation::CapabilityInvocation => {
+                &self.capability_invocation
+            }
+            signature::suite::VerificationRelation::CapabilityDelegation => {
+                &self.capability_delegation
+            }
+        };
+        entries
+            .iter()
+            .any(|entry| self.is_same_id(entry.id(), id))
+            .then(|| self.get_verification_method(id))
+            .flatten()
+    }
+
+    /// Return the resource of this document the DID URL points to, see crate::did::dereference.
+    pub fn dereference(
+        &self,
+        did_url: &crate::did::DidUrl,
+    ) -> Option<crate::did::DereferencedResource> {
+        if did_url.did().to_string() != self.id {
+            return None;
+        }
+
+        if let Some(service) = did_url.query_param("service") {
+            return self
+                .get_service(&format!("#{service}"))
+                .cloned()
+                .map(crate::did::DereferencedResource::Service);
+        }
+
+        match (did_url.path(), did_url.fragment()) {
+            (None, None) => Some(crate::did::DereferencedResource::Document(Box::new(
+                self.clone(),
+            ))),
+            (_, Some(fragment)) => {
+                let id = format!("#{fragment}");
+                match self.get_verification_method(&id) {
+                    Some(method) => Some(crate::did::DereferencedResource::VerificationMethod(
+                        method.clone(),
+                    )),
+                    None => self
+                        .get_service(&id)
+                        .cloned()
+                        .map(crate::did::DereferencedResource::Service),
+                }
+            }
+            // Paths are method specific and not part of the DID Document
+            (Some(_), None) => None,
+        }
+    }
+
+    fn is_same_id(&self, left: &str, right: &str) -> bool {
+        let relative = |id: &'_ str| -> String {
+            match id.strip_prefix(self.id.as_str()) {
+                Some(fragment) if fragment.starts_with('#') => fragment.to_string(),
+                _ => id.to_string(),
+            }
+        };
+        relative(left) == relative(right)
+    }
+}
+
+impl DidDocument {
+    /// Sign the document with the `capabilityInvocation` key of the `signer`, see DIDResolver::update and
+    /// DIDResolver::deactivate.
+    pub fn try_into_signed_document<S: signature::suite::Signature>(
+        self,
+        signer: &impl signature::suite::DIDSigner<S>,
+    ) -> Result<SignedDidDocument, crate::error::Error> {
+        let proof = crate::proof::create_data_integrity_proof(
+            signer,
+            serde_json::to_value(&self)?,
+            signature::suite::VerificationRelation::CapabilityInvocation,
+            &crate::clock::SystemClock,
+        )?;
+
+        Ok(SignedDidDocument {
+            document: self,
+            proof,
+        })
+    }
+}
+
+impl SignedDidDocument {
+    /// Verify that the document was signed by one of the `capabilityInvocation` keys of `controller_document`, the
+    /// currently registered document of the DID.
+    /// Currently only `Ed25519Signature2020` proofs created by `Ed25519VerificationKey2020` keys are supported.
+    pub fn verify_capability_invocation(
+        &self,
+        controller_document: &DidDocument,
+    ) -> Result<(), crate::error::ResolverError> {
+        let unauthorized = |reason: &str| {
+            crate::error::ResolverError::Unauthorized(format!(
+                "Changing {} is not authorized: {reason}",
+                controller_document.id
+            ))
+        };
+
+        if self.document.id != controller_document.id {
+            return Err(unauthorized("the document id does not match the DID"));
+        }
+        let proof = match &self.proof {
+            crate::proof::CredentialProof::Single(
+                crate::proof::ProofType::Ed25519Signature2020(proof),
+            ) => proof,
+            _ => {
+                return Err(unauthorized(
+                    "a single Ed25519Signature2020 proof is required",
+                ))
+            }
+        };
+        if proof.proof_purpose != signature::suite::VerificationRelation::CapabilityInvocation {
+            return Err(unauthorized(
+                "the proof purpose must be capabilityInvocation",
+            ));
+        }
+
+        let is_invocation_key = controller_document
+            .capability_invocation
+            .iter()
+            .any(|entry| controller_document.is_same_id(entry.id(), &proof.verification_method));
+        let public_key = controller_document
+            .get_verification_method(&proof.verification_method)
+            .filter(|_| is_invocation_key)
+            .and_then(|method| method.public_key_multibase.as_deref())
+            .ok_or_else(|| {
+                unauthorized("the proof was not created by a capabilityInvocation key")
+            })?;
+
+        let method = crate::did::Did::from_str(&controller_document.id)
+            .map_err(|e| unauthorized(&e.to_string()))?
+            .method()
+            .to_string();
+        let verifier =
+            signature::suite::ed25519_2020::Ed25519DidVerifier::from_public_key_multibase(
+                method, public_key,
+            )
+            .map_err(|e| unauthorized(&e.to_string()))?;
+        let unsecured_doc =
+            serde_json::to_value(&self.document).map_err(|e| unauthorized(&e.to_string()))?;
+
+        crate::proof::verify_ed25519_signature_2020_proof(&verifier, unsecured_doc, proof)
+            .map_err(|e| unauthorized(&e.to_string()))
+    }
+}
+
+impl TryFrom<serde_json::Value> for DidDocument {
+    type Error = crate::error::Error;
+
+    fn try_from(value: serde_json::Value) -> Result<Self, Self::Error> {
+        Ok(DidDocument::from_json_value(value)?)
+    }
+}
+
+impl std::str::FromStr for DidDocument {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(DidDocument::from_json_str(s)?)
+    }
 }
 
 #[cfg(test)]
@@ -124,6 +645,7 @@ mod tests {
     use crate::{MockDIDResolver, ResolveResponse};
     use assert_json_diff::assert_json_eq;
     use serde_json::json;
+    use std::str::FromStr;
 
     const TEST_DID_METHOD: &str = "knox";
 
@@ -143,6 +665,7 @@ mod tests {
                 updated: chrono::DateTime::parse_from_rfc3339("2021-04-28T20:00:00.000Z")
                     .unwrap()
                     .into(),
+                ..Default::default()
             },
             did_resolution_metadata: crate::ResolutionMetadata {
                 content_type: None,
@@ -244,8 +767,117 @@ mod tests {
         Ok(())
     }
 
+    fn get_did_core_document() -> serde_json::Value {
+        json!({
+            "@context": [
+                "https://www.w3.org/ns/did/v1",
+                "https://w3id.org/security/suites/jws-2020/v1",
+                {"@vocab": "https://example.com/vocab#"}
+            ],
+            "id": "did:example:123456789abcdefghi",
+            "controller": "did:example:bcehfew7h32f32h7af3",
+            "alsoKnownAs": ["https://example.com/alice"],
+            "verificationMethod": [{
+                "id": "did:example:123456789abcdefghi#key-1",
+                "type": "JsonWebKey2020",
+                "controller": "did:example:123456789abcdefghi",
+                "publicKeyJwk": {
+                    "crv": "Ed25519",
+                    "x": "VCpo2LMLhn6iWku8MKvSLg2ZAoC-nlOyPVQaO3FxVeQ",
+                    "kty": "OKP",
+                    "kid": "_Qq0UL2Fq651Q0Fjd6TvnYE-faHiOpRlPVQcY_-tA4A"
+                }
+            }, {
+                "id": "#key-2",
+                "type": "EcdsaSecp256k1RecoveryMethod2020",
+                "controller": "did:example:123456789abcdefghi",
+                "blockchainAccountId": "eip155:1:0x89a932207c485f85226d86f7cd486a89a24fcc12"
+            }],
+            "authentication": [
+                "did:example:123456789abcdefghi#key-1",
+                {
+                    "id": "did:example:123456789abcdefghi#key-3",
+                    "type": "Ed25519VerificationKey2020",
+                    "controller": "did:example:123456789abcdefghi",
+                    "publicKeyMultibase": "z6MkmM42vxfqZQsv4ehtTjFFxQ4sQKS2w6WR7emozFAn5cxu"
+                }
+            ],
+            "assertionMethod": ["#key-1"],
+            "keyAgreement": [{
+                "id": "#key-agreement-1",
+                "type": "X25519KeyAgreementKey2020",
+                "controller": "did:example:123456789abcdefghi",
+                "publicKeyMultibase": "z6LSbysY2xFMRpGMhb7tFTLMpeuPRaqaWM1yECx2AtzE3KCc"
+            }],
+            "capabilityInvocation": ["did:example:123456789abcdefghi#key-1"],
+            "capabilityDelegation": ["did:example:123456789abcdefghi#key-1"],
+            "service": [{
+                "id": "did:example:123456789abcdefghi#linked-domain",
+                "type": "LinkedDomains",
+                "serviceEndpoint": "https://bar.example.com"
+            }],
+            "created": "2021-04-28T20:00:00Z"
+        })
+    }
+
+    #[test]
+    fn test_did_document_round_trip() {
+        let expect = get_did_core_document();
+        let did_doc = DidDocument::from_str(&expect.to_string()).unwrap();
+
+        assert_eq!(
+            did_doc.controller,
+            Some(Controller::Single(
+                "did:example:bcehfew7h32f32h7af3".to_string()
+            ))
+        );
+        assert_eq!(did_doc.verification_method.len(), 2);
+        assert_eq!(
+            did_doc.authentication[0],
+            VerificationMethodEntry::Reference("did:example:123456789abcdefghi#key-1".to_string())
+        );
+        assert!(matches!(
+            did_doc.authentication[1],
+            VerificationMethodEntry::Embedded(_)
+        ));
+        assert!(did_doc.service[0].service_type.contains("LinkedDomains"));
+        assert_eq!(
+            did_doc.property_set.get("created"),
+            Some(&json!("2021-04-28T20:00:00Z"))
+        );
+
+        assert_json_eq!(expect, serde_json::to_value(did_doc).unwrap());
+    }
+
+    #[rstest::rstest]
+    #[case::invalid_id("id", json!("example:123456789abcdefghi"))]
+    #[case::invalid_controller("controller", json!(["did:example:bcehfew7h32f32h7af3", "not-a-did"]))]
+    #[case::invalid_reference("assertionMethod", json!(["did:example:123456789abcdefghi#key 1"]))]
+    #[case::empty_fragment("capabilityInvocation", json!(["did:example:123456789abcdefghi#"]))]
+    #[case::duplicate_service_id("service", json!([{
+        "id": "#key-1",
+        "type": "LinkedDomains",
+        "serviceEndpoint": "https://bar.example.com"
+    }]))]
+    #[case::conflicting_method_id("keyAgreement", json!([{
+        "id": "#key-2",
+        "type": "X25519KeyAgreementKey2020",
+        "controller": "did:example:123456789abcdefghi",
+        "publicKeyMultibase": "z6LSbysY2xFMRpGMhb7tFTLMpeuPRaqaWM1yECx2AtzE3KCc"
+    }]))]
+    fn test_did_document_validation(#[case] property: &str, #[case] value: serde_json::Value) {
+        let mut did_doc = get_did_core_document();
+        did_doc[property] = value;
+
+        assert!(DidDocument::from_str(&did_doc.to_string()).is_err());
+    }
+
     #[rstest::rstest]
     #[case::restored_successfully(get_did(), get_restore_response(), true)]
+    #[case::invalid_document(get_did(), get_restore_response().map(|rsp| ResolveResponse {
+        did_document: json!({"id": "not-a-did"}),
+        ..rsp
+    }), false)]
     fn test_restore_identity(
         #[case] _did: String,
         #[case] restore_response: Result<ResolveResponse, crate::error::ResolverError>,
@@ -284,6 +916,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_resolve_typed_document() {
+        let mut did_doc = get_did_core_document();
+        did_doc["extensionProperty"] = json!({"foo": "bar"});
+        let raw_did_doc = did_doc.clone();
+
+        let mut resolver_mock = MockDIDResolver::default();
+        resolver_mock
+            .expect_resolve()
+            .with(mockall::predicate::eq(
+                "did:example:123456789abcdefghi".to_string(),
+            ))
+            .return_once(|_| {
+                get_restore_response().map(|rsp| ResolveResponse {
+                    did_document: did_doc,
+                    ..rsp
+                })
+            });
+
+        let rsp = aw!(resolve(&resolver_mock, "did:example:123456789abcdefghi")).unwrap();
+        assert_eq!(rsp.did_document.id, "did:example:123456789abcdefghi");
+        assert_eq!(
+            rsp.did_document.property_set.get("extensionProperty"),
+            Some(&json!({"foo": "bar"}))
+        );
+        assert_json_eq!(rsp.raw_did_document, raw_did_doc);
+
+        let mut resolver_mock = MockDIDResolver::default();
+        resolver_mock.expect_resolve().return_once(|_| {
+            get_restore_response().map(|rsp| ResolveResponse {
+                did_document: json!({"id": "not-a-did"}),
+                ..rsp
+            })
+        });
+
+        assert!(matches!(
+            aw!(resolve(&resolver_mock, "did:example:123456789abcdefghi")),
+            Err(crate::error::ResolverError::InvalidData(_))
+        ));
+    }
+
     #[test]
     fn test_create_identity() {
         let kp =
@@ -291,7 +964,111 @@ mod tests {
                 .unwrap();
         let verifier = signature::suite::ed25519_2020::Ed25519DidVerifier::from(kp);
 
-        let _did_doc = aw!(create_identity(verifier)).unwrap();
+        let _did_doc = aw!(create_identity(verifier, vec![])).unwrap();
+    }
+
+    fn get_services() -> Vec<Service> {
+        vec![
+            Service::new(
+                "#linked-domain",
+                LINKED_DOMAINS_SERVICE_TYPE,
+                ServiceEndpoint::Uri("https://knoxnetworks.io".to_string()),
+            ),
+            Service::new(
+                "#didcomm",
+                DIDCOMM_MESSAGING_SERVICE_TYPE,
+                ServiceEndpoint::Map(std::collections::HashMap::from([
+                    ("uri".to_string(), json!("https://didcomm.knoxnetworks.io")),
+                    ("accept".to_string(), json!(["didcomm/v2"])),
+                ])),
+            ),
+            Service::new(
+                "#credential-issuance",
+                "CredentialIssuanceService",
+                ServiceEndpoint::Set(vec![
+                    ServiceEndpointValue::Uri("https://issuer.knoxnetworks.io".to_string()),
+                    ServiceEndpointValue::Map(std::collections::HashMap::from([(
+                        "uri".to_string(),
+                        json!("https://issuer-backup.knoxnetworks.io"),
+                    )])),
+                ]),
+            ),
+        ]
+    }
+
+    #[rstest::rstest]
+    #[case::with_services(get_services(), true)]
+    #[case::invalid_endpoint(
+        vec![Service::new("#linked-domain", LINKED_DOMAINS_SERVICE_TYPE, ServiceEndpoint::Uri("not a uri".to_string()))],
+        false
+    )]
+    #[case::duplicate_ids(
+        vec![get_services()[0].clone(), get_services()[0].clone()],
+        false
+    )]
+    fn test_create_identity_with_services(#[case] services: Vec<Service>, #[case] expect_ok: bool) {
+        let kp =
+            signature::suite::ed25519_2020::Ed25519KeyPair::new(TEST_DID_METHOD.to_string(), None)
+                .unwrap();
+        let verifier = signature::suite::ed25519_2020::Ed25519DidVerifier::from(kp);
+
+        let res = aw!(create_identity(verifier, services.clone()));
+        assert_eq!(res.is_ok(), expect_ok);
+        if let Ok(did_doc) = res {
+            assert_eq!(did_doc.service, services);
+        }
+    }
+
+    #[test]
+    fn test_get_service_endpoint_by_type() {
+        let kp =
+            signature::suite::ed25519_2020::Ed25519KeyPair::new(TEST_DID_METHOD.to_string(), None)
+                .unwrap();
+        let verifier = signature::suite::ed25519_2020::Ed25519DidVerifier::from(kp);
+        let did = signature::suite::DIDVerifier::get_did(&verifier);
+        let did_doc = aw!(create_identity(verifier, get_services())).unwrap();
+        let encoded_did_doc = serde_json::to_value(did_doc).unwrap();
+
+        let mut resolver_mock = MockDIDResolver::default();
+        resolver_mock.expect_resolve().return_once(move |_| {
+            let mut rsp = get_restore_response().unwrap();
+            rsp.did_document = encoded_did_doc;
+            Ok(rsp)
+        });
+
+        let rsp = aw!(crate::DIDResolver::resolve(&resolver_mock, did.clone())).unwrap();
+        let did_doc = DidDocument::try_from(rsp.did_document).unwrap();
+
+        assert_eq!(
+            did_doc
+                .get_service_endpoint_by_type(LINKED_DOMAINS_SERVICE_TYPE)
+                .unwrap()
+                .uris(),
+            vec!["https://knoxnetworks.io"]
+        );
+        assert_eq!(
+            did_doc
+                .get_service_endpoint_by_type(DIDCOMM_MESSAGING_SERVICE_TYPE)
+                .unwrap()
+                .uris(),
+            vec!["https://didcomm.knoxnetworks.io"]
+        );
+        assert_eq!(
+            did_doc
+                .get_service_endpoint_by_type("CredentialIssuanceService")
+                .unwrap()
+                .uris(),
+            vec![
+                "https://issuer.knoxnetworks.io",
+                "https://issuer-backup.knoxnetworks.io"
+            ]
+        );
+        assert!(did_doc.get_service_endpoint_by_type("Unknown").is_none());
+        assert_eq!(
+            did_doc.get_service(&format!("{did}#didcomm")),
+            did_doc.get_service("#didcomm")
+        );
+        assert!(did_doc.get_service("#didcomm").is_some());
     }
 
     #[rstest::rstest]
@@ -321,10 +1098,161 @@ mod tests {
             )
             .return_once(|_, _| mock_create_response);
 
-        let did_doc = aw!(create_identity(verifier)).unwrap();
+        let did_doc = aw!(create_identity(verifier, vec![])).unwrap();
 
         let res = aw!(register_identity(&resolver_mock, did_doc));
 
         assert_eq!(res.is_ok(), expect_ok);
+        if let Err(e) = res {
+            assert!(matches!(
+                e,
+                crate::error::Error::Resolver(crate::error::ResolverError::Unknown(_))
+            ));
+        }
+    }
+
+    fn create_key_pair() -> signature::suite::ed25519_2020::Ed25519KeyPair {
+        signature::suite::ed25519_2020::Ed25519KeyPair::new(TEST_DID_METHOD.to_string(), None)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_verify_capability_invocation() {
+        let kp = create_key_pair();
+        let signer = signature::suite::ed25519_2020::Ed25519DidSigner::from(kp.clone());
+        let current_doc = aw!(create_identity(
+            signature::suite::ed25519_2020::Ed25519DidVerifier::from(kp),
+            vec![]
+        ))
+        .unwrap();
+        let updated_doc = DidDocument {
+            service: get_services(),
+            ..current_doc.clone()
+        };
+
+        let signed_doc = updated_doc
+            .clone()
+            .try_into_signed_document(&signer)
+            .unwrap();
+        assert!(signed_doc
+            .verify_capability_invocation(&current_doc)
+            .is_ok());
+
+        // The signed document round trips through its JSON representation
+        let signed_doc: SignedDidDocument =
+            serde_json::from_value(serde_json::to_value(&signed_doc).unwrap()).unwrap();
+        assert_eq!(signed_doc.document, updated_doc);
+        assert!(signed_doc
+            .verify_capability_invocation(&current_doc)
+            .is_ok());
+
+        // Signed by a key that is not a capabilityInvocation key of the current document
+        let other_signer =
+            signature::suite::ed25519_2020::Ed25519DidSigner::from(create_key_pair());
+        let signed_doc = updated_doc
+            .clone()
+            .try_into_signed_document(&other_signer)
+            .unwrap();
+        assert!(matches!(
+            signed_doc.verify_capability_invocation(&current_doc),
+            Err(crate::error::ResolverError::Unauthorized(_))
+        ));
+
+        // Tampered after signing
+        let mut signed_doc = updated_doc.try_into_signed_document(&signer).unwrap();
+        signed_doc.document.service.clear();
+        assert!(matches!(
+            signed_doc.verify_capability_invocation(&current_doc),
+            Err(crate::error::ResolverError::Unauthorized(_))
+        ));
+    }
+
+    #[test]
+    fn test_update_identity() {
+        let kp = create_key_pair();
+        let signer = signature::suite::ed25519_2020::Ed25519DidSigner::from(kp.clone());
+        let did_doc = aw!(create_identity(
+            signature::suite::ed25519_2020::Ed25519DidVerifier::from(kp),
+            get_services()
+        ))
+        .unwrap();
+        let expected_doc = did_doc.clone();
+
+        let mut resolver_mock = MockDIDResolver::default();
+        resolver_mock
+            .expect_update()
+            .with(
+                mockall::predicate::eq(did_doc.id.clone()),
+                mockall::predicate::function(move |signed_doc: &serde_json::Value| {
+                    serde_json::from_value::<SignedDidDocument>(signed_doc.clone())
+                        .is_ok_and(|signed_doc| signed_doc.document == expected_doc)
+                }),
+            )
+            .return_once(|_, _| Ok(()));
+
+        let res = aw!(update_identity(&resolver_mock, did_doc.clone(), &signer));
+        assert_eq!(res.unwrap(), did_doc);
+    }
+
+    #[test]
+    fn test_deactivate_identity() {
+        let kp = create_key_pair();
+        let signer = signature::suite::ed25519_2020::Ed25519DidSigner::from(kp.clone());
+        let did_doc = aw!(create_identity(
+            signature::suite::ed25519_2020::Ed25519DidVerifier::from(kp),
+            vec![]
+        ))
+        .unwrap();
+        let expected_doc = did_doc.clone();
+
+        let mut resolver_mock = MockDIDResolver::default();
+        resolver_mock
+            .expect_deactivate()
+            .with(
+                mockall::predicate::eq(did_doc.id.clone()),
+                mockall::predicate::function(move |signed_doc: &serde_json::Value| {
+                    serde_json::from_value::<SignedDidDocument>(signed_doc.clone()).is_ok_and(
+                        |signed_doc| {
+                            signed_doc
+                                .verify_capability_invocation(&expected_doc)
+                                .is_ok()
+                        },
+                    )
+                }),
+            )
+            .return_once(|_, _| Ok(()));
+
+        let res = aw!(deactivate_identity(&resolver_mock, did_doc, &signer));
+        assert!(res.is_ok());
+    }
+
+    // The resolver errors are kept so that callers can tell authorization failures from transient ones
+    #[rstest::rstest]
+    #[case::unauthorized(|| crate::error::ResolverError::Unauthorized("wrong key".to_string()))]
+    #[case::deactivated(|| crate::error::ResolverError::Deactivated("deactivated".to_string()))]
+    #[case::timeout(|| crate::error::ResolverError::Timeout("registry".to_string()))]
+    fn test_update_and_deactivate_identity_errors(
+        #[case] error: fn() -> crate::error::ResolverError,
+    ) {
+        let kp = create_key_pair();
+        let signer = signature::suite::ed25519_2020::Ed25519DidSigner::from(kp.clone());
+        let did_doc = aw!(create_identity(
+            signature::suite::ed25519_2020::Ed25519DidVerifier::from(kp),
+            vec![]
+        ))
+        .unwrap();
+
+        let mut resolver_mock = MockDIDResolver::default();
+        resolver_mock
+            .expect_update()
+            .return_once(move |_, _| Err(error()));
+        resolver_mock
+            .expect_deactivate()
+            .return_once(move |_, _| Err(error()));
+
+        let res = aw!(update_identity(&resolver_mock, did_doc.clone(), &signer));
+        assert!(matches!(res, Err(crate::error::Error::Resolver(e)) if e == error()));
+        let res = aw!(deactivate_identity(&resolver_mock, did_doc, &signer));
+        assert!(matches!(res, Err(crate::error::Error::Resolver(e)) if e == error()));
     }
 }
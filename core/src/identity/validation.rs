@@ -0,0 +1,300 @@
+use std::str::FromStr;
+
+use super::{Controller, DidContext, KeyMaterial, Service, VerificationMethodEntry};
+use crate::did::{Did, DidUrl};
+use signature::did::is_relative_did_url;
+
+// The first context of a JSON-LD DID Document must be https://www.w3.org/ns/did/v1 (use DID_CONTEXT)
+// Plain JSON representations are allowed to omit the context entirely
+pub fn did_context_validation(val: &DidContext) -> Result<(), serde_valid::validation::Error> {
+    match val.first() {
+        None => Ok(()),
+        Some(crate::credential::ContextValue::String(ref s)) if s != super::DID_CONTEXT => {
+            Err(serde_valid::validation::Error::Custom(format!(
+                "The first URI must be {}, instead found {}",
+                super::DID_CONTEXT,
+                s
+            )))
+        }
+        Some(crate::credential::ContextValue::Object(_)) => Err(
+            serde_valid::validation::Error::Custom("The first URI must be a string".to_string()),
+        ),
+        _ => Ok(()),
+    }
+}
+
+pub fn did_validation(val: &str) -> Result<(), serde_valid::validation::Error> {
+    if Did::from_str(val).is_ok() {
+        Ok(())
+    } else {
+        Err(serde_valid::validation::Error::Custom(format!(
+            "{val} is not a valid DID"
+        )))
+    }
+}
+
+pub fn controller_validation(
+    val: &Option<Controller>,
+) -> Result<(), serde_valid::validation::Error> {
+    match val {
+        None => Ok(()),
+        Some(controller) => controller.as_vec().into_iter().try_for_each(did_validation),
+    }
+}
+
+pub fn also_known_as_validation(val: &[String]) -> Result<(), serde_valid::validation::Error> {
+    match val.iter().find(|uri| !is_uri(uri)) {
+        Some(uri) => Err(serde_valid::validation::Error::Custom(format!(
+            "alsoKnownAs entry {uri} is not a valid URI"
+        ))),
+        None => Ok(()),
+    }
+}
+
+pub fn verification_method_validation(
+    val: &[KeyMaterial],
+) -> Result<(), serde_valid::validation::Error> {
+    val.iter().try_for_each(key_material_validation)
+}
+
+pub fn verification_relationship_validation(
+    val: &[VerificationMethodEntry],
+) -> Result<(), serde_valid::validation::Error> {
+    val.iter().try_for_each(|entry| match entry {
+        VerificationMethodEntry::Reference(reference) => did_url_validation(reference),
+        VerificationMethodEntry::Embedded(method) => key_material_validation(method),
+    })
+}
+
+pub fn service_validation(val: &[Service]) -> Result<(), serde_valid::validation::Error> {
+    val.iter().try_for_each(|service| {
+        if !is_uri(&service.id) && !is_relative_did_url(&service.id) {
+            return Err(serde_valid::validation::Error::Custom(format!(
+                "Service id {} is not a valid URI",
+                service.id
+            )));
+        }
+
+        let is_type_empty = match &service.service_type {
+            super::ServiceType::Single(t) => t.is_empty(),
+            super::ServiceType::Set(types) => {
+                types.is_empty() || types.iter().any(String::is_empty)
+            }
+        };
+        if is_type_empty {
+            return Err(serde_valid::validation::Error::Custom(format!(
+                "Service {} must have a type",
+                service.id
+            )));
+        }
+
+        let endpoint_uris = match &service.service_endpoint {
+            super::ServiceEndpoint::Uri(uri) => vec![uri.as_str()],
+            super::ServiceEndpoint::Map(_) => vec![],
+            super::ServiceEndpoint::Set(set) => set
+                .iter()
+                .filter_map(|value| match value {
+                    super::ServiceEndpointValue::Uri(uri) => Some(uri.as_str()),
+                    super::ServiceEndpointValue::Map(_) => None,
+                })
+                .collect(),
+        };
+        if let Some(uri) = endpoint_uris.into_iter().find(|uri| !is_uri(uri)) {
+            return Err(serde_valid::validation::Error::Custom(format!(
+                "Service {} endpoint {uri} is not a valid URI",
+                service.id
+            )));
+        }
+
+        Ok(())
+    })
+}
+
+// Every id must identify exactly one verification method or service.
+// The same verification method may be embedded in several relationships as long as every copy is identical.
+#[allow(clippy::too_many_arguments)]
+pub fn unique_id_validation(
+    did: &str,
+    verification_method: &[KeyMaterial],
+    authentication: &[VerificationMethodEntry],
+    assertion_method: &[VerificationMethodEntry],
+    key_agreement: &[VerificationMethodEntry],
+    capability_invocation: &[VerificationMethodEntry],
+    capability_delegation: &[VerificationMethodEntry],
+    service: &[Service],
+) -> Result<(), serde_valid::validation::Error> {
+    let mut methods: std::collections::HashMap<String, &KeyMaterial> =
+        std::collections::HashMap::new();
+    let mut services: std::collections::HashSet<String> = std::collections::HashSet::new();
+
+    let embedded = [
+        authentication,
+        assertion_method,
+        key_agreement,
+        capability_invocation,
+        capability_delegation,
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(|entry| match entry {
+        VerificationMethodEntry::Embedded(method) => Some(method),
+        VerificationMethodEntry::Reference(_) => None,
+    });
+
+    for method in verification_method.iter().chain(embedded) {
+        let id = absolute_id(did, &method.id);
+        match methods.get(&id) {
+            Some(existing) if *existing != method => {
+                return Err(serde_valid::validation::Error::Custom(format!(
+                    "Duplicate verification method id {id}"
+                )));
+            }
+            _ => {
+                methods.insert(id, method);
+            }
+        }
+    }
+
+    for entry in service {
+        let id = absolute_id(did, &entry.id);
+        if methods.contains_key(&id) || !services.insert(id.clone()) {
+            return Err(serde_valid::validation::Error::Custom(format!(
+                "Duplicate service id {id}"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn key_material_validation(val: &KeyMaterial) -> Result<(), serde_valid::validation::Error> {
+    if DidUrl::from_str(&val.id).is_err() && !is_relative_did_url(&val.id) {
+        return Err(serde_valid::validation::Error::Custom(format!(
+            "Verification method id {} is not a valid DID URL",
+            val.id
+        )));
+    }
+    if val.proof_type.is_empty() {
+        return Err(serde_valid::validation::Error::Custom(format!(
+            "Verification method {} must have a type",
+            val.id
+        )));
+    }
+    did_validation(&val.controller)
+}
+
+fn did_url_validation(val: &str) -> Result<(), serde_valid::validation::Error> {
+    if DidUrl::from_str(val).is_ok() || is_relative_did_url(val) {
+        Ok(())
+    } else {
+        Err(serde_valid::validation::Error::Custom(format!(
+            "{val} is not a valid DID URL"
+        )))
+    }
+}
+
+fn absolute_id(did: &str, id: &str) -> String {
+    match id.strip_prefix('#') {
+        Some(fragment) => format!("{did}#{fragment}"),
+        None => id.to_string(),
+    }
+}
+
+// scheme ":" hier-part, only the scheme is strictly checked
+pub(crate) fn is_uri(val: &str) -> bool {
+    match val.split_once(':') {
+        Some((scheme, rest)) => {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+                && !rest.is_empty()
+                && !rest.chars().any(|c| c.is_whitespace())
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{DidContext, KeyMaterial, VerificationMethodEntry, DID_CONTEXT};
+    use crate::credential::ContextValue;
+
+    fn create_key_material(id: &str) -> KeyMaterial {
+        KeyMaterial {
+            id: id.to_string(),
+            proof_type: "Ed25519VerificationKey2020".to_string(),
+            controller: "did:example:123".to_string(),
+            public_key_multibase: Some(
+                "z6MkmgYPyjwqrMyHYBFfEcetAAoW7A9njsC4ToZ1WnjAgRL1".to_string(),
+            ),
+            public_key_jwk: None,
+            property_set: std::collections::HashMap::new(),
+        }
+    }
+
+    #[rstest::rstest]
+    #[case::empty_context(DidContext::Set(vec![]), Ok(()))]
+    #[case::single_context(
+        DidContext::Single(ContextValue::String(DID_CONTEXT.to_string())),
+        Ok(())
+    )]
+    #[case::first_uri_not_did_context(
+        DidContext::Set(vec![ContextValue::String("https://www.w3.org/2018/credentials/v1".to_string())]),
+        Err(serde_valid::validation::Error::Custom(
+            "The first URI must be https://www.w3.org/ns/did/v1, instead found https://www.w3.org/2018/credentials/v1".to_string()
+        ))
+    )]
+    #[case::first_uri_not_string(
+        DidContext::Set(vec![ContextValue::Object(std::collections::HashMap::new())]),
+        Err(serde_valid::validation::Error::Custom(
+            "The first URI must be a string".to_string()
+        ))
+    )]
+    fn test_validate_did_context(
+        #[case] context: DidContext,
+        #[case] expected: Result<(), serde_valid::validation::Error>,
+    ) {
+        match super::did_context_validation(&context) {
+            Ok(_) => assert!(expected.is_ok()),
+            Err(e) => assert_eq!(e.to_string(), expected.unwrap_err().to_string()),
+        }
+    }
+
+    #[rstest::rstest]
+    #[case::identical_embedded_methods(
+        vec![VerificationMethodEntry::Embedded(create_key_material("did:example:123#key-1"))],
+        vec![VerificationMethodEntry::Embedded(create_key_material("did:example:123#key-1"))],
+        true
+    )]
+    #[case::relative_and_absolute_reference(
+        vec![VerificationMethodEntry::Reference("#key-1".to_string())],
+        vec![VerificationMethodEntry::Reference("did:example:123#key-1".to_string())],
+        true
+    )]
+    #[case::conflicting_embedded_methods(
+        vec![VerificationMethodEntry::Embedded(create_key_material("did:example:123#key-1"))],
+        vec![VerificationMethodEntry::Embedded(KeyMaterial {
+            public_key_multibase: Some("z6MkfFmsob7fC3MmqU1JVfdBnMbnAw7xm1mrEtPvAoojLcRh".to_string()),
+            ..create_key_material("#key-1")
+        })],
+        false
+    )]
+    fn test_unique_id_validation(
+        #[case] authentication: Vec<VerificationMethodEntry>,
+        #[case] assertion_method: Vec<VerificationMethodEntry>,
+        #[case] expect_ok: bool,
+    ) {
+        let res = super::unique_id_validation(
+            "did:example:123",
+            &[],
+            &authentication,
+            &assertion_method,
+            &[],
+            &[],
+            &[],
+            &[],
+        );
+        assert_eq!(res.is_ok(), expect_ok);
+    }
+}
@@ -1,9 +1,12 @@
+pub mod clock;
 pub mod credential;
+pub mod did;
 pub mod error;
 pub mod identity;
 pub mod proof;
+pub mod resolver;
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct DidResolutionURL {
     // W3C Decentralized Identifier (DID) of the wallet
     pub did: String,
@@ -15,26 +18,128 @@ pub struct DidResolutionURL {
     pub method_specific_id: String,
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub const DID_JSON_CONTENT_TYPE: &str = "application/did+json";
+pub const DID_LD_JSON_CONTENT_TYPE: &str = "application/did+ld+json";
+
+// Error codes follow https://w3c-ccg.github.io/did-resolution/#errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ResolutionErrorCode {
+    InvalidDid,
+    NotFound,
+    RepresentationNotSupported,
+    MethodNotSupported,
+    Deactivated,
+    #[serde(other)]
+    InternalError,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct ResolutionMetadata {
     #[serde(rename = "contentType")]
     pub content_type: Option<String>,
     pub duration: Option<i64>,
     #[serde(rename = "didUrl")]
     pub did_url: Option<DidResolutionURL>,
-    pub error: Option<String>,
+    pub error: Option<ResolutionErrorCode>,
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct DidDocumentMetadata {
     // Timestamp representing the DID document creation time.
     pub created: chrono::DateTime<chrono::Utc>,
     // Timestamp representing the DID document last update time.
     pub updated: chrono::DateTime<chrono::Utc>,
+    // Set to true once the DID has been deactivated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deactivated: Option<bool>,
+    // Version of the resolved DID document.
+    #[serde(rename = "versionId", skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<String>,
+    // Timestamp of the next update when a previous version of the DID document was resolved.
+    #[serde(rename = "nextUpdate", skip_serializing_if = "Option::is_none")]
+    pub next_update: Option<chrono::DateTime<chrono::Utc>>,
+    // DIDs that are logically equivalent to the resolved DID.
+    #[serde(
+        rename = "equivalentId",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub equivalent_id: Vec<String>,
+}
+
+// Resolution options follow https://w3c-ccg.github.io/did-resolution/#did-resolution-options
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct ResolutionOptions {
+    // Media type of the requested representation, see DIDResolver::resolve_representation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accept: Option<String>,
+    // Resolve a specific version of the DID document.
+    #[serde(rename = "versionId", skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<String>,
+    // Resolve the version of the DID document that was valid at the given time.
+    #[serde(rename = "versionTime", skip_serializing_if = "Option::is_none")]
+    pub version_time: Option<chrono::DateTime<chrono::Utc>>,
+    // Skip any cached result and resolve the DID again, see resolver::cache::CachingResolver.
+    #[serde(
+        rename = "noCache",
+        default,
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub no_cache: bool,
+}
+
+impl ResolutionOptions {
+    // Fails with `RepresentationNotSupported` when `accept` is not a DID document media type.
+    pub fn check_accept(&self) -> Result<(), error::ResolverError> {
+        match self.accept.as_deref() {
+            None | Some(DID_JSON_CONTENT_TYPE) | Some(DID_LD_JSON_CONTENT_TYPE) => Ok(()),
+            Some(accept) => Err(error::ResolverError::RepresentationNotSupported(format!(
+                "{accept} is not a supported representation"
+            ))),
+        }
+    }
+
+    pub fn is_versioned(&self) -> bool {
+        self.version_id.is_some() || self.version_time.is_some()
+    }
+}
+
+impl ResolutionErrorCode {
+    // Converts an error code reported in resolution metadata into the matching ResolverError.
+    pub fn into_resolver_error(self, message: String) -> error::ResolverError {
+        match self {
+            ResolutionErrorCode::InvalidDid => error::ResolverError::InvalidDid(message),
+            ResolutionErrorCode::NotFound => error::ResolverError::DocumentNotFound(message),
+            ResolutionErrorCode::RepresentationNotSupported => {
+                error::ResolverError::RepresentationNotSupported(message)
+            }
+            ResolutionErrorCode::MethodNotSupported => {
+                error::ResolverError::MethodNotSupported(message)
+            }
+            ResolutionErrorCode::Deactivated => error::ResolverError::Deactivated(message),
+            ResolutionErrorCode::InternalError => error::ResolverError::Unknown(message),
+        }
+    }
+}
+
+impl From<&error::ResolverError> for ResolutionErrorCode {
+    fn from(err: &error::ResolverError) -> Self {
+        match err {
+            error::ResolverError::InvalidDid(_) => ResolutionErrorCode::InvalidDid,
+            error::ResolverError::DocumentNotFound(_) => ResolutionErrorCode::NotFound,
+            error::ResolverError::RepresentationNotSupported(_) => {
+                ResolutionErrorCode::RepresentationNotSupported
+            }
+            error::ResolverError::MethodNotSupported(_) => ResolutionErrorCode::MethodNotSupported,
+            error::ResolverError::Deactivated(_) => ResolutionErrorCode::Deactivated,
+            _ => ResolutionErrorCode::InternalError,
+        }
+    }
 }
 
 // Response follows the structure defined in - https://www.w3.org/TR/did-core/#did-resolution
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ResolveResponse {
     #[serde(rename = "didDocument")]
     pub did_document: serde_json::Value,
@@ -44,6 +149,47 @@ pub struct ResolveResponse {
     pub did_resolution_metadata: ResolutionMetadata,
 }
 
+// Response of the DID resolution `resolveRepresentation` function - https://w3c-ccg.github.io/did-resolution/#resolving
+// The media type of `did_document_stream` is the `contentType` of the resolution metadata.
+#[derive(Debug, Clone)]
+pub struct ResolveRepresentationResponse {
+    pub did_document_stream: Vec<u8>,
+    pub did_document_metadata: DidDocumentMetadata,
+    pub did_resolution_metadata: ResolutionMetadata,
+}
+
+impl ResolveRepresentationResponse {
+    pub fn content_type(&self) -> Option<&str> {
+        self.did_resolution_metadata.content_type.as_deref()
+    }
+}
+
+// Same as ResolveResponse, with the DID Document parsed into identity::DidDocument.
+// The document as returned by the resolver is kept in `raw_did_document`.
+#[derive(Debug)]
+pub struct TypedResolveResponse {
+    pub did_document: identity::DidDocument,
+    pub raw_did_document: serde_json::Value,
+    pub did_document_metadata: DidDocumentMetadata,
+    pub did_resolution_metadata: ResolutionMetadata,
+}
+
+impl TryFrom<ResolveResponse> for TypedResolveResponse {
+    type Error = error::ResolverError;
+
+    fn try_from(rsp: ResolveResponse) -> Result<Self, Self::Error> {
+        let did_document = identity::DidDocument::try_from(rsp.did_document.clone())
+            .map_err(|e| error::ResolverError::InvalidData(e.to_string()))?;
+
+        Ok(TypedResolveResponse {
+            did_document,
+            raw_did_document: rsp.did_document,
+            did_document_metadata: rsp.did_document_metadata,
+            did_resolution_metadata: rsp.did_resolution_metadata,
+        })
+    }
+}
+
 /// Verification of Data Integrity Proofs requires the resolution of the `verificationMethod` specified in the proof.
 /// The `verificationMethod` refers to a cryptographic key stored in some external source.
 /// The DIDResolver is responsible for resolving the `verificationMethod` to a key that can be used to verify the proof.
@@ -54,9 +200,75 @@ pub trait DIDResolver: Send + Sync + 'static {
     /// Given a `did`, resolve the full DID document associated with that matching `did`.
     /// Return the JSON-LD document representing the DID.
     async fn resolve(&self, did: String) -> Result<ResolveResponse, error::ResolverError>;
+    /// Same as `resolve`, following the DID resolution `options` (e.g. `versionId` or `versionTime`).
+    /// Resolvers that do not keep a history of the DID documents only resolve the latest version.
+    async fn resolve_with_options(
+        &self,
+        did: String,
+        options: ResolutionOptions,
+    ) -> Result<ResolveResponse, error::ResolverError> {
+        options.check_accept()?;
+        if options.is_versioned() {
+            return Err(error::ResolverError::DocumentNotFound(format!(
+                "Versions of {did} can not be resolved"
+            )));
+        }
+
+        self.resolve(did).await
+    }
+    /// Same as `resolve_with_options`, returning the DID document serialized in the media type requested by
+    /// `options.accept` (`application/did+ld+json` by default) so that signatures can be verified over the exact bytes.
+    /// Resolvers without a native representation serialize the resolved document as JSON.
+    async fn resolve_representation(
+        &self,
+        did: String,
+        options: ResolutionOptions,
+    ) -> Result<ResolveRepresentationResponse, error::ResolverError> {
+        let content_type = options
+            .accept
+            .clone()
+            .unwrap_or_else(|| DID_LD_JSON_CONTENT_TYPE.to_string());
+        let rsp = self.resolve_with_options(did, options).await?;
+        let did_document_stream = serde_json::to_vec(&rsp.did_document)
+            .map_err(|e| error::ResolverError::InvalidData(e.to_string()))?;
+
+        Ok(ResolveRepresentationResponse {
+            did_document_stream,
+            did_document_metadata: rsp.did_document_metadata,
+            did_resolution_metadata: ResolutionMetadata {
+                content_type: Some(content_type),
+                ..rsp.did_resolution_metadata
+            },
+        })
+    }
     /// Given a `did` and the associated DID Document, register the DID Document with the external source used by the DIDResolver.
     async fn create(&self, did: String, doc: serde_json::Value)
         -> Result<(), error::ResolverError>;
+    /// Given a `did` and its new DID Document signed by a `capabilityInvocation` key of the current document
+    /// (see identity::SignedDidDocument), replace the DID Document registered with the external source.
+    /// Resolvers of DID methods whose documents can not be updated fail with `MethodNotSupported`.
+    async fn update(
+        &self,
+        did: String,
+        _signed_doc: serde_json::Value,
+    ) -> Result<(), error::ResolverError> {
+        Err(error::ResolverError::MethodNotSupported(format!(
+            "{did} can not be updated"
+        )))
+    }
+    /// Given a `did` and its current DID Document signed by a `capabilityInvocation` key (see
+    /// identity::SignedDidDocument) as a proof of ownership, deactivate the DID. Resolving a deactivated DID reports
+    /// `deactivated: true` in the document metadata.
+    /// Resolvers of DID methods whose documents can not be deactivated fail with `MethodNotSupported`.
+    async fn deactivate(
+        &self,
+        did: String,
+        _signed_doc: serde_json::Value,
+    ) -> Result<(), error::ResolverError> {
+        Err(error::ResolverError::MethodNotSupported(format!(
+            "{did} can not be deactivated"
+        )))
+    }
     // Returns the DID Method that the DID Resolver is compatible with. Each resolver can only be compatible with one.
     fn get_method(&self) -> &'static str
     where
@@ -90,51 +302,150 @@ impl Clone for MockDIDResolver {
 }
 
 pub trait DocumentBuilder {
-    fn get_contexts(cred_type: &credential::CredentialType) -> credential::DocumentContext {
-        match cred_type {
-            credential::CredentialType::BankAccount => {
-                vec![
-                    credential::ContextValue::String(
-                        credential::BASE_CREDENTIAL_CONTEXT.to_string(),
-                    ),
-                    credential::ContextValue::String(
-                        credential::BANK_ACCOUNT_CREDENTIAL_CONTEXT.to_string(),
-                    ),
-                ]
-            }
-            _ => {
-                vec![credential::ContextValue::String(
-                    credential::BASE_CREDENTIAL_CONTEXT.to_string(),
-                )]
-            }
-        }
+    /// The registry of the contexts required by each credential and presentation type.
+    /// Override it to issue credentials of types that need contexts other than the defaults.
+    fn context_registry(&self) -> credential::context_registry::ContextRegistry {
+        credential::context_registry::ContextRegistry::default()
     }
 
-    /// Given the credential type and the credential subject information, create a unissued JSON-LD credential.
+    /// The clock giving the issuance date of new credentials and the time documents are verified at, the system time
+    /// by default.
+    fn clock(&self) -> Box<dyn clock::Clock> {
+        Box::new(clock::SystemClock)
+    }
+
+    /// The difference tolerated between the clock of the builder and the clocks of issuers when verifying validity
+    /// periods, see `credential::validity`.
+    fn clock_skew(&self) -> chrono::Duration {
+        clock::default_clock_skew()
+    }
+
+    /// Whether `issue_credential` rejects credentials with terms that their contexts do not define, since those terms
+    /// drop out of the signed data. Override it to return false to opt out of the check.
+    fn strict_context_adherence(&self) -> bool {
+        true
+    }
+
+    /// The version of the VC Data Model of new credentials and presentations, V1 by default.
+    /// V1 credentials get an `issuanceDate`, V2 credentials a `validFrom` instead.
+    fn credential_version(&self) -> credential::CredentialVersion {
+        credential::CredentialVersion::V1
+    }
+
+    fn get_contexts(&self, cred_type: &credential::CredentialType) -> credential::DocumentContext {
+        self.context_registry().get_contexts(
+            self.credential_version(),
+            &[
+                credential::CredentialType::VerifiableCredential.as_str(),
+                cred_type.as_str(),
+            ],
+        )
+    }
+
+    /// Given the typed credential subject, create a unissued JSON-LD credential of the subject's credential type.
     /// In order to become a Verifiable Credential, a data integrity proof must be created for the credential and appended to the JSON-LD document.
     /// this is the default implementation of the `create` method. The `create` method can be overridden to create a custom credential.
     fn create_credential(
+        &self,
+        cred_subject: impl credential::subject::TypedCredentialSubject,
+        property_set: std::collections::HashMap<String, serde_json::Value>,
+        id: &str,
+        issuer: impl Into<credential::Issuer>,
+    ) -> Result<credential::Credential, error::Error> {
+        let mut credential = self.create_untyped_credential(
+            cred_subject.credential_type(),
+            cred_subject.to_subject()?,
+            property_set,
+            id,
+            issuer,
+        )?;
+        for subject_context in cred_subject.contexts() {
+            if !credential.context.contains(&subject_context) {
+                credential.context.push(subject_context);
+            }
+        }
+        Ok(credential)
+    }
+
+    /// Given the credential type and the credential subject information, create a unissued JSON-LD credential.
+    /// Use it for subjects without a `TypedCredentialSubject`, their terms must be defined by the contexts registered
+    /// for `cred_type`.
+    fn create_untyped_credential(
         &self,
         cred_type: credential::CredentialType,
         cred_subject: std::collections::HashMap<String, serde_json::Value>,
         property_set: std::collections::HashMap<String, serde_json::Value>,
         id: &str,
-        issuer: String,
+        issuer: impl Into<credential::Issuer>,
     ) -> Result<credential::Credential, error::Error> {
-        let context = Self::get_contexts(&cred_type);
-
+        let context = self.get_contexts(&cred_type);
+        let (issuance_date, valid_from) = match self.credential_version() {
+            credential::CredentialVersion::V1 => (Some(self.clock().now()), None),
+            credential::CredentialVersion::V2 => (None, Some(self.clock().now())),
+        };
         Ok(credential::Credential {
             context,
             id: Some(id.to_string()),
             cred_type: vec![credential::CredentialType::VerifiableCredential, cred_type],
-            issuance_date: Some(chrono::Utc::now()),
+            issuance_date,
             expiration_date: None,
-            issuer,
+            valid_from,
+            valid_until: None,
+            name: None,
+            description: None,
+            issuer: issuer.into(),
             subject: credential::CredentialSubject::Single(cred_subject),
+            credential_status: None,
+            credential_schema: None,
             property_set,
         })
     }
 
+    /// Create the credential as `create_credential` does, declaring `schema` in its `credentialSchema`.
+    /// The credential subject must conform to `json_schema`, the JSON Schema identified by `schema`.
+    fn create_credential_with_schema(
+        &self,
+        cred_subject: impl credential::subject::TypedCredentialSubject,
+        schema: credential::schema::SchemaEntry,
+        json_schema: &serde_json::Value,
+        property_set: std::collections::HashMap<String, serde_json::Value>,
+        id: &str,
+        issuer: impl Into<credential::Issuer>,
+    ) -> Result<credential::Credential, error::Error> {
+        let mut credential = self.create_credential(cred_subject, property_set, id, issuer)?;
+        credential.credential_schema = Some(schema.into());
+        credential::schema::validate_credential_subject(&credential, json_schema)?;
+        Ok(credential)
+    }
+
+    /// The contexts used in place of loading them over HTTP when issuing credentials, none by default.
+    fn preloaded_contexts(&self) -> credential::preloaded_contexts::PreloadedContexts {
+        credential::preloaded_contexts::PreloadedContexts::default()
+    }
+
+    /// How long the proofs of issued credentials are valid, see `proof::SigningOptions::expires`.
+    /// Proofs do not expire by default.
+    fn proof_lifetime(&self) -> Option<chrono::Duration> {
+        None
+    }
+
+    /// Sign the credential at the time of the builder's clock, creating a Verifiable Credential.
+    /// Unless opted out, see `strict_context_adherence`, the credential must only use terms defined by its contexts.
+    fn issue_credential<S: signature::suite::Signature>(
+        &self,
+        credential: credential::Credential,
+        signer: &impl signature::suite::DIDSigner<S>,
+        relation: signature::suite::VerificationRelation,
+    ) -> Result<credential::VerifiableCredential, error::Error> {
+        let clock = self.clock();
+        let options = proof::SigningOptions {
+            expires: self.proof_lifetime().map(|lifetime| clock.now() + lifetime),
+            strict_context_adherence: self.strict_context_adherence(),
+            preloaded_contexts: self.preloaded_contexts(),
+        };
+        credential.try_into_verifiable_credential_with_options(signer, relation, &*clock, &options)
+    }
+
     /// Given the set of credentials, create a unsigned JSON-LD Presentation of those credentials.
     /// In order to become a Verifiable Presentation, a data integrity proof must be created for the
     /// presentation and appended to the JSON-LD document.
@@ -142,12 +453,16 @@ pub trait DocumentBuilder {
         &self,
         credentials: Vec<credential::VerifiableCredential>,
     ) -> Result<credential::Presentation, error::Error> {
-        let context = Self::get_contexts(&credential::CredentialType::VerifiableCredential);
+        let context = self.context_registry().get_contexts(
+            self.credential_version(),
+            &[credential::PresentationType::VerifiablePresentation.as_str()],
+        );
         Ok(credential::Presentation {
             context,
             id: None,
             presentation_type: vec![credential::PresentationType::VerifiablePresentation],
-            verifiable_credential: Some(credentials),
+            holder: None,
+            verifiable_credential: Some(credentials.into_iter().map(Into::into).collect()),
         })
     }
 }
@@ -156,25 +471,129 @@ pub struct DefaultDocumentBuilder {}
 
 impl DocumentBuilder for DefaultDocumentBuilder {}
 
-/// Given a JSON-LD document and a DIDResolver, verify the data integrity proof for the document.
-/// This will by parsing the `verificationMethod` property of the data integrity proof and resolving it to a key that can be used to verify the proof.
-/// Currently only `Ed25519Signature2018` is supported for data integrity proof verification.
-pub fn verify_data_integrity_proof<S: signature::suite::Signature>(
-    _doc: serde_json::Value,
-    _resolver: &impl DIDResolver,
-    _verifier: &impl signature::suite::DIDVerifier<S>,
-) -> Result<bool, error::Error> {
-    unimplemented!();
+/// Given a JSON-LD document and a DIDResolver, verify the data integrity proofs of the document.
+/// The key of each proof is found by resolving the DID Document of `controller`, the DID expected to secure the
+/// document (e.g. the issuer of a credential), where the `verificationMethod` must be a key of the `relation` of the proof.
+/// Currently only `Ed25519Signature2020` is supported for data integrity proof verification.
+pub async fn verify_data_integrity_proof(
+    doc: serde_json::Value,
+    controller: &str,
+    relation: signature::suite::VerificationRelation,
+    resolver: &(impl DIDResolver + ?Sized),
+) -> Result<(), error::Error> {
+    let mut unsecured_doc = doc;
+    let proof = unsecured_doc
+        .as_object_mut()
+        .and_then(|doc| doc.remove("proof"))
+        .ok_or_else(|| error::Error::InvalidProof("The document has no proof".to_string()))?;
+    let proofs = match serde_json::from_value(proof)? {
+        proof::CredentialProof::Single(proof) => vec![proof],
+        proof::CredentialProof::Set(proofs) => proofs,
+    };
+    let did_method = controller.parse::<did::Did>()?.method().to_string();
+    let did_document = identity::resolve(resolver, controller).await?.did_document;
+
+    for proof in proofs {
+        let proof = match proof {
+            proof::ProofType::Ed25519Signature2020(proof) => proof,
+            proof::ProofType::RsaSignature2018(proof) => {
+                return Err(error::Error::InvalidProof(format!(
+                    "{} proofs are not supported",
+                    proof.proof_type
+                )))
+            }
+        };
+        if proof.proof_purpose != relation {
+            return Err(error::Error::InvalidProof(format!(
+                "The proof purpose must be {relation}, got {}",
+                proof.proof_purpose
+            )));
+        }
+        let public_key = did_document
+            .get_verification_method_by_relation(&proof.verification_method, relation)
+            .and_then(|method| method.public_key_multibase.as_deref())
+            .ok_or_else(|| {
+                error::Error::InvalidProof(format!(
+                    "{} is not a {relation} key of {controller}",
+                    proof.verification_method
+                ))
+            })?;
+        let verifier =
+            signature::suite::ed25519_2020::Ed25519DidVerifier::from_public_key_multibase(
+                did_method.clone(),
+                public_key,
+            )
+            .map_err(|e| error::Error::InvalidProof(e.to_string()))?;
+
+        proof::verify_ed25519_signature_2020_proof(&verifier, unsecured_doc.clone(), &proof)?;
+    }
+    Ok(())
 }
 
-/// Given a JSON-LD document and a DIDResolver, verify the data integrity proof for the Verifiable Presentation.
-/// Then each claimed Verifiable Credential must be verified for validity and ownership of the credential by the subject.
-pub fn verify_presentation<S: signature::suite::Signature>(
-    _doc: serde_json::Value,
-    _resolver: &impl DIDResolver,
-    _verifier: &impl signature::suite::DIDVerifier<S>,
-) -> Result<bool, error::Error> {
-    unimplemented!();
+/// Verify a Verifiable Credential: its data integrity proof must be created by an `assertionMethod` key of its issuer,
+/// it must be valid at the time of the builder's clock (see `credential::validity`), it must not be revoked or
+/// suspended by its credential status and its subject must conform to its credential schema.
+/// Status list credentials are resolved through `status_resolver` and schemas loaded with `schema_loader`.
+pub async fn verify_credential(
+    builder: &impl DocumentBuilder,
+    vc: &credential::VerifiableCredential,
+    resolver: &(impl DIDResolver + ?Sized),
+    status_resolver: &(impl credential::status::StatusListResolver + ?Sized),
+    schema_loader: &(impl credential::schema::SchemaLoader + ?Sized),
+) -> Result<(), error::Error> {
+    verify_data_integrity_proof(
+        serde_json::to_value(vc)?,
+        vc.credential.issuer.id(),
+        signature::suite::VerificationRelation::AssertionMethod,
+        resolver,
+    )
+    .await?;
+    credential::validity::verify_credential_validity(vc, &*builder.clock(), builder.clock_skew())?;
+    credential::status::verify_credential_status(&vc.credential, status_resolver, resolver).await?;
+    credential::schema::verify_credential_schema(&vc.credential, schema_loader, resolver).await
+}
+
+/// Verify a Verifiable Presentation: its data integrity proof must be created by an `authentication` key of its
+/// holder and it must be valid at the time of the builder's clock. Each embedded credential is then verified with
+/// `verify_credential`. Enveloped credentials are rejected, their envelopes can not be verified yet.
+pub async fn verify_presentation(
+    builder: &impl DocumentBuilder,
+    vp: &credential::VerifiablePresentation,
+    resolver: &(impl DIDResolver + ?Sized),
+    status_resolver: &(impl credential::status::StatusListResolver + ?Sized),
+    schema_loader: &(impl credential::schema::SchemaLoader + ?Sized),
+) -> Result<(), error::Error> {
+    let holder =
+        vp.presentation.holder.as_deref().ok_or_else(|| {
+            error::Error::InvalidProof("The presentation has no holder".to_string())
+        })?;
+    verify_data_integrity_proof(
+        serde_json::to_value(vp)?,
+        holder,
+        signature::suite::VerificationRelation::Authentication,
+        resolver,
+    )
+    .await?;
+    credential::validity::verify_presentation_validity(
+        vp,
+        &*builder.clock(),
+        builder.clock_skew(),
+    )?;
+
+    for credential in vp.presentation.verifiable_credential.iter().flatten() {
+        match credential {
+            credential::PresentationCredential::Embedded(vc) => {
+                verify_credential(builder, vc, resolver, status_resolver, schema_loader).await?
+            }
+            credential::PresentationCredential::Enveloped(vc) => {
+                return Err(error::Error::InvalidProof(format!(
+                    "The enveloped credential {} can not be verified",
+                    vc.media_type().unwrap_or("of unknown media type")
+                )))
+            }
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -182,23 +601,19 @@ mod tests {
 
     use super::*;
     use assert_json_diff::assert_json_eq;
-    use json_ld::{syntax::Parse, JsonLdProcessor};
+    use credential::subject::TypedCredentialSubject;
     use serde_json::json;
     use signature::suite::KeyPair;
-    use static_iref::iri;
-    use std::{collections::HashMap, vec};
+    use std::{collections::HashMap, str::FromStr, vec};
 
     use serde_json::Value;
 
     const TEST_DID_METHOD: &str = "knox";
 
-    macro_rules! aw {
-        ($e:expr) => {
-            tokio_test::block_on($e)
-        };
-    }
-
-    fn get_body_subject() -> (HashMap<String, Value>, HashMap<String, Value>) {
+    fn get_body_subject() -> (
+        HashMap<String, Value>,
+        credential::subject::PermanentResident,
+    ) {
         let mut kv_body: HashMap<String, Value> = HashMap::new();
 
         let type_rs = json!(["VerifiableCredential", "PermanentResidentCard"]);
@@ -271,8 +686,9 @@ mod tests {
         .collect();
 
         kv_subject.insert("type".to_string(), json!(["PermanentResident", "Person"]));
+        let subject = credential::subject::PermanentResident::from_subject(&kv_subject).unwrap();
 
-        (kv_body, kv_subject)
+        (kv_body, subject)
     }
 
     #[test]
@@ -284,6 +700,7 @@ mod tests {
         let expect_credential = json!({
             "@context": [
             "https://www.w3.org/2018/credentials/v1",
+            "https://w3id.org/citizenship/v1",
           ],
           "id": "https://issuer.oidp.uscis.gov/credentials/83627465",
           "type": ["VerifiableCredential", "PermanentResidentCard"],
@@ -311,7 +728,6 @@ mod tests {
         let (kv_body, kv_subject) = get_body_subject();
 
         let vc = builder.create_credential(
-            credential::CredentialType::PermanentResidentCard,
             kv_subject,
             kv_body,
             "https://issuer.oidp.uscis.gov/credentials/83627465",
@@ -335,7 +751,7 @@ mod tests {
         "type" : ["VerifiablePresentation"],
         "verifiableCredential":[
             {
-            "@context":["https://www.w3.org/2018/credentials/v1"],
+            "@context":["https://www.w3.org/2018/credentials/v1","https://w3id.org/citizenship/v1"],
             "id":"https://issuer.oidp.uscis.gov/credentials/83627465",
             "type":["VerifiableCredential","PermanentResidentCard"],
             "credentialSubject":{
@@ -375,7 +791,6 @@ mod tests {
         let (kv_body, kv_subject) = get_body_subject();
 
         let vc = builder.create_credential(
-            credential::CredentialType::PermanentResidentCard,
             kv_subject,
             kv_body,
             "https://issuer.oidp.uscis.gov/credentials/83627465",
@@ -388,6 +803,7 @@ mod tests {
             &signer,
             serde_json::to_value(credential.clone()).unwrap(),
             signature::suite::VerificationRelation::AssertionMethod,
+            &clock::SystemClock,
         );
 
         assert!(proof.is_ok());
@@ -398,7 +814,10 @@ mod tests {
             .create_presentation(credentials)
             .expect("unable to create presentation from credentials");
         let verifiable_credential = interim_presentation.verifiable_credential.clone().unwrap();
-        let interim_proof = &verifiable_credential[0].proof;
+        let interim_proof = match &verifiable_credential[0] {
+            credential::PresentationCredential::Embedded(vc) => &vc.proof,
+            vc => panic!("unexpected credential {vc:?}"),
+        };
         let interim_proof = serde_json::to_value(interim_proof).unwrap();
         expect_presentation["verifiableCredential"][0]["proof"] = interim_proof;
 
@@ -408,99 +827,679 @@ mod tests {
         Ok(())
     }
 
-    #[ignore = "Expand issue remains unresolved"]
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct DriverLicense {
+        id: String,
+        #[serde(rename = "licenseClass")]
+        license_class: String,
+    }
+
+    impl credential::subject::TypedCredentialSubject for DriverLicense {
+        fn credential_type(&self) -> credential::CredentialType {
+            credential::CredentialType::Other("DriverLicense".to_string())
+        }
+
+        fn subject_types(&self) -> Vec<&str> {
+            vec!["Driver"]
+        }
+    }
+
+    struct DriverLicenseBuilder {}
+
+    impl DocumentBuilder for DriverLicenseBuilder {
+        fn context_registry(&self) -> credential::context_registry::ContextRegistry {
+            let mut registry = credential::context_registry::ContextRegistry::default();
+            registry.register("DriverLicense", &["https://example.com/driver-license/v1"]);
+            registry
+        }
+
+        fn clock(&self) -> Box<dyn clock::Clock> {
+            Box::new(clock::FixedClock("2023-02-24T23:36:38Z".parse().unwrap()))
+        }
+    }
+
+    fn create_driver_license_credential(builder: &impl DocumentBuilder) -> credential::Credential {
+        let subject = DriverLicense {
+            id: "did:example:b34ca6cd37bbf23".to_string(),
+            license_class: "B".to_string(),
+        };
+        builder
+            .create_credential(
+                subject,
+                HashMap::new(),
+                "https://example.com/credentials/1",
+                "did:example:28394728934792387".to_string(),
+            )
+            .unwrap()
+    }
+
     #[test]
-    fn test_context_adherance() {
-        let kp =
-            signature::suite::ed25519_2020::Ed25519KeyPair::new("test".to_string(), None).unwrap();
-        let issuer = kp.get_did();
-        let builder = DefaultDocumentBuilder {};
-        let mut cred_subject = std::collections::HashMap::new();
-        cred_subject.insert("accountId".to_string(), serde_json::json!("1111111"));
-        cred_subject.insert("type".to_string(), serde_json::json!(["BankAccount"]));
-        cred_subject.insert(
-            "address".to_string(),
-            serde_json::json!({
-                "type": ["PostalAddress"],
-                "streetAddress": "19 Knox St",
-                "addressLocality": "Toronto",
-                "addressRegion": "ON",
-                "addressCountry": "Canada",
-                "postalCode": "M3B 1A2"
-            }),
+    fn test_create_credential_with_registered_contexts() {
+        let credential = create_driver_license_credential(&DriverLicenseBuilder {});
+        assert_eq!(
+            serde_json::to_value(&credential.context).unwrap(),
+            json!([
+                "https://www.w3.org/2018/credentials/v1",
+                "https://example.com/driver-license/v1"
+            ])
         );
-        cred_subject.insert(
-            "routingInfo".to_string(),
-            serde_json::json!({
-                "type":["RoutingInfo"],
-                "code": "GBDSC",
-                "value": "042962"
-            }),
-        );
-        cred_subject.insert(
-            "id".to_string(),
-            serde_json::json!("did:knox:z6Mk2cd21e9abe57fae7...31073da1b522790e63834fe17a4c2be"),
+        assert_eq!(
+            credential.cred_type,
+            vec![
+                credential::CredentialType::VerifiableCredential,
+                credential::CredentialType::Other("DriverLicense".to_string())
+            ]
         );
 
-        cred_subject.insert("givenName".to_string(), serde_json::json!("Alice"));
-        cred_subject.insert("familyName".to_string(), serde_json::json!("Smith"));
-        cred_subject.insert(
-            "iban".to_string(),
-            serde_json::json!("GB74GSLD04296280001319"),
+        let credential = create_driver_license_credential(&DefaultDocumentBuilder {});
+        assert_eq!(
+            serde_json::to_value(&credential.context).unwrap(),
+            json!(["https://www.w3.org/2018/credentials/v1"])
         );
-        cred_subject.insert("BIC11".to_string(), serde_json::json!("TDOMCATTTOR"));
-        let optional_properties = std::collections::HashMap::new();
-        let credential_id = "12345";
+    }
 
+    struct V2DriverLicenseBuilder {}
+
+    impl DocumentBuilder for V2DriverLicenseBuilder {
+        fn context_registry(&self) -> credential::context_registry::ContextRegistry {
+            DriverLicenseBuilder {}.context_registry()
+        }
+
+        fn clock(&self) -> Box<dyn clock::Clock> {
+            DriverLicenseBuilder {}.clock()
+        }
+
+        fn credential_version(&self) -> credential::CredentialVersion {
+            credential::CredentialVersion::V2
+        }
+    }
+
+    #[test]
+    fn test_create_untyped_credential() {
+        let builder = DriverLicenseBuilder {};
+        let subject: HashMap<String, Value> = serde_json::from_value(json!({
+            "id": "did:example:b34ca6cd37bbf23",
+            "type": ["Driver"],
+            "licenseClass": "B"
+        }))
+        .unwrap();
         let credential = builder
-            .create_credential(
-                credential::CredentialType::BankAccount,
-                cred_subject,
-                optional_properties,
-                credential_id,
-                issuer,
+            .create_untyped_credential(
+                credential::CredentialType::Other("DriverLicense".to_string()),
+                subject.clone(),
+                HashMap::new(),
+                "https://example.com/credentials/1",
+                "did:example:28394728934792387".to_string(),
             )
             .unwrap();
 
-        let value = serde_json::to_value(credential).unwrap().to_string();
-
-        // Create a "remote" document by parsing a file manually.
-        let input = json_ld::RemoteDocument::new(
-            // We use `IriBuf` as IRI type.
-            None,
-            // Optional content type.
-            None,
-            // Parse the file.
-            json_ld::syntax::Value::parse_str(
-                value.as_str(),
-                |span| span, // keep the source `Span` of each element as metadata.
+        assert_eq!(
+            serde_json::to_value(&credential).unwrap(),
+            serde_json::to_value(create_driver_license_credential(&builder)).unwrap()
+        );
+        assert!(matches!(
+            credential.subject,
+            credential::CredentialSubject::Single(ref s) if *s == subject
+        ));
+    }
+
+    #[test]
+    fn test_create_v2_credential() {
+        let builder = V2DriverLicenseBuilder {};
+        let credential = create_driver_license_credential(&builder);
+        assert_eq!(
+            serde_json::to_value(&credential.context).unwrap(),
+            json!([
+                "https://www.w3.org/ns/credentials/v2",
+                "https://example.com/driver-license/v1"
+            ])
+        );
+        assert_eq!(credential.issuance_date, None);
+        assert_eq!(credential.valid_from, Some(builder.clock().now()));
+        assert!(serde_valid::Validate::validate(&credential).is_ok());
+
+        let presentation = builder.create_presentation(vec![]).unwrap();
+        assert_eq!(
+            serde_json::to_value(&presentation.context).unwrap(),
+            json!(["https://www.w3.org/ns/credentials/v2"])
+        );
+    }
+
+    struct ExpiringProofBuilder {}
+
+    impl DocumentBuilder for ExpiringProofBuilder {
+        fn clock(&self) -> Box<dyn clock::Clock> {
+            DriverLicenseBuilder {}.clock()
+        }
+
+        fn strict_context_adherence(&self) -> bool {
+            false
+        }
+
+        fn proof_lifetime(&self) -> Option<chrono::Duration> {
+            Some(chrono::Duration::days(30))
+        }
+    }
+
+    #[test]
+    fn test_issue_credential_with_proof_lifetime() {
+        let builder = ExpiringProofBuilder {};
+        let signer: signature::suite::ed25519_2020::Ed25519DidSigner =
+            signature::suite::ed25519_2020::Ed25519KeyPair::new(TEST_DID_METHOD.to_string(), None)
+                .unwrap()
+                .into();
+
+        let vc = builder
+            .issue_credential(
+                create_driver_license_credential(&builder),
+                &signer,
+                signature::suite::VerificationRelation::AssertionMethod,
+            )
+            .unwrap();
+        assert!(matches!(
+            vc.proof,
+            proof::CredentialProof::Single(proof::ProofType::Ed25519Signature2020(proof))
+                if proof.expires == Some("2023-03-26T23:36:38Z".parse().unwrap())
+        ));
+    }
+
+    #[test]
+    fn test_issue_credential_with_clock() {
+        let builder = DriverLicenseBuilder {};
+        let signer: signature::suite::ed25519_2020::Ed25519DidSigner =
+            signature::suite::ed25519_2020::Ed25519KeyPair::new(TEST_DID_METHOD.to_string(), None)
+                .unwrap()
+                .into();
+
+        let mut credential = create_driver_license_credential(&builder);
+        assert_eq!(credential.issuance_date, Some(builder.clock().now()));
+        // Signing loads the contexts, the example vocabulary defines the driver license terms
+        credential.context = vec![
+            credential::ContextValue::String(credential::CREDENTIAL_V1_CONTEXT.to_string()),
+            credential::ContextValue::String(credential::EXAMPLE_CREDENTIAL_V1_CONTEXT.to_string()),
+        ];
+        let vc = credential
+            .try_into_verifiable_credential(
+                &signer,
+                signature::suite::VerificationRelation::AssertionMethod,
+                &*builder.clock(),
+            )
+            .unwrap();
+
+        let skew = clock::default_clock_skew();
+        let verify_at = |now: &str| {
+            credential::validity::verify_credential_validity(
+                &vc,
+                &clock::FixedClock(now.parse().unwrap()),
+                skew,
+            )
+        };
+        assert!(verify_at("2023-02-24T23:36:38Z").is_ok());
+        assert!(verify_at("2023-02-24T23:40:00Z").is_ok());
+        assert!(verify_at("2023-02-24T23:35:00Z").is_ok());
+        assert!(matches!(
+            verify_at("2023-02-24T23:00:00Z"),
+            Err(error::Error::CredentialNotYetValid(_))
+        ));
+    }
+
+    // A DID resolver of the DID Document of the key pair
+    fn create_did_resolver(kp: &signature::suite::ed25519_2020::Ed25519KeyPair) -> MockDIDResolver {
+        let verifier = signature::suite::ed25519_2020::Ed25519DidVerifier::from(kp.clone());
+        let did_document =
+            tokio_test::block_on(identity::create_identity(verifier, vec![])).unwrap();
+        let mut resolver = MockDIDResolver::default();
+        resolver.expect_resolve().returning(move |_| {
+            Ok(ResolveResponse {
+                did_document: serde_json::to_value(&did_document).unwrap(),
+                did_document_metadata: Default::default(),
+                did_resolution_metadata: Default::default(),
+            })
+        });
+        resolver
+    }
+
+    #[rstest::rstest]
+    #[case::valid(signature::suite::VerificationRelation::AssertionMethod, true, true)]
+    #[case::other_purpose(signature::suite::VerificationRelation::Authentication, true, false)]
+    #[case::other_controller(signature::suite::VerificationRelation::AssertionMethod, false, false)]
+    fn test_verify_data_integrity_proof(
+        #[case] relation: signature::suite::VerificationRelation,
+        #[case] same_controller: bool,
+        #[case] expect_ok: bool,
+    ) {
+        let kp =
+            signature::suite::ed25519_2020::Ed25519KeyPair::new(TEST_DID_METHOD.to_string(), None)
+                .unwrap();
+        let controller = kp.get_did();
+        let signer: signature::suite::ed25519_2020::Ed25519DidSigner = kp.clone().into();
+        let resolver = match same_controller {
+            true => create_did_resolver(&kp),
+            false => create_did_resolver(
+                &signature::suite::ed25519_2020::Ed25519KeyPair::new(
+                    TEST_DID_METHOD.to_string(),
+                    None,
+                )
+                .unwrap(),
+            ),
+        };
+
+        let mut doc = json!({
+            "@context": [credential::CREDENTIAL_V1_CONTEXT, credential::EXAMPLE_CREDENTIAL_V1_CONTEXT],
+            "type": ["VerifiableCredential"],
+            "issuer": controller,
+            "credentialSubject": {"id": "did:example:b34ca6cd37bbf23", "name": "Alice"}
+        });
+        let proof =
+            proof::create_data_integrity_proof(&signer, doc.clone(), relation, &clock::SystemClock)
+                .unwrap();
+        doc["proof"] = serde_json::to_value(proof).unwrap();
+
+        let res = tokio_test::block_on(verify_data_integrity_proof(
+            doc.clone(),
+            &controller,
+            signature::suite::VerificationRelation::AssertionMethod,
+            &resolver,
+        ));
+        assert_eq!(res.is_ok(), expect_ok, "{res:?}");
+        if !expect_ok {
+            assert!(matches!(res, Err(error::Error::InvalidProof(_))));
+            return;
+        }
+
+        doc["credentialSubject"]["name"] = json!("Bob");
+        let res = tokio_test::block_on(verify_data_integrity_proof(
+            doc,
+            &controller,
+            signature::suite::VerificationRelation::AssertionMethod,
+            &resolver,
+        ));
+        assert!(matches!(res, Err(error::Error::Signature(_))));
+    }
+
+    const DRIVER_LICENSE_SCHEMA_ID: &str = "https://example.com/schemas/driver-license.json";
+
+    // A driver license credential issued by the key pair at the time of the clock of `DriverLicenseBuilder`
+    fn create_verifiable_driver_license(
+        kp: &signature::suite::ed25519_2020::Ed25519KeyPair,
+    ) -> credential::VerifiableCredential {
+        let builder = DriverLicenseBuilder {};
+        let mut credential = create_driver_license_credential(&builder);
+        credential.issuer = kp.get_did().into();
+        credential.context.push(credential::ContextValue::String(
+            credential::EXAMPLE_CREDENTIAL_V1_CONTEXT.to_string(),
+        ));
+        credential.credential_schema = Some(
+            credential::schema::SchemaEntry {
+                id: DRIVER_LICENSE_SCHEMA_ID.to_string(),
+                schema_type: credential::schema::SchemaType::JsonSchema,
+            }
+            .into(),
+        );
+        let signer: signature::suite::ed25519_2020::Ed25519DidSigner = kp.clone().into();
+        credential
+            .try_into_verifiable_credential_unchecked(
+                &signer,
+                signature::suite::VerificationRelation::AssertionMethod,
+                &*builder.clock(),
+            )
+            .unwrap()
+    }
+
+    fn create_driver_license_schema_loader(license_class: &str) -> HashMap<String, Value> {
+        HashMap::from([(
+            DRIVER_LICENSE_SCHEMA_ID.to_string(),
+            json!({
+                "type": "object",
+                "properties": {"licenseClass": {"const": license_class}},
+                "required": ["licenseClass"]
+            }),
+        )])
+    }
+
+    // A builder verifying documents at a fixed time
+    struct VerifierBuilder(&'static str);
+
+    impl DocumentBuilder for VerifierBuilder {
+        fn clock(&self) -> Box<dyn clock::Clock> {
+            Box::new(clock::FixedClock(self.0.parse().unwrap()))
+        }
+    }
+
+    #[rstest::rstest]
+    #[case::valid("B", false, "2023-02-24T23:40:00Z", None)]
+    #[case::schema_violation(
+        "A",
+        false,
+        "2023-02-24T23:40:00Z",
+        Some("Schema Validation Error: /credentialSubject/licenseClass: \"A\" was expected")
+    )]
+    #[case::tampered(
+        "B",
+        true,
+        "2023-02-24T23:40:00Z",
+        Some("Signature Error: Message Verification Error: Invalid signature.")
+    )]
+    #[case::not_yet_valid(
+        "B",
+        false,
+        "2023-02-24T23:00:00Z",
+        Some("Credential not yet valid: https://example.com/credentials/1 is not valid before 2023-02-24 23:36:38 UTC")
+    )]
+    fn test_verify_credential(
+        #[case] license_class: &str,
+        #[case] tampered: bool,
+        #[case] verified_at: &'static str,
+        #[case] expected_error: Option<&str>,
+    ) {
+        let kp =
+            signature::suite::ed25519_2020::Ed25519KeyPair::new(TEST_DID_METHOD.to_string(), None)
+                .unwrap();
+        let resolver = create_did_resolver(&kp);
+        let mut vc = create_verifiable_driver_license(&kp);
+        if tampered {
+            vc.credential.id = Some("https://example.com/credentials/2".to_string());
+        }
+
+        let res = tokio_test::block_on(verify_credential(
+            &VerifierBuilder(verified_at),
+            &vc,
+            &resolver,
+            &credential::status::MockStatusListResolver::default(),
+            &create_driver_license_schema_loader(license_class),
+        ));
+        assert_eq!(res.err().map(|e| e.to_string()).as_deref(), expected_error);
+    }
+
+    #[rstest::rstest]
+    #[case::valid("valid", "B")]
+    #[case::tampered_presentation("tampered_presentation", "B")]
+    #[case::tampered_credential("tampered_credential", "B")]
+    #[case::invalid_credential("invalid_credential", "A")]
+    fn test_verify_presentation(#[case] kind: &str, #[case] license_class: &str) {
+        let kp =
+            signature::suite::ed25519_2020::Ed25519KeyPair::new(TEST_DID_METHOD.to_string(), None)
+                .unwrap();
+        let resolver = create_did_resolver(&kp);
+        let signer: signature::suite::ed25519_2020::Ed25519DidSigner = kp.clone().into();
+        let builder = VerifierBuilder("2023-02-24T23:40:00Z");
+
+        let mut vc = create_verifiable_driver_license(&kp);
+        if kind == "tampered_credential" {
+            vc.credential.id = Some("https://example.com/credentials/2".to_string());
+        }
+        let mut vp = builder
+            .create_presentation(vec![vc])
+            .unwrap()
+            .try_into_verifiable_presentation(
+                &signer,
+                signature::suite::VerificationRelation::Authentication,
+                &*DriverLicenseBuilder {}.clock(),
             )
-            .expect("Error creating remote document"),
+            .unwrap();
+        if kind == "tampered_presentation" {
+            vp.presentation.id = Some("https://example.com/presentations/2".to_string());
+        }
+
+        let res = tokio_test::block_on(verify_presentation(
+            &builder,
+            &vp,
+            &resolver,
+            &credential::status::MockStatusListResolver::default(),
+            &create_driver_license_schema_loader(license_class),
+        ));
+        match kind {
+            "valid" => assert!(res.is_ok(), "{res:?}"),
+            "invalid_credential" => {
+                assert!(matches!(res, Err(error::Error::SchemaValidation(_))))
+            }
+            _ => assert!(matches!(res, Err(error::Error::Signature(_)))),
+        }
+
+        // Presentations are only valid once created
+        let res = tokio_test::block_on(verify_presentation(
+            &VerifierBuilder("2023-02-24T23:00:00Z"),
+            &vp,
+            &resolver,
+            &credential::status::MockStatusListResolver::default(),
+            &create_driver_license_schema_loader(license_class),
+        ));
+        if kind != "tampered_presentation" {
+            assert!(matches!(res, Err(error::Error::ProofNotYetValid(_))));
+        }
+    }
+
+    #[test]
+    fn test_typed_credential_subject() {
+        let credential = create_driver_license_credential(&DriverLicenseBuilder {});
+        let subject = serde_json::to_value(&credential.subject).unwrap();
+        assert_eq!(
+            subject,
+            json!({"id": "did:example:b34ca6cd37bbf23", "type": ["Driver"], "licenseClass": "B"})
         );
 
-        // Use `NoLoader` as we won't need to load any remote document.
-        let mut loader = json_ld::NoLoader::<_, _>::new();
+        let vc = credential::VerifiableCredential::from_str(
+            &serde_json::to_string(&credential.into_verifiable_credential(
+                proof::CredentialProof::Single(proof::ProofType::Ed25519Signature2020(
+                    proof::DataIntegrityProof {
+                        proof_type: "Ed25519Signature2020".to_string(),
+                        created: None,
+                        expires: None,
+                        verification_method: "did:example:28394728934792387#key-1".to_string(),
+                        proof_purpose: signature::suite::VerificationRelation::AssertionMethod,
+                        proof_value: "z3FXQjecWufY46yg5abdVZsXqLhxhueuSoZgNSARiKBk".to_string(),
+                    },
+                )),
+            ))
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            vc.credential.subject_as::<DriverLicense>().unwrap(),
+            DriverLicense {
+                id: "did:example:b34ca6cd37bbf23".to_string(),
+                license_class: "B".to_string(),
+            }
+        );
+        assert!(matches!(
+            vc.credential
+                .subject_as::<credential::subject::PermanentResident>(),
+            Err(error::Error::InvalidCredentialSubject(_))
+        ));
+    }
 
-        // Expand the "remote" document.
-        let expanded = aw!(input.expand(&mut loader));
+    #[rstest::rstest]
+    #[case::valid("B", None)]
+    #[case::invalid(
+        "Z",
+        Some("Schema Validation Error: /credentialSubject/licenseClass: \"Z\" is not one of [\"A\",\"B\",\"C\"]")
+    )]
+    fn test_create_credential_with_schema(
+        #[case] license_class: &str,
+        #[case] expected: Option<&str>,
+    ) {
+        let schema = credential::schema::SchemaEntry {
+            id: "https://example.com/schemas/driver-license.json".to_string(),
+            schema_type: credential::schema::SchemaType::JsonSchema,
+        };
+        let json_schema = json!({
+            "type": "object",
+            "properties": {"licenseClass": {"enum": ["A", "B", "C"]}},
+            "required": ["id", "licenseClass"]
+        });
+        let subject = DriverLicense {
+            id: "did:example:b34ca6cd37bbf23".to_string(),
+            license_class: license_class.to_string(),
+        };
 
-        match expanded {
-            Err(e) => {
-                println!("Error: {e:?}");
+        let res = DriverLicenseBuilder {}.create_credential_with_schema(
+            subject,
+            schema.clone(),
+            &json_schema,
+            HashMap::new(),
+            "https://example.com/credentials/1",
+            "did:example:28394728934792387",
+        );
+        match res {
+            Ok(credential) => {
+                assert!(expected.is_none());
+                assert_eq!(
+                    serde_json::to_value(&credential).unwrap()["credentialSchema"],
+                    json!({"id": schema.id, "type": "JsonSchema"})
+                );
             }
-            Ok(expanded) => {
-                for object in expanded.into_value() {
-                    if let Some(_id) = object.id() {
-                        let _name = object
-                            .as_node()
-                            .unwrap()
-                            .get_any(&iri!("http://xmlns.com/foaf/0.1/name"))
-                            .unwrap()
-                            .as_str()
-                            .unwrap();
+            Err(e) => assert_eq!(Some(e.to_string().as_str()), expected),
+        }
+    }
+
+    fn create_bank_account_credential(builder: &impl DocumentBuilder) -> credential::Credential {
+        let kp =
+            signature::suite::ed25519_2020::Ed25519KeyPair::new(TEST_DID_METHOD.to_string(), None)
+                .unwrap();
+        let cred_subject = credential::subject::BankAccount {
+            id: "did:knox:z6Mk2cd21e9abe57fae7...31073da1b522790e63834fe17a4c2be".to_string(),
+            account_id: "1111111".to_string(),
+            given_name: "Alice".to_string(),
+            family_name: "Smith".to_string(),
+            address: Some(credential::subject::PostalAddress {
+                street_address: "19 Knox St".to_string(),
+                address_locality: "Toronto".to_string(),
+                address_region: "ON".to_string(),
+                address_country: "Canada".to_string(),
+                postal_code: "M3B 1A2".to_string(),
+            }),
+            routing_info: Some(credential::subject::RoutingInfo {
+                code: "GBDSC".to_string(),
+                value: "042962".to_string(),
+            }),
+            iban: Some("GB74GSLD04296280001319".to_string()),
+            bic11: Some("TDOMCATTTOR".to_string()),
+        };
+
+        builder
+            .create_credential(cred_subject, HashMap::new(), "12345", kp.get_did())
+            .unwrap()
+    }
+
+    // Excerpts of the published contexts, enough to expand the test credentials offline. The terms of the bank account
+    // subject are defined by its own context, none is needed from the traceability context.
+    struct OfflineDocumentBuilder {}
+
+    impl DocumentBuilder for OfflineDocumentBuilder {
+        fn preloaded_contexts(&self) -> credential::preloaded_contexts::PreloadedContexts {
+            let credentials_v1 = json!({"@context": {
+                "@version": 1.1,
+                "@protected": true,
+                "id": "@id",
+                "type": "@type",
+                "VerifiableCredential": {
+                    "@id": "https://www.w3.org/2018/credentials#VerifiableCredential",
+                    "@context": {
+                        "@version": 1.1,
+                        "@protected": true,
+                        "id": "@id",
+                        "type": "@type",
+                        "cred": "https://www.w3.org/2018/credentials#",
+                        "credentialSubject": {"@id": "cred:credentialSubject", "@type": "@id"},
+                        "issuer": {"@id": "cred:issuer", "@type": "@id"},
+                        "issuanceDate": {
+                            "@id": "cred:issuanceDate",
+                            "@type": "http://www.w3.org/2001/XMLSchema#dateTime"
+                        }
                     }
                 }
-            }
+            }});
+            let credentials_v2 = json!({"@context": {
+                "@version": 1.1,
+                "@protected": true,
+                "id": "@id",
+                "type": "@type",
+                "@vocab": "https://www.w3.org/ns/credentials/issuer-dependent#"
+            }});
+
+            let mut contexts = credential::preloaded_contexts::PreloadedContexts::new();
+            contexts
+                .insert(credential::CREDENTIAL_V1_CONTEXT, credentials_v1)
+                .insert(
+                    credential::EXAMPLE_CREDENTIAL_V1_CONTEXT,
+                    json!({"@context": {"@vocab": "https://example.org/examples#"}}),
+                )
+                .insert(credential::CREDENTIAL_V2_CONTEXT, credentials_v2)
+                .insert(
+                    credential::EXAMPLE_CREDENTIAL_V2_CONTEXT,
+                    json!({"@context": {"@vocab": "https://www.w3.org/ns/credentials/examples#"}}),
+                )
+                .insert(
+                    credential::BANK_ACCOUNT_CREDENTIAL_CONTEXT,
+                    json!({"@context": {"@version": 1.1}}),
+                );
+            contexts
+        }
+    }
+
+    #[test]
+    fn test_context_adherance() {
+        let builder = OfflineDocumentBuilder {};
+        let signer: signature::suite::ed25519_2020::Ed25519DidSigner =
+            signature::suite::ed25519_2020::Ed25519KeyPair::new(TEST_DID_METHOD.to_string(), None)
+                .unwrap()
+                .into();
+        let relation = signature::suite::VerificationRelation::AssertionMethod;
+
+        // The contexts of the bank account subject define all its terms
+        let credential = create_bank_account_credential(&builder);
+        let res = builder.issue_credential(credential.clone(), &signer, relation);
+        assert!(res.is_ok(), "{res:?}");
+
+        // An undefined term would silently drop out of the signed data
+        let mut with_nickname = credential.clone();
+        with_nickname
+            .property_set
+            .insert("nickname".to_string(), json!("Al"));
+        let res = builder.issue_credential(with_nickname, &signer, relation);
+        assert!(
+            matches!(res, Err(error::Error::UndefinedTerms(terms)) if terms == vec!["nickname".to_string()])
+        );
+
+        // The vocabulary of the v2 context defines the terms of the other contexts
+        let mut v2 = credential;
+        v2.context = vec![
+            credential::ContextValue::String(credential::CREDENTIAL_V2_CONTEXT.to_string()),
+            credential::ContextValue::String(
+                credential::BANK_ACCOUNT_CREDENTIAL_CONTEXT.to_string(),
+            ),
+        ];
+        v2.property_set.insert("nickname".to_string(), json!("Al"));
+        assert!(builder.issue_credential(v2, &signer, relation).is_ok());
+    }
+
+    struct LenientDocumentBuilder {}
+
+    impl DocumentBuilder for LenientDocumentBuilder {
+        fn strict_context_adherence(&self) -> bool {
+            false
+        }
+
+        fn preloaded_contexts(&self) -> credential::preloaded_contexts::PreloadedContexts {
+            OfflineDocumentBuilder {}.preloaded_contexts()
         }
     }
+
+    #[test]
+    fn test_context_adherance_opt_out() {
+        let builder = LenientDocumentBuilder {};
+        let signer: signature::suite::ed25519_2020::Ed25519DidSigner =
+            signature::suite::ed25519_2020::Ed25519KeyPair::new(TEST_DID_METHOD.to_string(), None)
+                .unwrap()
+                .into();
+        let relation = signature::suite::VerificationRelation::AssertionMethod;
+
+        let mut credential = create_bank_account_credential(&builder);
+        credential
+            .property_set
+            .insert("nickname".to_string(), json!("Al"));
+        assert!(builder
+            .issue_credential(credential, &signer, relation)
+            .is_ok());
+    }
 }
@@ -1,4 +1,4 @@
-mod normalization;
+pub(crate) mod normalization;
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq)]
 struct ProofOptionDocument {
@@ -8,6 +8,8 @@ struct ProofOptionDocument {
     proof_type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     created: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires: Option<chrono::DateTime<chrono::Utc>>,
     #[serde(rename = "verificationMethod")]
     verification_method: String,
     #[serde(rename = "proofPurpose")]
@@ -15,14 +17,13 @@ struct ProofOptionDocument {
 }
 
 impl ProofOptionDocument {
-    fn get_default_context() -> super::credential::DocumentContext {
+    // The proof options use the contexts of the VC Data Model version of the secured document, V1 by default
+    fn get_context(unsecured_doc: &serde_json::Value) -> super::credential::DocumentContext {
+        let version = super::credential::CredentialVersion::from_document(unsecured_doc)
+            .unwrap_or(super::credential::CredentialVersion::V1);
         vec![
-            super::credential::ContextValue::String(
-                super::credential::BASE_CREDENTIAL_CONTEXT.to_string(),
-            ),
-            super::credential::ContextValue::String(
-                super::credential::EXAMPLE_CREDENTIAL_CONTEXT.to_string(),
-            ),
+            super::credential::ContextValue::String(version.base_context().to_string()),
+            super::credential::ContextValue::String(version.example_context().to_string()),
         ]
     }
 }
@@ -33,6 +34,8 @@ pub struct DataIntegrityProof {
     pub proof_type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub created: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub expires: Option<chrono::DateTime<chrono::Utc>>,
     #[serde(rename = "verificationMethod")]
     pub verification_method: String,
     #[serde(rename = "proofPurpose")]
@@ -83,6 +86,7 @@ impl ProofOptionDocument {
         DataIntegrityProof {
             proof_type: self.proof_type,
             created: self.created,
+            expires: self.expires,
             verification_method: self.verification_method,
             proof_purpose: self.proof_purpose,
             proof_value,
@@ -90,61 +94,121 @@ impl ProofOptionDocument {
     }
 }
 
+/// Options of new data integrity proofs, on top of the signer and the proof purpose.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SigningOptions {
+    /// The time the proof expires at, signed along with the other proof options.
+    pub expires: Option<chrono::DateTime<chrono::Utc>>,
+    /// Reject documents with terms that their contexts do not define, see `credential::context_adherence`. The check
+    /// uses the expansion of the document that is signed.
+    pub strict_context_adherence: bool,
+    /// Contexts used in place of loading them over HTTP when expanding the document and the proof options.
+    pub preloaded_contexts: super::credential::preloaded_contexts::PreloadedContexts,
+}
+
 // Use it as an example
 /// Given a JSON-LD document, create a data integrity proof for the document.
 /// Currently, only `Ed25519Signature2020` data integrity proofs in the JSON-LD format can be created.
 /// We should move to the new spec approach as `Ed25519Signature2020` is considered legacy
 /// Follows algorithm described in https://www.w3.org/TR/vc-data-integrity/#add-proof
+/// The proof is created at the time of `clock`.
 pub fn create_data_integrity_proof<S: signature::suite::Signature>(
     signer: &impl signature::suite::DIDSigner<S>,
     unsecured_doc: serde_json::Value,
     relation: signature::suite::VerificationRelation,
+    clock: &(impl super::clock::Clock + ?Sized),
+) -> Result<CredentialProof, super::error::Error> {
+    create_data_integrity_proof_with_options(
+        signer,
+        unsecured_doc,
+        relation,
+        clock,
+        &SigningOptions::default(),
+    )
+}
+
+/// Create a data integrity proof for the document as `create_data_integrity_proof` does, with the given `options`.
+pub fn create_data_integrity_proof_with_options<S: signature::suite::Signature>(
+    signer: &impl signature::suite::DIDSigner<S>,
+    unsecured_doc: serde_json::Value,
+    relation: signature::suite::VerificationRelation,
+    clock: &(impl super::clock::Clock + ?Sized),
+    options: &SigningOptions,
 ) -> Result<CredentialProof, super::error::Error> {
     let proof_options = ProofOptionDocument {
-        context: ProofOptionDocument::get_default_context(),
+        context: ProofOptionDocument::get_context(&unsecured_doc),
         proof_type: signer.get_proof_type(),
-        created: Some(chrono::Utc::now()),
+        created: Some(clock.now()),
+        expires: options.expires,
         verification_method: signer.get_verification_method(relation),
         proof_purpose: relation,
     };
-    let proof = create_ed25519_signature_2020_proof_value(signer, unsecured_doc, &proof_options)?;
+    let proof =
+        create_ed25519_signature_2020_proof_value(signer, unsecured_doc, &proof_options, options)?;
 
     Ok(CredentialProof::Single(ProofType::Ed25519Signature2020(
         proof_options.into_data_integrity_proof(proof),
     )))
 }
 
-#[cfg(feature = "v2_test")]
-pub fn create_data_integrity_proof_for_test<S: signature::suite::Signature>(
-    signer: &impl signature::suite::DIDSigner<S>,
+/// Given a JSON-LD document and its `Ed25519Signature2020` data integrity proof, verify the proof value with the `verifier`.
+/// The `verifier` must hold the public key of the proof `verificationMethod`.
+/// Follows algorithm described in https://www.w3.org/TR/vc-data-integrity/#verify-proof
+pub fn verify_ed25519_signature_2020_proof<S: signature::suite::Signature>(
+    verifier: &impl signature::suite::DIDVerifier<S>,
     unsecured_doc: serde_json::Value,
-    proof_time: chrono::DateTime<chrono::Utc>,
-    verification_method: String,
-) -> Result<CredentialProof, super::error::Error> {
+    proof: &DataIntegrityProof,
+) -> Result<(), super::error::Error> {
     let proof_options = ProofOptionDocument {
-        context: ProofOptionDocument::get_default_context(),
-        proof_type: signer.get_proof_type(),
-        created: Some(proof_time),
-        verification_method,
-        proof_purpose: signature::suite::VerificationRelation::AssertionMethod,
+        context: ProofOptionDocument::get_context(&unsecured_doc),
+        proof_type: proof.proof_type.clone(),
+        created: proof.created,
+        expires: proof.expires,
+        verification_method: proof.verification_method.clone(),
+        proof_purpose: proof.proof_purpose,
     };
+    let combined_hash_data =
+        hash_document_and_proof_options(unsecured_doc, &proof_options, &SigningOptions::default())?;
 
-    let proof = create_ed25519_signature_2020_proof_value(signer, unsecured_doc, &proof_options)?;
-
-    Ok(CredentialProof::Single(ProofType::Ed25519Signature2020(
-        proof_options.into_data_integrity_proof(proof),
-    )))
+    verifier.decoded_relational_verify(
+        &combined_hash_data,
+        proof.proof_value.clone(),
+        proof.proof_purpose,
+    )?;
+    Ok(())
 }
 
 fn create_ed25519_signature_2020_proof_value<S: signature::suite::Signature>(
     signer: &impl signature::suite::DIDSigner<S>,
     unsecured_doc: serde_json::Value,
     proof_options: &ProofOptionDocument,
+    options: &SigningOptions,
 ) -> Result<String, super::error::Error> {
+    let combined_hash_data =
+        hash_document_and_proof_options(unsecured_doc, proof_options, options)?;
+    let proof = signer.encoded_relational_sign(&combined_hash_data, proof_options.proof_purpose)?;
+
+    Ok(proof)
+}
+
+// The context adherence of `options` only applies to the document, the proof options are defined by their contexts
+fn hash_document_and_proof_options(
+    unsecured_doc: serde_json::Value,
+    proof_options: &ProofOptionDocument,
+    options: &SigningOptions,
+) -> Result<Vec<u8>, super::error::Error> {
     let serialized_proof_options = serde_json::to_value(proof_options)?;
 
-    let transformed_data = normalization::create_normalized_doc(unsecured_doc)?;
-    let transformed_proof_options = normalization::create_normalized_doc(serialized_proof_options)?;
+    let transformed_data = normalization::create_normalized_doc(
+        &unsecured_doc,
+        options.strict_context_adherence,
+        &options.preloaded_contexts,
+    )?;
+    let transformed_proof_options = normalization::create_normalized_doc(
+        &serialized_proof_options,
+        false,
+        &options.preloaded_contexts,
+    )?;
     let hashed_unsecured_doc = normalization::hash(&transformed_data);
     let hash_proof_options = normalization::hash(&transformed_proof_options);
 
@@ -154,15 +218,16 @@ fn create_ed25519_signature_2020_proof_value<S: signature::suite::Signature>(
     let mut combined_hash_data = hash_proof_options.to_vec();
     combined_hash_data.extend_from_slice(&hashed_unsecured_doc);
 
-    let proof = signer.encoded_relational_sign(&combined_hash_data, proof_options.proof_purpose)?;
-
-    Ok(proof)
+    Ok(combined_hash_data)
 }
 
 #[cfg(test)]
 mod tests {
 
-    use super::create_data_integrity_proof;
+    use super::{
+        create_data_integrity_proof, create_data_integrity_proof_with_options,
+        verify_ed25519_signature_2020_proof, SigningOptions,
+    };
 
     const TEST_DID_METHOD: &str = "knox";
 
@@ -186,9 +251,111 @@ mod tests {
         )
         .unwrap();
         let signer: signature::suite::ed25519_2020::Ed25519DidSigner = kp.clone().into();
-        let res = create_data_integrity_proof(&signer, doc.clone(), relation);
-        assert!(res.is_ok());
+        let created = "2023-02-24T23:36:38Z".parse().unwrap();
+        let res = create_data_integrity_proof(
+            &signer,
+            doc.clone(),
+            relation,
+            &crate::clock::FixedClock(created),
+        );
+        assert!(matches!(
+            res,
+            Ok(super::CredentialProof::Single(super::ProofType::Ed25519Signature2020(proof)))
+                if proof.created == Some(created)
+        ));
+    }
+
+    #[test]
+    fn test_verify_ed25519_signature_2020_proof() {
+        let kp =
+            signature::suite::ed25519_2020::Ed25519KeyPair::new(TEST_DID_METHOD.to_string(), None)
+                .unwrap();
+        let signer: signature::suite::ed25519_2020::Ed25519DidSigner = kp.clone().into();
+        let verifier: signature::suite::ed25519_2020::Ed25519DidVerifier = kp.into();
+        let doc = create_unverified_credential_doc();
+
+        let proof = match create_data_integrity_proof(
+            &signer,
+            doc.clone(),
+            signature::suite::VerificationRelation::CapabilityInvocation,
+            &crate::clock::SystemClock,
+        )
+        .unwrap()
+        {
+            super::CredentialProof::Single(super::ProofType::Ed25519Signature2020(proof)) => proof,
+            proof => panic!("unexpected proof {proof:?}"),
+        };
+        assert!(verify_ed25519_signature_2020_proof(&verifier, doc.clone(), &proof).is_ok());
+
+        let mut tampered_doc = doc;
+        tampered_doc["issuer"] =
+            serde_json::json!("did:knox:z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2");
+        assert!(verify_ed25519_signature_2020_proof(&verifier, tampered_doc, &proof).is_err());
+    }
+
+    #[test]
+    fn test_verify_proof_with_tampered_expires() {
+        let kp =
+            signature::suite::ed25519_2020::Ed25519KeyPair::new(TEST_DID_METHOD.to_string(), None)
+                .unwrap();
+        let signer: signature::suite::ed25519_2020::Ed25519DidSigner = kp.clone().into();
+        let verifier: signature::suite::ed25519_2020::Ed25519DidVerifier = kp.into();
+        let doc = create_unverified_credential_doc();
+        let expires = "2023-03-24T23:36:38Z".parse().unwrap();
+
+        let mut proof = match create_data_integrity_proof_with_options(
+            &signer,
+            doc.clone(),
+            signature::suite::VerificationRelation::AssertionMethod,
+            &crate::clock::SystemClock,
+            &SigningOptions {
+                expires: Some(expires),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        {
+            super::CredentialProof::Single(super::ProofType::Ed25519Signature2020(proof)) => proof,
+            proof => panic!("unexpected proof {proof:?}"),
+        };
+        assert_eq!(proof.expires, Some(expires));
+        assert!(verify_ed25519_signature_2020_proof(&verifier, doc.clone(), &proof).is_ok());
+
+        for tampered_expires in [Some("2033-03-24T23:36:38Z".parse().unwrap()), None] {
+            proof.expires = tampered_expires;
+            assert!(verify_ed25519_signature_2020_proof(&verifier, doc.clone(), &proof).is_err());
+        }
     }
+
+    #[rstest::rstest]
+    #[case::v1(
+        "https://www.w3.org/2018/credentials/v1",
+        "https://www.w3.org/2018/credentials/examples/v1"
+    )]
+    #[case::v2(
+        "https://www.w3.org/ns/credentials/v2",
+        "https://www.w3.org/ns/credentials/examples/v2"
+    )]
+    #[case::did_document(
+        "https://www.w3.org/ns/did/v1",
+        "https://www.w3.org/2018/credentials/examples/v1"
+    )]
+    fn test_proof_options_context(#[case] doc_context: &str, #[case] example_context: &str) {
+        let doc = serde_json::json!({ "@context": [doc_context] });
+        let base_context = match doc_context {
+            "https://www.w3.org/ns/did/v1" => "https://www.w3.org/2018/credentials/v1",
+            _ => doc_context,
+        };
+
+        assert_eq!(
+            super::ProofOptionDocument::get_context(&doc),
+            vec![
+                crate::credential::ContextValue::String(base_context.to_string()),
+                crate::credential::ContextValue::String(example_context.to_string()),
+            ]
+        );
+    }
+
     fn create_unverified_credential_doc() -> serde_json::Value {
         let expect = serde_json::json!({
                 "@context": [
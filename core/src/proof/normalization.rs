@@ -1,5 +1,5 @@
 use sophia::{
-    api::{parser::QuadParser, source::QuadSource},
+    api::{dataset::Dataset, parser::QuadParser, quad::Quad, source::QuadSource, term::Term},
     c14n::hash::HashFunction,
     inmem::dataset::FastDataset,
     jsonld::loader::HttpLoader,
@@ -9,8 +9,65 @@ use sophia::{
  * This function is used to create a normalized document from a JSON-LD document.
  * It uses the RDFC10 normalization algorithm. As is required by Ed25519Signature2020 and `eddsa-rdfc-2022`.
  * It is essentially a very slightly modified version of URDNA2015.
+ * The document is expanded once, with the `preloaded_contexts` inlined. With `strict_context_adherence`, that same
+ * expansion is checked for terms that the contexts do not define, see `create_adherent_dataset`.
  */
-pub fn create_normalized_doc(doc: serde_json::Value) -> Result<Vec<u8>, crate::error::Error> {
+pub fn create_normalized_doc(
+    doc: &serde_json::Value,
+    strict_context_adherence: bool,
+    preloaded_contexts: &crate::credential::preloaded_contexts::PreloadedContexts,
+) -> Result<Vec<u8>, crate::error::Error> {
+    let doc = preloaded_contexts.inline(doc);
+    let dataset = match strict_context_adherence {
+        true => create_adherent_dataset(&doc)?,
+        false => create_dataset(&doc)?,
+    };
+
+    let mut output = Vec::<u8>::new();
+    sophia::c14n::rdfc10::normalize(&dataset, &mut output).map_err(|e| {
+        crate::error::Error::Unknown(format!("Error normalizing JSON-LD dataset1: {}", e))
+    })?;
+
+    Ok(output)
+}
+
+/// Expand the JSON-LD document with the vocabulary of undefined terms and reject it if any of its terms expands with
+/// it, see `context_adherence::verify_defined_terms`. Otherwise the vocabulary did not apply and the dataset is the
+/// one of the document itself, ready for normalization.
+pub fn create_adherent_dataset(
+    doc: &serde_json::Value,
+) -> Result<FastDataset, crate::error::Error> {
+    use crate::credential::context_adherence;
+
+    let dataset = create_dataset(&context_adherence::with_undefined_terms_vocab(doc))?;
+    context_adherence::verify_defined_terms(&get_dataset_iris(&dataset)?)?;
+    Ok(dataset)
+}
+
+// The IRIs of the RDF dataset of a JSON-LD document, i.e. of its expanded properties, types and nodes.
+fn get_dataset_iris(
+    dataset: &FastDataset,
+) -> Result<std::collections::BTreeSet<String>, crate::error::Error> {
+    let mut iris = std::collections::BTreeSet::new();
+    for quad in dataset.quads() {
+        let quad = quad.map_err(|e| {
+            crate::error::Error::Unknown(format!("Error reading JSON-LD dataset: {}", e))
+        })?;
+        let terms = [Some(quad.s()), Some(quad.p()), Some(quad.o()), quad.g()];
+        iris.extend(
+            terms
+                .into_iter()
+                .flatten()
+                .filter_map(|term| term.iri())
+                .map(|iri| iri.as_str().to_string()),
+        );
+    }
+
+    Ok(iris)
+}
+
+// Expand the JSON-LD document into its RDF dataset, loading its contexts over HTTP.
+fn create_dataset(doc: &serde_json::Value) -> Result<FastDataset, crate::error::Error> {
     let encoded = doc.to_string();
     let mut dataset = FastDataset::new();
     let loader = sophia::jsonld::loader::HttpLoader::default();
@@ -25,12 +82,7 @@ pub fn create_normalized_doc(doc: serde_json::Value) -> Result<Vec<u8>, crate::e
             crate::error::Error::Unknown(format!("Error parsing JSON-LD document: {}", e))
         })?;
 
-    let mut output = Vec::<u8>::new();
-    sophia::c14n::rdfc10::normalize(&dataset, &mut output).map_err(|e| {
-        crate::error::Error::Unknown(format!("Error normalizing JSON-LD dataset1: {}", e))
-    })?;
-
-    Ok(output)
+    Ok(dataset)
 }
 
 pub fn hash(data: &[u8]) -> [u8; 32] {
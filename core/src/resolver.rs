@@ -0,0 +1,232 @@
+use std::str::FromStr;
+
+pub mod cache;
+pub mod retry;
+
+/// A DIDResolver routing each DID to the resolver registered for its method, so that a single resolver can be used to
+/// verify documents issued by `did:knox`, `did:key`, `did:web`, etc.
+/// The resolver given to `UniversalResolver::new` is the default one: its method is the one returned by `get_method`.
+pub struct UniversalResolver {
+    default_method: &'static str,
+    resolvers: std::collections::HashMap<String, Box<dyn crate::DIDResolver>>,
+}
+
+impl UniversalResolver {
+    pub fn new(default_resolver: impl crate::DIDResolver) -> Self {
+        let mut resolver = UniversalResolver {
+            default_method: default_resolver.get_method(),
+            resolvers: std::collections::HashMap::new(),
+        };
+        resolver.register(default_resolver);
+        resolver
+    }
+
+    /// Register `resolver` for its method, replacing any resolver previously registered for that method.
+    pub fn with_resolver(mut self, resolver: impl crate::DIDResolver) -> Self {
+        self.register(resolver);
+        self
+    }
+
+    pub fn register(&mut self, resolver: impl crate::DIDResolver) {
+        self.register_boxed(resolver.get_method(), Box::new(resolver));
+    }
+
+    /// Same as `register` for resolvers only available as trait objects, which can not report their method.
+    pub fn register_boxed(&mut self, method: &str, resolver: Box<dyn crate::DIDResolver>) {
+        self.resolvers.insert(method.to_string(), resolver);
+    }
+
+    pub fn get_resolver(&self, method: &str) -> Option<&dyn crate::DIDResolver> {
+        self.resolvers.get(method).map(Box::as_ref)
+    }
+
+    pub fn methods(&self) -> Vec<&str> {
+        let mut methods = self
+            .resolvers
+            .keys()
+            .map(String::as_str)
+            .collect::<Vec<&str>>();
+        methods.sort_unstable();
+        methods
+    }
+
+    fn route(&self, did: &str) -> Result<&dyn crate::DIDResolver, crate::error::ResolverError> {
+        let did = crate::did::Did::from_str(did)?;
+        let method = did.method();
+
+        self.get_resolver(method).ok_or_else(|| {
+            crate::error::ResolverError::MethodNotSupported(format!(
+                "did:{method} is not supported, supported methods are {}",
+                self.methods().join(", ")
+            ))
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::DIDResolver for UniversalResolver {
+    fn get_method(&self) -> &'static str {
+        self.default_method
+    }
+
+    async fn resolve(
+        &self,
+        did: String,
+    ) -> Result<crate::ResolveResponse, crate::error::ResolverError> {
+        self.route(&did)?.resolve(did).await
+    }
+
+    async fn resolve_with_options(
+        &self,
+        did: String,
+        options: crate::ResolutionOptions,
+    ) -> Result<crate::ResolveResponse, crate::error::ResolverError> {
+        self.route(&did)?.resolve_with_options(did, options).await
+    }
+
+    async fn resolve_representation(
+        &self,
+        did: String,
+        options: crate::ResolutionOptions,
+    ) -> Result<crate::ResolveRepresentationResponse, crate::error::ResolverError> {
+        self.route(&did)?.resolve_representation(did, options).await
+    }
+
+    async fn create(
+        &self,
+        did: String,
+        doc: serde_json::Value,
+    ) -> Result<(), crate::error::ResolverError> {
+        self.route(&did)?.create(did, doc).await
+    }
+
+    async fn update(
+        &self,
+        did: String,
+        signed_doc: serde_json::Value,
+    ) -> Result<(), crate::error::ResolverError> {
+        self.route(&did)?.update(did, signed_doc).await
+    }
+
+    async fn deactivate(
+        &self,
+        did: String,
+        signed_doc: serde_json::Value,
+    ) -> Result<(), crate::error::ResolverError> {
+        self.route(&did)?.deactivate(did, signed_doc).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UniversalResolver;
+    use crate::{error::ResolverError, DIDResolver, MockDIDResolver};
+
+    macro_rules! aw {
+        ($e:expr) => {
+            tokio_test::block_on($e)
+        };
+    }
+
+    fn create_did_doc(did: &str) -> serde_json::Value {
+        serde_json::json!({
+            "@context": ["https://www.w3.org/ns/did/v1"],
+            "id": did,
+        })
+    }
+
+    fn create_resolver_mock(method: &'static str) -> MockDIDResolver {
+        let mut resolver_mock = MockDIDResolver::default();
+        resolver_mock.expect_get_method().return_const(method);
+        resolver_mock.expect_resolve().returning(|did| {
+            Ok(crate::ResolveResponse {
+                did_document: create_did_doc(&did),
+                did_document_metadata: crate::DidDocumentMetadata::default(),
+                did_resolution_metadata: crate::ResolutionMetadata::default(),
+            })
+        });
+        resolver_mock
+    }
+
+    fn create_universal_resolver() -> UniversalResolver {
+        UniversalResolver::new(create_resolver_mock("knox"))
+            .with_resolver(create_resolver_mock("key"))
+    }
+
+    #[rstest::rstest]
+    #[case::default_method("did:knox:z6MkmgYPyjwqrMyHYBFfEcetAAoW7A9njsC4ToZ1WnjAgRL1")]
+    #[case::other_method("did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK")]
+    fn test_resolve(#[case] did: &str) {
+        let resolver = create_universal_resolver();
+
+        let rsp = aw!(resolver.resolve(did.to_string())).unwrap();
+        assert_eq!(rsp.did_document, create_did_doc(did));
+    }
+
+    #[rstest::rstest]
+    #[case::method_not_supported(
+        "did:web:example.com",
+        ResolverError::MethodNotSupported(
+            "did:web is not supported, supported methods are key, knox".to_string()
+        )
+    )]
+    #[case::invalid_did(
+        "did:knox",
+        ResolverError::InvalidDid("did:knox is not a valid DID".to_string())
+    )]
+    fn test_resolve_error(#[case] did: &str, #[case] expected: ResolverError) {
+        let resolver = create_universal_resolver();
+
+        assert_eq!(
+            aw!(resolver.resolve(did.to_string())).unwrap_err(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_create() {
+        let did = "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK";
+        let mut key_resolver = create_resolver_mock("key");
+        key_resolver
+            .expect_create()
+            .with(
+                mockall::predicate::eq(did.to_string()),
+                mockall::predicate::eq(create_did_doc(did)),
+            )
+            .return_once(|_, _| Ok(()));
+        let mut knox_resolver = create_resolver_mock("knox");
+        knox_resolver.expect_create().never();
+        let resolver = UniversalResolver::new(knox_resolver).with_resolver(key_resolver);
+
+        let res = aw!(resolver.create(did.to_string(), create_did_doc(did)));
+        assert_eq!(res, Ok(()));
+    }
+
+    #[test]
+    fn test_update() {
+        let did = "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK";
+        let mut key_resolver = create_resolver_mock("key");
+        key_resolver
+            .expect_update()
+            .with(
+                mockall::predicate::eq(did.to_string()),
+                mockall::predicate::eq(create_did_doc(did)),
+            )
+            .return_once(|_, _| Ok(()));
+        let resolver =
+            UniversalResolver::new(create_resolver_mock("knox")).with_resolver(key_resolver);
+
+        let res = aw!(resolver.update(did.to_string(), create_did_doc(did)));
+        assert_eq!(res, Ok(()));
+    }
+
+    #[test]
+    fn test_trait_object() {
+        let did = "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK";
+        let resolver: Box<dyn DIDResolver> = Box::new(create_universal_resolver());
+
+        let rsp = aw!(crate::identity::resolve(resolver.as_ref(), did)).unwrap();
+        assert_eq!(rsp.did_document.id, did);
+        assert_eq!(create_universal_resolver().get_method(), "knox");
+    }
+}
@@ -0,0 +1,467 @@
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_TTL: Duration = Duration::from_secs(300);
+pub const DEFAULT_NEGATIVE_TTL: Duration = Duration::from_secs(30);
+pub const DEFAULT_MAX_SIZE: usize = 1000;
+
+// Resolutions are cached per DID and resolution options, `noCache` only controls the lookup.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    did: String,
+    options: crate::ResolutionOptions,
+}
+
+#[derive(Debug, Clone)]
+enum CachedResolution {
+    Found(Box<crate::ResolveResponse>),
+    // Only `DocumentNotFound` is cached, other errors are usually transient.
+    NotFound(String),
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    resolution: CachedResolution,
+    expires_at: Instant,
+}
+
+/// A DIDResolver caching the resolutions of the wrapped resolver.
+/// Each entry expires after the TTL it was stored with, `DocumentNotFound` errors are cached with their own (usually
+/// shorter) TTL. Once `max_size` entries are cached, the entry closest to its expiry is evicted.
+/// The cache of a DID is invalidated by `create`, `update`, `deactivate` or explicitly with `invalidate`, and can be
+/// bypassed per request by setting `ResolutionOptions::no_cache`, in which case the fresh resolution replaces the cached
+/// one. Representations are not cached, `resolve_representation` always reaches the wrapped resolver.
+pub struct CachingResolver<R>
+where
+    R: crate::DIDResolver,
+{
+    inner: R,
+    ttl: Duration,
+    negative_ttl: Duration,
+    max_size: usize,
+    entries: std::sync::Mutex<std::collections::HashMap<CacheKey, CacheEntry>>,
+}
+
+impl<R> CachingResolver<R>
+where
+    R: crate::DIDResolver,
+{
+    pub fn new(inner: R) -> Self {
+        CachingResolver {
+            inner,
+            ttl: DEFAULT_TTL,
+            negative_ttl: DEFAULT_NEGATIVE_TTL,
+            max_size: DEFAULT_MAX_SIZE,
+            entries: std::sync::Mutex::new(std::collections::HashMap::new()),
+        }
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// TTL of cached `DocumentNotFound` errors, `Duration::ZERO` disables negative caching.
+    pub fn with_negative_ttl(mut self, negative_ttl: Duration) -> Self {
+        self.negative_ttl = negative_ttl;
+        self
+    }
+
+    /// Maximum number of cached resolutions, `0` disables caching.
+    pub fn with_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// Remove every cached resolution of `did`.
+    pub fn invalidate(&self, did: &str) {
+        let mut entries = self.lock_entries();
+        let len = entries.len();
+        entries.retain(|key, _| key.did != did);
+        tracing::debug!(did = %did, removed = len - entries.len(), "DID resolution cache invalidated");
+    }
+
+    pub fn clear(&self) {
+        self.lock_entries().clear();
+        tracing::debug!("DID resolution cache cleared");
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock_entries().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // The cache only holds resolutions, a panic while holding the lock can not leave it in an inconsistent state.
+    fn lock_entries(
+        &self,
+    ) -> std::sync::MutexGuard<'_, std::collections::HashMap<CacheKey, CacheEntry>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn get(&self, key: &CacheKey) -> Option<CachedResolution> {
+        let mut entries = self.lock_entries();
+        match entries.get(key) {
+            Some(entry) if Instant::now() < entry.expires_at => {
+                tracing::debug!(did = %key.did, "DID resolution cache hit");
+                Some(entry.resolution.clone())
+            }
+            Some(_) => {
+                tracing::debug!(did = %key.did, "DID resolution cache expired");
+                entries.remove(key);
+                None
+            }
+            None => {
+                tracing::debug!(did = %key.did, "DID resolution cache miss");
+                None
+            }
+        }
+    }
+
+    fn insert(&self, key: CacheKey, resolution: CachedResolution) {
+        let ttl = match resolution {
+            CachedResolution::Found(_) => self.ttl,
+            CachedResolution::NotFound(_) => self.negative_ttl,
+        };
+        if ttl.is_zero() || self.max_size == 0 {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut entries = self.lock_entries();
+        if !entries.contains_key(&key) && entries.len() >= self.max_size {
+            entries.retain(|_, entry| now < entry.expires_at);
+        }
+        if !entries.contains_key(&key) && entries.len() >= self.max_size {
+            let evicted = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.expires_at)
+                .map(|(key, _)| key.clone());
+            if let Some(evicted) = evicted {
+                tracing::debug!(did = %evicted.did, "DID resolution cache eviction");
+                entries.remove(&evicted);
+            }
+        }
+
+        entries.insert(
+            key,
+            CacheEntry {
+                resolution,
+                expires_at: now + ttl,
+            },
+        );
+    }
+}
+
+#[async_trait::async_trait]
+impl<R> crate::DIDResolver for CachingResolver<R>
+where
+    R: crate::DIDResolver,
+{
+    fn get_method(&self) -> &'static str {
+        self.inner.get_method()
+    }
+
+    async fn resolve(
+        &self,
+        did: String,
+    ) -> Result<crate::ResolveResponse, crate::error::ResolverError> {
+        self.resolve_with_options(did, crate::ResolutionOptions::default())
+            .await
+    }
+
+    async fn resolve_with_options(
+        &self,
+        did: String,
+        options: crate::ResolutionOptions,
+    ) -> Result<crate::ResolveResponse, crate::error::ResolverError> {
+        let key = CacheKey {
+            did: did.clone(),
+            options: crate::ResolutionOptions {
+                no_cache: false,
+                ..options.clone()
+            },
+        };
+
+        let cached = if options.no_cache {
+            tracing::debug!(did = %did, "DID resolution cache bypassed");
+            None
+        } else {
+            self.get(&key)
+        };
+
+        let resolution = match cached {
+            Some(resolution) => resolution,
+            None => match self.inner.resolve_with_options(did, options).await {
+                Ok(rsp) => {
+                    let rsp = Box::new(rsp);
+                    self.insert(key, CachedResolution::Found(rsp.clone()));
+                    CachedResolution::Found(rsp)
+                }
+                Err(crate::error::ResolverError::DocumentNotFound(message)) => {
+                    self.insert(key, CachedResolution::NotFound(message.clone()));
+                    CachedResolution::NotFound(message)
+                }
+                Err(e) => return Err(e),
+            },
+        };
+
+        match resolution {
+            CachedResolution::Found(rsp) => Ok(*rsp),
+            CachedResolution::NotFound(message) => {
+                Err(crate::error::ResolverError::DocumentNotFound(message))
+            }
+        }
+    }
+
+    async fn resolve_representation(
+        &self,
+        did: String,
+        options: crate::ResolutionOptions,
+    ) -> Result<crate::ResolveRepresentationResponse, crate::error::ResolverError> {
+        self.inner.resolve_representation(did, options).await
+    }
+
+    async fn create(
+        &self,
+        did: String,
+        doc: serde_json::Value,
+    ) -> Result<(), crate::error::ResolverError> {
+        let res = self.inner.create(did.clone(), doc).await;
+        // A cached `DocumentNotFound` would hide the newly created DID
+        self.invalidate(&did);
+        res
+    }
+
+    async fn update(
+        &self,
+        did: String,
+        signed_doc: serde_json::Value,
+    ) -> Result<(), crate::error::ResolverError> {
+        let res = self.inner.update(did.clone(), signed_doc).await;
+        self.invalidate(&did);
+        res
+    }
+
+    async fn deactivate(
+        &self,
+        did: String,
+        signed_doc: serde_json::Value,
+    ) -> Result<(), crate::error::ResolverError> {
+        let res = self.inner.deactivate(did.clone(), signed_doc).await;
+        self.invalidate(&did);
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CachingResolver, DEFAULT_NEGATIVE_TTL};
+    use crate::{error::ResolverError, DIDResolver, MockDIDResolver, ResolutionOptions};
+    use std::time::Duration;
+    use tracing_test::traced_test;
+
+    macro_rules! aw {
+        ($e:expr) => {
+            tokio_test::block_on($e)
+        };
+    }
+
+    const DID: &str = "did:knox:z6MkmgYPyjwqrMyHYBFfEcetAAoW7A9njsC4ToZ1WnjAgRL1";
+    const OTHER_DID: &str = "did:knox:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK";
+
+    fn create_resolve_response(did: &str) -> crate::ResolveResponse {
+        crate::ResolveResponse {
+            did_document: serde_json::json!({
+                "@context": ["https://www.w3.org/ns/did/v1"],
+                "id": did,
+            }),
+            did_document_metadata: crate::DidDocumentMetadata::default(),
+            did_resolution_metadata: crate::ResolutionMetadata::default(),
+        }
+    }
+
+    fn create_resolver_mock(times: usize) -> MockDIDResolver {
+        let mut resolver_mock = MockDIDResolver::default();
+        resolver_mock
+            .expect_resolve_with_options()
+            .times(times)
+            .returning(|did, _| Ok(create_resolve_response(&did)));
+        resolver_mock
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_resolve_cached() {
+        let resolver = CachingResolver::new(create_resolver_mock(1));
+
+        for _ in 0..3 {
+            let rsp = aw!(resolver.resolve(DID.to_string())).unwrap();
+            assert_eq!(rsp.did_document["id"], DID);
+        }
+        assert_eq!(resolver.len(), 1);
+        assert!(logs_contain("DID resolution cache miss"));
+        assert!(logs_contain("DID resolution cache hit"));
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_resolve_expired() {
+        let resolver =
+            CachingResolver::new(create_resolver_mock(2)).with_ttl(Duration::from_millis(10));
+
+        aw!(resolver.resolve(DID.to_string())).unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        aw!(resolver.resolve(DID.to_string())).unwrap();
+        assert!(logs_contain("DID resolution cache expired"));
+    }
+
+    #[rstest::rstest]
+    #[case::not_found_cached(
+        || ResolverError::DocumentNotFound("not found".to_string()),
+        DEFAULT_NEGATIVE_TTL,
+        1
+    )]
+    #[case::negative_caching_disabled(
+        || ResolverError::DocumentNotFound("not found".to_string()),
+        Duration::ZERO,
+        2
+    )]
+    #[case::network_failure_not_cached(
+        || ResolverError::NetworkFailure("unavailable".to_string()),
+        DEFAULT_NEGATIVE_TTL,
+        2
+    )]
+    fn test_resolve_error(
+        #[case] error: fn() -> ResolverError,
+        #[case] negative_ttl: Duration,
+        #[case] expected_calls: usize,
+    ) {
+        let mut resolver_mock = MockDIDResolver::default();
+        resolver_mock
+            .expect_resolve_with_options()
+            .times(expected_calls)
+            .returning(move |_, _| Err(error()));
+        let resolver = CachingResolver::new(resolver_mock).with_negative_ttl(negative_ttl);
+
+        for _ in 0..2 {
+            assert_eq!(aw!(resolver.resolve(DID.to_string())).unwrap_err(), error());
+        }
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_resolve_no_cache() {
+        let resolver = CachingResolver::new(create_resolver_mock(2));
+        let options = ResolutionOptions {
+            no_cache: true,
+            ..Default::default()
+        };
+
+        aw!(resolver.resolve(DID.to_string())).unwrap();
+        aw!(resolver.resolve_with_options(DID.to_string(), options)).unwrap();
+        // The bypassing request refreshed the cached resolution
+        aw!(resolver.resolve(DID.to_string())).unwrap();
+        assert!(logs_contain("DID resolution cache bypassed"));
+    }
+
+    #[test]
+    fn test_resolve_with_options() {
+        let resolver = CachingResolver::new(create_resolver_mock(2));
+        let options = ResolutionOptions {
+            accept: Some(crate::DID_LD_JSON_CONTENT_TYPE.to_string()),
+            ..Default::default()
+        };
+
+        aw!(resolver.resolve(DID.to_string())).unwrap();
+        aw!(resolver.resolve_with_options(DID.to_string(), options.clone())).unwrap();
+        aw!(resolver.resolve_with_options(DID.to_string(), options)).unwrap();
+        assert_eq!(resolver.len(), 2);
+    }
+
+    #[test]
+    fn test_invalidate() {
+        let resolver = CachingResolver::new(create_resolver_mock(3));
+
+        aw!(resolver.resolve(DID.to_string())).unwrap();
+        aw!(resolver.resolve(OTHER_DID.to_string())).unwrap();
+        resolver.invalidate(DID);
+        assert_eq!(resolver.len(), 1);
+        aw!(resolver.resolve(DID.to_string())).unwrap();
+        aw!(resolver.resolve(OTHER_DID.to_string())).unwrap();
+
+        resolver.clear();
+        assert!(resolver.is_empty());
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_max_size() {
+        let resolver = CachingResolver::new(create_resolver_mock(3)).with_max_size(1);
+
+        aw!(resolver.resolve(DID.to_string())).unwrap();
+        aw!(resolver.resolve(OTHER_DID.to_string())).unwrap();
+        aw!(resolver.resolve(DID.to_string())).unwrap();
+        assert_eq!(resolver.len(), 1);
+        assert!(logs_contain("DID resolution cache eviction"));
+    }
+
+    #[test]
+    fn test_create_invalidates() {
+        let mut resolver_mock = MockDIDResolver::default();
+        let mut seq = mockall::Sequence::new();
+        resolver_mock
+            .expect_resolve_with_options()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|did, _| Err(ResolverError::DocumentNotFound(did)));
+        resolver_mock
+            .expect_create()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _| Ok(()));
+        resolver_mock
+            .expect_resolve_with_options()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|did, _| Ok(create_resolve_response(&did)));
+        let resolver = CachingResolver::new(resolver_mock);
+
+        assert!(aw!(resolver.resolve(DID.to_string())).is_err());
+        aw!(resolver.create(DID.to_string(), create_resolve_response(DID).did_document)).unwrap();
+        assert!(aw!(resolver.resolve(DID.to_string())).is_ok());
+    }
+
+    #[test]
+    fn test_update_invalidates() {
+        let mut resolver_mock = MockDIDResolver::default();
+        let mut seq = mockall::Sequence::new();
+        resolver_mock
+            .expect_resolve_with_options()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|did, _| Ok(create_resolve_response(&did)));
+        resolver_mock
+            .expect_update()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _| Ok(()));
+        resolver_mock
+            .expect_resolve_with_options()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|did, _| Ok(create_resolve_response(&did)));
+        let resolver = CachingResolver::new(resolver_mock);
+
+        assert!(aw!(resolver.resolve(DID.to_string())).is_ok());
+        aw!(resolver.update(DID.to_string(), create_resolve_response(DID).did_document)).unwrap();
+        assert!(aw!(resolver.resolve(DID.to_string())).is_ok());
+    }
+}
@@ -0,0 +1,530 @@
+use std::time::{Duration, Instant};
+
+/// Retries of resolutions failing with a transient error (see `ResolverError::is_transient`).
+/// The n-th retry waits `initial_backoff * multiplier^(n - 1)`, capped at `max_backoff`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(2),
+            multiplier: 2.0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    pub fn get_backoff(&self, retry: u32) -> Duration {
+        let factor = self.multiplier.powi(retry.saturating_sub(1) as i32);
+        self.initial_backoff.mul_f64(factor).min(self.max_backoff)
+    }
+}
+
+/// The circuit opens after `failure_threshold` consecutive requests failed with a transient error, requests are then
+/// rejected without reaching the wrapped resolver until `reset_timeout` elapsed. The circuit is then half-open: the next
+/// request is let through alone while the others keep being rejected, and the circuit closes again when it succeeds and
+/// reopens when it fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitBreakerPolicy {
+    pub failure_threshold: u32,
+    pub reset_timeout: Duration,
+}
+
+impl Default for CircuitBreakerPolicy {
+    fn default() -> Self {
+        CircuitBreakerPolicy {
+            failure_threshold: 5,
+            reset_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct CircuitState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    // The `open_until` of the half-open circuit while the request it let through is in flight
+    probing: Option<Instant>,
+}
+
+// The request let through by the half-open circuit. Dropping it before its result is recorded, e.g. when the request
+// is cancelled, lets the next request through instead.
+struct CircuitProbe<'a> {
+    state: &'a std::sync::Mutex<CircuitState>,
+    open_until: Instant,
+}
+
+impl Drop for CircuitProbe<'_> {
+    fn drop(&mut self) {
+        let mut state = lock_circuit_state(self.state);
+        if state.probing == Some(self.open_until) {
+            state.probing = None;
+        }
+    }
+}
+
+fn lock_circuit_state(
+    state: &std::sync::Mutex<CircuitState>,
+) -> std::sync::MutexGuard<'_, CircuitState> {
+    state
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// A DIDResolver adding per-call timeouts, retries with exponential backoff and a circuit breaker to the wrapped
+/// resolver. Calls exceeding the timeout fail with `ResolverError::Timeout`.
+/// Only resolutions are retried, `create`, `update` and `deactivate` are not idempotent and are only subject to the
+/// timeout and circuit breaker.
+pub struct RetryResolver<R>
+where
+    R: crate::DIDResolver,
+{
+    inner: R,
+    timeout: Option<Duration>,
+    retry_policy: RetryPolicy,
+    circuit_breaker: Option<CircuitBreakerPolicy>,
+    circuit_state: std::sync::Mutex<CircuitState>,
+}
+
+impl<R> RetryResolver<R>
+where
+    R: crate::DIDResolver,
+{
+    pub fn new(inner: R) -> Self {
+        RetryResolver {
+            inner,
+            timeout: None,
+            retry_policy: RetryPolicy::default(),
+            circuit_breaker: None,
+            circuit_state: std::sync::Mutex::new(CircuitState::default()),
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn with_circuit_breaker(mut self, circuit_breaker: CircuitBreakerPolicy) -> Self {
+        self.circuit_breaker = Some(circuit_breaker);
+        self
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// Whether requests are rejected, i.e. the circuit is open or half-open with a request in flight.
+    pub fn is_circuit_open(&self) -> bool {
+        let state = lock_circuit_state(&self.circuit_state);
+        state.probing.is_some()
+            || state
+                .open_until
+                .is_some_and(|open_until| Instant::now() < open_until)
+    }
+
+    // Returns the probe to hold until the result is recorded when the request is let through by the half-open circuit
+    fn check_circuit(
+        &self,
+        did: &str,
+    ) -> Result<Option<CircuitProbe<'_>>, crate::error::ResolverError> {
+        if self.circuit_breaker.is_none() {
+            return Ok(None);
+        }
+
+        let mut state = lock_circuit_state(&self.circuit_state);
+        match state.open_until {
+            Some(open_until) if Instant::now() < open_until => {
+                tracing::debug!(did = %did, "DID resolver circuit open, request rejected");
+                Err(crate::error::ResolverError::NetworkFailure(format!(
+                    "Circuit breaker open, request for {did} rejected"
+                )))
+            }
+            Some(_) if state.probing.is_some() => {
+                tracing::debug!(did = %did, "DID resolver circuit half-open, request rejected");
+                Err(crate::error::ResolverError::NetworkFailure(format!(
+                    "Circuit breaker half-open, request for {did} rejected"
+                )))
+            }
+            Some(open_until) => {
+                tracing::debug!(did = %did, "DID resolver circuit half-open, request let through");
+                state.probing = Some(open_until);
+                Ok(Some(CircuitProbe {
+                    state: &self.circuit_state,
+                    open_until,
+                }))
+            }
+            None => Ok(None),
+        }
+    }
+
+    // Errors other than transient ones mean the resolver is reachable, so they close the circuit as well.
+    fn record_result<T>(&self, res: &Result<T, crate::error::ResolverError>) {
+        let Some(circuit_breaker) = &self.circuit_breaker else {
+            return;
+        };
+
+        let mut state = lock_circuit_state(&self.circuit_state);
+        match res {
+            Err(e) if e.is_transient() => {
+                state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+                if state.consecutive_failures >= circuit_breaker.failure_threshold {
+                    tracing::warn!(
+                        failures = state.consecutive_failures,
+                        "DID resolver circuit opened"
+                    );
+                    state.open_until = Some(Instant::now() + circuit_breaker.reset_timeout);
+                }
+            }
+            _ => {
+                if state.open_until.is_some() {
+                    tracing::info!("DID resolver circuit closed");
+                }
+                *state = CircuitState::default();
+            }
+        }
+    }
+
+    async fn call<T, F>(&self, did: &str, fut: F) -> Result<T, crate::error::ResolverError>
+    where
+        F: std::future::Future<Output = Result<T, crate::error::ResolverError>>,
+    {
+        match self.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, fut)
+                .await
+                .unwrap_or_else(|_| {
+                    Err(crate::error::ResolverError::Timeout(format!(
+                        "Request for {did} timed out after {timeout:?}"
+                    )))
+                }),
+            None => fut.await,
+        }
+    }
+
+    async fn call_with_retries<T, F, Fut>(
+        &self,
+        did: &str,
+        f: F,
+    ) -> Result<T, crate::error::ResolverError>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T, crate::error::ResolverError>>,
+    {
+        let _probe = self.check_circuit(did)?;
+
+        let mut retry = 0;
+        let res = loop {
+            match self.call(did, f()).await {
+                Err(e) if e.is_transient() && retry < self.retry_policy.max_retries => {
+                    retry += 1;
+                    let backoff = self.retry_policy.get_backoff(retry);
+                    tracing::warn!(did = %did, retry, ?backoff, error = %e, "Retrying DID resolution");
+                    tokio::time::sleep(backoff).await;
+                }
+                res => break res,
+            }
+        };
+
+        self.record_result(&res);
+        res
+    }
+}
+
+#[async_trait::async_trait]
+impl<R> crate::DIDResolver for RetryResolver<R>
+where
+    R: crate::DIDResolver,
+{
+    fn get_method(&self) -> &'static str {
+        self.inner.get_method()
+    }
+
+    async fn resolve(
+        &self,
+        did: String,
+    ) -> Result<crate::ResolveResponse, crate::error::ResolverError> {
+        self.resolve_with_options(did, crate::ResolutionOptions::default())
+            .await
+    }
+
+    async fn resolve_with_options(
+        &self,
+        did: String,
+        options: crate::ResolutionOptions,
+    ) -> Result<crate::ResolveResponse, crate::error::ResolverError> {
+        self.call_with_retries(&did, || {
+            self.inner
+                .resolve_with_options(did.clone(), options.clone())
+        })
+        .await
+    }
+
+    async fn resolve_representation(
+        &self,
+        did: String,
+        options: crate::ResolutionOptions,
+    ) -> Result<crate::ResolveRepresentationResponse, crate::error::ResolverError> {
+        self.call_with_retries(&did, || {
+            self.inner
+                .resolve_representation(did.clone(), options.clone())
+        })
+        .await
+    }
+
+    async fn create(
+        &self,
+        did: String,
+        doc: serde_json::Value,
+    ) -> Result<(), crate::error::ResolverError> {
+        let _probe = self.check_circuit(&did)?;
+
+        let res = self.call(&did, self.inner.create(did.clone(), doc)).await;
+        self.record_result(&res);
+        res
+    }
+
+    async fn update(
+        &self,
+        did: String,
+        signed_doc: serde_json::Value,
+    ) -> Result<(), crate::error::ResolverError> {
+        let _probe = self.check_circuit(&did)?;
+
+        let res = self
+            .call(&did, self.inner.update(did.clone(), signed_doc))
+            .await;
+        self.record_result(&res);
+        res
+    }
+
+    async fn deactivate(
+        &self,
+        did: String,
+        signed_doc: serde_json::Value,
+    ) -> Result<(), crate::error::ResolverError> {
+        let _probe = self.check_circuit(&did)?;
+
+        let res = self
+            .call(&did, self.inner.deactivate(did.clone(), signed_doc))
+            .await;
+        self.record_result(&res);
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CircuitBreakerPolicy, RetryPolicy, RetryResolver};
+    use crate::{error::ResolverError, DIDResolver, MockDIDResolver};
+    use std::time::Duration;
+
+    macro_rules! aw {
+        ($e:expr) => {
+            tokio_test::block_on($e)
+        };
+    }
+
+    const DID: &str = "did:knox:z6MkmgYPyjwqrMyHYBFfEcetAAoW7A9njsC4ToZ1WnjAgRL1";
+
+    fn create_retry_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+            multiplier: 2.0,
+        }
+    }
+
+    #[rstest::rstest]
+    #[case::first_retry(1, Duration::from_millis(100))]
+    #[case::second_retry(2, Duration::from_millis(200))]
+    #[case::third_retry(3, Duration::from_millis(400))]
+    #[case::capped(10, Duration::from_secs(2))]
+    fn test_get_backoff(#[case] retry: u32, #[case] expected: Duration) {
+        assert_eq!(RetryPolicy::default().get_backoff(retry), expected);
+    }
+
+    #[test]
+    fn test_create_not_retried() {
+        let mut resolver_mock = MockDIDResolver::default();
+        resolver_mock
+            .expect_create()
+            .times(1)
+            .returning(|_, _| Err(ResolverError::NetworkFailure("unavailable".to_string())));
+        let resolver = RetryResolver::new(resolver_mock).with_retry_policy(create_retry_policy());
+
+        let res = aw!(resolver.create(DID.to_string(), serde_json::json!({})));
+        assert!(matches!(res, Err(ResolverError::NetworkFailure(_))));
+    }
+
+    #[test]
+    fn test_circuit_breaker() {
+        let mut resolver_mock = MockDIDResolver::default();
+        let mut seq = mockall::Sequence::new();
+        // Both requests exhaust their retries and open the circuit
+        resolver_mock
+            .expect_resolve_with_options()
+            .times(2)
+            .in_sequence(&mut seq)
+            .returning(|_, _| Err(ResolverError::NetworkFailure("unavailable".to_string())));
+        resolver_mock
+            .expect_resolve_with_options()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|did, _| Err(ResolverError::DocumentNotFound(did)));
+        let resolver = RetryResolver::new(resolver_mock)
+            .with_retry_policy(RetryPolicy {
+                max_retries: 0,
+                ..create_retry_policy()
+            })
+            .with_circuit_breaker(CircuitBreakerPolicy {
+                failure_threshold: 2,
+                reset_timeout: Duration::from_millis(20),
+            });
+
+        for _ in 0..2 {
+            assert!(aw!(resolver.resolve(DID.to_string())).is_err());
+        }
+        assert!(resolver.is_circuit_open());
+        // Rejected without reaching the wrapped resolver
+        assert_eq!(
+            aw!(resolver.resolve(DID.to_string())).unwrap_err(),
+            ResolverError::NetworkFailure(format!(
+                "Circuit breaker open, request for {DID} rejected"
+            ))
+        );
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(matches!(
+            aw!(resolver.resolve(DID.to_string())),
+            Err(ResolverError::DocumentNotFound(_))
+        ));
+        assert!(!resolver.is_circuit_open());
+    }
+
+    // Fails until `available`, then answers after a delay so that concurrent requests overlap
+    #[derive(Debug, Default)]
+    struct RecoveringResolver {
+        available: std::sync::atomic::AtomicBool,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl DIDResolver for RecoveringResolver {
+        fn get_method(&self) -> &'static str {
+            "knox"
+        }
+
+        async fn resolve(&self, did: String) -> Result<crate::ResolveResponse, ResolverError> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if !self.available.load(std::sync::atomic::Ordering::SeqCst) {
+                return Err(ResolverError::NetworkFailure("unavailable".to_string()));
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Err(ResolverError::DocumentNotFound(did))
+        }
+
+        async fn create(&self, _did: String, _doc: serde_json::Value) -> Result<(), ResolverError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_circuit_breaker_half_open() {
+        let resolver = RetryResolver::new(RecoveringResolver::default())
+            .with_retry_policy(RetryPolicy::none())
+            .with_circuit_breaker(CircuitBreakerPolicy {
+                failure_threshold: 1,
+                reset_timeout: Duration::from_millis(20),
+            });
+        assert!(aw!(resolver.resolve(DID.to_string())).is_err());
+        assert!(resolver.is_circuit_open());
+
+        resolver
+            .inner()
+            .available
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(!resolver.is_circuit_open());
+
+        // Only the first of the concurrent requests reaches the recovered resolver
+        let resolve = || resolver.resolve(DID.to_string());
+        let results = aw!(async { tokio::join!(resolve(), resolve(), resolve(), resolve()) });
+        let results = [results.0, results.1, results.2, results.3];
+        assert!(matches!(
+            results[0],
+            Err(ResolverError::DocumentNotFound(_))
+        ));
+        for res in &results[1..] {
+            assert_eq!(
+                res.as_ref().unwrap_err(),
+                &ResolverError::NetworkFailure(format!(
+                    "Circuit breaker half-open, request for {DID} rejected"
+                ))
+            );
+        }
+        assert_eq!(
+            resolver
+                .inner()
+                .calls
+                .load(std::sync::atomic::Ordering::SeqCst),
+            2
+        );
+
+        // The probe succeeded and closed the circuit
+        assert!(!resolver.is_circuit_open());
+        assert!(matches!(
+            aw!(resolver.resolve(DID.to_string())),
+            Err(ResolverError::DocumentNotFound(_))
+        ));
+    }
+
+    #[derive(Debug)]
+    struct SlowResolver;
+
+    #[async_trait::async_trait]
+    impl DIDResolver for SlowResolver {
+        fn get_method(&self) -> &'static str {
+            "knox"
+        }
+
+        async fn resolve(&self, did: String) -> Result<crate::ResolveResponse, ResolverError> {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            Err(ResolverError::DocumentNotFound(did))
+        }
+
+        async fn create(&self, _did: String, _doc: serde_json::Value) -> Result<(), ResolverError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_timeout() {
+        let resolver = RetryResolver::new(SlowResolver)
+            .with_timeout(Duration::from_millis(5))
+            .with_retry_policy(create_retry_policy());
+
+        let res = aw!(resolver.resolve(DID.to_string()));
+        assert!(matches!(res, Err(ResolverError::Timeout(_))));
+    }
+}
@@ -1,9 +1,23 @@
+use std::str::FromStr;
+
 const DID_METHOD: &str = "ephemeral";
 
+// Every version of a DID document kept by the resolver, the last one being the current version.
+#[derive(Debug, Clone)]
+struct DidRecord {
+    versions: Vec<DidVersion>,
+    deactivated: bool,
+}
+
+#[derive(Debug, Clone)]
+struct DidVersion {
+    document: serde_json::Value,
+    timestamp: chrono::DateTime<chrono::Utc>,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct EphemeralResolver {
-    registry:
-        std::sync::Arc<tokio::sync::RwLock<std::collections::HashMap<String, serde_json::Value>>>,
+    registry: std::sync::Arc<tokio::sync::RwLock<std::collections::HashMap<String, DidRecord>>>,
 }
 
 impl EphemeralResolver {
@@ -16,6 +30,61 @@ impl EphemeralResolver {
     }
 }
 
+impl DidRecord {
+    // Version ids start at 1 and follow the order of the updates.
+    fn find_version(
+        &self,
+        options: &ssi_core::ResolutionOptions,
+    ) -> Result<usize, ssi_core::error::ResolverError> {
+        let index = match (&options.version_id, &options.version_time) {
+            (Some(version_id), _) => version_id
+                .parse::<usize>()
+                .ok()
+                .filter(|version| *version >= 1 && *version <= self.versions.len())
+                .map(|version| version - 1),
+            (None, Some(version_time)) => self
+                .versions
+                .iter()
+                .rposition(|version| version.timestamp <= *version_time),
+            (None, None) => Some(self.versions.len() - 1),
+        };
+
+        index.ok_or_else(|| {
+            ssi_core::error::ResolverError::DocumentNotFound(
+                "No document found with the requested version".to_string(),
+            )
+        })
+    }
+}
+
+// Changes to a DID must be signed with a capabilityInvocation key of the current version of its document and are
+// rejected once the DID is deactivated.
+fn get_authorized_record<'a>(
+    registry: &'a mut std::collections::HashMap<String, DidRecord>,
+    did: &str,
+    signed_doc: serde_json::Value,
+) -> Result<&'a mut DidRecord, ssi_core::error::ResolverError> {
+    let signed_doc = serde_json::from_value::<ssi_core::identity::SignedDidDocument>(signed_doc)
+        .map_err(|e| ssi_core::error::ResolverError::InvalidData(e.to_string()))?;
+
+    let record = registry.get_mut(did).ok_or_else(|| {
+        ssi_core::error::ResolverError::DocumentNotFound("No document found with did".to_string())
+    })?;
+    if record.deactivated {
+        return Err(ssi_core::error::ResolverError::Deactivated(format!(
+            "{did} is deactivated"
+        )));
+    }
+
+    let current_doc = serde_json::from_value::<ssi_core::identity::DidDocument>(
+        record.versions[record.versions.len() - 1].document.clone(),
+    )
+    .map_err(|e| ssi_core::error::ResolverError::InvalidData(e.to_string()))?;
+    signed_doc.verify_capability_invocation(&current_doc)?;
+
+    Ok(record)
+}
+
 #[async_trait::async_trait]
 impl ssi_core::DIDResolver for EphemeralResolver {
     fn get_method(&self) -> &'static str {
@@ -27,7 +96,45 @@ impl ssi_core::DIDResolver for EphemeralResolver {
         did: String,
         document: serde_json::Value,
     ) -> Result<(), ssi_core::error::ResolverError> {
-        self.registry.write().await.insert(did, document);
+        self.registry.write().await.insert(
+            did,
+            DidRecord {
+                versions: vec![DidVersion {
+                    document,
+                    timestamp: chrono::Utc::now(),
+                }],
+                deactivated: false,
+            },
+        );
+        Ok(())
+    }
+
+    // The update must be signed with a capabilityInvocation key of the current version of the document.
+    async fn update(
+        &self,
+        did: String,
+        signed_doc: serde_json::Value,
+    ) -> Result<(), ssi_core::error::ResolverError> {
+        let mut registry = self.registry.write().await;
+        let record = get_authorized_record(&mut registry, &did, signed_doc.clone())?;
+
+        let document = serde_json::from_value::<ssi_core::identity::SignedDidDocument>(signed_doc)
+            .and_then(|signed_doc| serde_json::to_value(signed_doc.document))
+            .map_err(|e| ssi_core::error::ResolverError::InvalidData(e.to_string()))?;
+        record.versions.push(DidVersion {
+            document,
+            timestamp: chrono::Utc::now(),
+        });
+        Ok(())
+    }
+
+    async fn deactivate(
+        &self,
+        did: String,
+        signed_doc: serde_json::Value,
+    ) -> Result<(), ssi_core::error::ResolverError> {
+        let mut registry = self.registry.write().await;
+        get_authorized_record(&mut registry, &did, signed_doc)?.deactivated = true;
         Ok(())
     }
 
@@ -35,33 +142,47 @@ impl ssi_core::DIDResolver for EphemeralResolver {
         &self,
         did: String,
     ) -> Result<ssi_core::ResolveResponse, ssi_core::error::ResolverError> {
-        let document = self
-            .registry
-            .read()
+        self.resolve_with_options(did, ssi_core::ResolutionOptions::default())
             .await
-            .get(&did)
-            .ok_or_else(|| {
-                ssi_core::error::ResolverError::DocumentNotFound(
-                    "No document found with did".to_string(),
-                )
-            })?
-            .clone();
+    }
+
+    // The ephemeral resolver is used for testing, so DIDs of any method are accepted.
+    async fn resolve_with_options(
+        &self,
+        did: String,
+        options: ssi_core::ResolutionOptions,
+    ) -> Result<ssi_core::ResolveResponse, ssi_core::error::ResolverError> {
+        ssi_core::did::Did::from_str(&did)?;
+        options.check_accept()?;
+
+        let registry = self.registry.read().await;
+        let record = registry.get(&did).ok_or_else(|| {
+            ssi_core::error::ResolverError::DocumentNotFound(
+                "No document found with did".to_string(),
+            )
+        })?;
+        let index = record.find_version(&options)?;
 
         Ok(ssi_core::ResolveResponse {
-            did_document: document,
+            did_document: record.versions[index].document.clone(),
             did_document_metadata: ssi_core::DidDocumentMetadata {
-                created: chrono::DateTime::parse_from_rfc3339("2021-01-01T00:00:00.000Z")
-                    .unwrap()
-                    .into(),
-                updated: chrono::DateTime::parse_from_rfc3339("2021-01-01T00:00:00.000Z")
-                    .unwrap()
-                    .into(),
+                created: record.versions[0].timestamp,
+                updated: record.versions[index].timestamp,
+                deactivated: record.deactivated.then_some(true),
+                version_id: Some((index + 1).to_string()),
+                next_update: record
+                    .versions
+                    .get(index + 1)
+                    .map(|version| version.timestamp),
+                equivalent_id: vec![],
             },
             did_resolution_metadata: ssi_core::ResolutionMetadata {
-                content_type: None,
-                duration: None,
-                did_url: None,
-                error: None,
+                content_type: Some(
+                    options
+                        .accept
+                        .unwrap_or_else(|| ssi_core::DID_LD_JSON_CONTENT_TYPE.to_string()),
+                ),
+                ..Default::default()
             },
         })
     }
@@ -155,6 +276,90 @@ mod tests {
         assert_ne!(retrieved_did_doc.did_document, did_doc);
     }
 
+    #[rstest::rstest]
+    #[case::latest(ssi_core::ResolutionOptions::default(), Ok(Some("1")))]
+    #[case::version_id(
+        ssi_core::ResolutionOptions {
+            version_id: Some("1".to_string()),
+            ..Default::default()
+        },
+        Ok(Some("1"))
+    )]
+    #[case::unknown_version_id(
+        ssi_core::ResolutionOptions {
+            version_id: Some("2".to_string()),
+            ..Default::default()
+        },
+        Err(ssi_core::error::ResolverError::DocumentNotFound(
+            "No document found with the requested version".to_string()
+        ))
+    )]
+    #[case::version_time_before_creation(
+        ssi_core::ResolutionOptions {
+            version_time: Some(chrono::DateTime::parse_from_rfc3339("2021-01-01T00:00:00.000Z").unwrap().into()),
+            ..Default::default()
+        },
+        Err(ssi_core::error::ResolverError::DocumentNotFound(
+            "No document found with the requested version".to_string()
+        ))
+    )]
+    #[case::unsupported_representation(
+        ssi_core::ResolutionOptions {
+            accept: Some("text/html".to_string()),
+            ..Default::default()
+        },
+        Err(ssi_core::error::ResolverError::RepresentationNotSupported(
+            "text/html is not a supported representation".to_string()
+        ))
+    )]
+    fn test_resolve_with_options(
+        #[case] options: ssi_core::ResolutionOptions,
+        #[case] expected_version: Result<Option<&str>, ssi_core::error::ResolverError>,
+    ) {
+        let public_key = get_public_key();
+        let did = create_did(public_key.clone());
+        let did_doc = create_did_doc(public_key);
+
+        let resolver = EphemeralResolver::new();
+        aw!(resolver.create(did.clone(), did_doc.clone())).unwrap();
+
+        let res = aw!(resolver.resolve_with_options(did, options));
+        match (res, expected_version) {
+            (Ok(rsp), Ok(version_id)) => {
+                assert_eq!(rsp.did_document, did_doc);
+                assert_eq!(rsp.did_document_metadata.version_id.as_deref(), version_id);
+                assert_eq!(rsp.did_document_metadata.deactivated, None);
+                assert_eq!(rsp.did_document_metadata.next_update, None);
+                assert_eq!(
+                    rsp.did_document_metadata.created,
+                    rsp.did_document_metadata.updated
+                );
+            }
+            (Err(e), Err(expected)) => assert_eq!(e, expected),
+            (res, expected) => panic!("unexpected result {res:?}, expected {expected:?}"),
+        }
+    }
+
+    #[rstest::rstest]
+    #[case::invalid_did(
+        "knox:123".to_string(),
+        ssi_core::error::ResolverError::InvalidDid("knox:123 is not a valid DID".to_string())
+    )]
+    #[case::not_found(
+        create_did(get_public_key()),
+        ssi_core::error::ResolverError::DocumentNotFound("No document found with did".to_string())
+    )]
+    fn test_resolve_error(#[case] did: String, #[case] expected: ssi_core::error::ResolverError) {
+        let resolver = EphemeralResolver::new();
+
+        let err = aw!(resolver.resolve(did)).unwrap_err();
+        assert_eq!(
+            ssi_core::ResolutionErrorCode::from(&err),
+            ssi_core::ResolutionErrorCode::from(&expected)
+        );
+        assert_eq!(err, expected);
+    }
+
     #[test]
     fn test_create() {
         let public_key = get_public_key();
@@ -169,4 +374,173 @@ mod tests {
 
         assert_eq!(did_doc, retrieved_did_doc.did_document);
     }
+
+    fn create_identity(
+        key_pair: signature::suite::ed25519_2020::Ed25519KeyPair,
+    ) -> ssi_core::identity::DidDocument {
+        aw!(ssi_core::identity::create_identity(
+            signature::suite::ed25519_2020::Ed25519DidVerifier::from(key_pair),
+            vec![]
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_update() {
+        let key_pair =
+            signature::suite::ed25519_2020::Ed25519KeyPair::new("knox".to_string(), None).unwrap();
+        let signer = signature::suite::ed25519_2020::Ed25519DidSigner::from(key_pair.clone());
+        let did_doc = create_identity(key_pair);
+        let did = did_doc.id.clone();
+
+        let resolver = EphemeralResolver::new();
+        aw!(resolver.create(did.clone(), serde_json::to_value(&did_doc).unwrap())).unwrap();
+
+        let updated_doc = ssi_core::identity::DidDocument {
+            also_known_as: vec!["https://example.com/alice".to_string()],
+            ..did_doc.clone()
+        };
+        let signed_doc = updated_doc
+            .clone()
+            .try_into_signed_document(&signer)
+            .unwrap();
+        aw!(resolver.update(did.clone(), serde_json::to_value(signed_doc).unwrap())).unwrap();
+
+        let rsp = aw!(resolver.resolve(did.clone())).unwrap();
+        assert_eq!(
+            rsp.did_document,
+            serde_json::to_value(&updated_doc).unwrap()
+        );
+        assert_eq!(rsp.did_document_metadata.version_id.as_deref(), Some("2"));
+
+        // The previous version is still resolvable
+        let rsp = aw!(resolver.resolve_with_options(
+            did,
+            ssi_core::ResolutionOptions {
+                version_id: Some("1".to_string()),
+                ..Default::default()
+            }
+        ))
+        .unwrap();
+        assert_eq!(rsp.did_document, serde_json::to_value(&did_doc).unwrap());
+    }
+
+    #[test]
+    fn test_update_unauthorized() {
+        let key_pair =
+            signature::suite::ed25519_2020::Ed25519KeyPair::new("knox".to_string(), None).unwrap();
+        let did_doc = create_identity(key_pair);
+        let did = did_doc.id.clone();
+
+        let resolver = EphemeralResolver::new();
+        aw!(resolver.create(did.clone(), serde_json::to_value(&did_doc).unwrap())).unwrap();
+
+        let other_signer = signature::suite::ed25519_2020::Ed25519DidSigner::from(
+            signature::suite::ed25519_2020::Ed25519KeyPair::new("knox".to_string(), None).unwrap(),
+        );
+        let signed_doc = did_doc.try_into_signed_document(&other_signer).unwrap();
+        let res = aw!(resolver.update(did.clone(), serde_json::to_value(signed_doc).unwrap()));
+        assert!(matches!(
+            res,
+            Err(ssi_core::error::ResolverError::Unauthorized(_))
+        ));
+
+        let rsp = aw!(resolver.resolve(did)).unwrap();
+        assert_eq!(rsp.did_document_metadata.version_id.as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn test_update_not_found() {
+        let resolver = EphemeralResolver::new();
+        let signer = signature::suite::ed25519_2020::Ed25519DidSigner::from(
+            signature::suite::ed25519_2020::Ed25519KeyPair::new("knox".to_string(), None).unwrap(),
+        );
+        let did_doc = create_identity(
+            signature::suite::ed25519_2020::Ed25519KeyPair::new("knox".to_string(), None).unwrap(),
+        );
+        let did = did_doc.id.clone();
+        let signed_doc = did_doc.try_into_signed_document(&signer).unwrap();
+
+        let res = aw!(resolver.update(did, serde_json::to_value(signed_doc).unwrap()));
+        assert_eq!(
+            res,
+            Err(ssi_core::error::ResolverError::DocumentNotFound(
+                "No document found with did".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_deactivate() {
+        let key_pair =
+            signature::suite::ed25519_2020::Ed25519KeyPair::new("knox".to_string(), None).unwrap();
+        let signer = signature::suite::ed25519_2020::Ed25519DidSigner::from(key_pair.clone());
+        let did_doc = create_identity(key_pair);
+        let did = did_doc.id.clone();
+
+        let resolver = EphemeralResolver::new();
+        aw!(resolver.create(did.clone(), serde_json::to_value(&did_doc).unwrap())).unwrap();
+
+        let other_signer = signature::suite::ed25519_2020::Ed25519DidSigner::from(
+            signature::suite::ed25519_2020::Ed25519KeyPair::new("knox".to_string(), None).unwrap(),
+        );
+        let res = aw!(ssi_core::identity::deactivate_identity(
+            &resolver,
+            did_doc.clone(),
+            &other_signer
+        ));
+        assert!(res.is_err());
+        let rsp = aw!(resolver.resolve(did.clone())).unwrap();
+        assert_eq!(rsp.did_document_metadata.deactivated, None);
+
+        aw!(ssi_core::identity::deactivate_identity(
+            &resolver,
+            did_doc.clone(),
+            &signer
+        ))
+        .unwrap();
+        let rsp = aw!(resolver.resolve(did.clone())).unwrap();
+        assert_eq!(rsp.did_document_metadata.deactivated, Some(true));
+
+        // A deactivated DID can neither be updated nor deactivated again
+        let signed_doc =
+            serde_json::to_value(did_doc.try_into_signed_document(&signer).unwrap()).unwrap();
+        assert!(matches!(
+            aw!(resolver.update(did.clone(), signed_doc.clone())),
+            Err(ssi_core::error::ResolverError::Deactivated(_))
+        ));
+        assert!(matches!(
+            aw!(resolver.deactivate(did, signed_doc)),
+            Err(ssi_core::error::ResolverError::Deactivated(_))
+        ));
+    }
+
+    #[rstest::rstest]
+    #[case::default(None, ssi_core::DID_LD_JSON_CONTENT_TYPE)]
+    #[case::json(
+        Some(ssi_core::DID_JSON_CONTENT_TYPE.to_string()),
+        ssi_core::DID_JSON_CONTENT_TYPE
+    )]
+    fn test_resolve_representation(#[case] accept: Option<String>, #[case] expected: &str) {
+        let public_key = get_public_key();
+        let did = create_did(public_key.clone());
+        let did_doc = create_did_doc(public_key);
+
+        let resolver = EphemeralResolver::new();
+        aw!(resolver.create(did.clone(), did_doc.clone())).unwrap();
+
+        let rsp = aw!(resolver.resolve_representation(
+            did,
+            ssi_core::ResolutionOptions {
+                accept,
+                ..Default::default()
+            }
+        ))
+        .unwrap();
+        assert_eq!(rsp.content_type(), Some(expected));
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&rsp.did_document_stream).unwrap(),
+            did_doc
+        );
+    }
 }
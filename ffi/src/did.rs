@@ -104,7 +104,7 @@ pub fn create_identity(
             .report("failed to create runtime")
             .expect("unable to launch runtime");
         let did_doc =
-            rt.block_on(async move { ssi_core::identity::create_identity(verifier).await });
+            rt.block_on(async move { ssi_core::identity::create_identity(verifier, vec![]).await });
 
         Ok(did_doc)
     });
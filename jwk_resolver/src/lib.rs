@@ -0,0 +1,233 @@
+use std::str::FromStr;
+
+// Implementation of https://github.com/quartzjer/did-jwk/blob/main/spec.md
+// The DID Document is derived from the JWK encoded in the DID, so no network access is needed.
+pub const DID_METHOD: &str = "jwk";
+
+const JSON_WEB_KEY_2020: &str = "JsonWebKey2020";
+
+#[derive(Debug, Clone, Default)]
+pub struct JwkResolver;
+
+impl JwkResolver {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl ssi_core::DIDResolver for JwkResolver {
+    fn get_method(&self) -> &'static str {
+        DID_METHOD
+    }
+
+    async fn create(
+        &self,
+        _did: String,
+        _document: serde_json::Value,
+    ) -> Result<(), ssi_core::error::ResolverError> {
+        Err(ssi_core::error::ResolverError::MethodNotSupported(
+            "did:jwk documents are derived from the key and can not be created".to_string(),
+        ))
+    }
+
+    async fn resolve(
+        &self,
+        did: String,
+    ) -> Result<ssi_core::ResolveResponse, ssi_core::error::ResolverError> {
+        let document = create_did_document(&did)?;
+        let document = serde_json::to_value(document)
+            .map_err(|e| ssi_core::error::ResolverError::InvalidData(e.to_string()))?;
+
+        Ok(ssi_core::ResolveResponse {
+            did_document: document,
+            did_document_metadata: ssi_core::DidDocumentMetadata::default(),
+            did_resolution_metadata: ssi_core::ResolutionMetadata {
+                content_type: Some(ssi_core::DID_LD_JSON_CONTENT_TYPE.to_string()),
+                ..Default::default()
+            },
+        })
+    }
+}
+
+// Given a did:jwk, decode the JWK and expand it into its DID Document.
+// Keys restricted to signing (`"use": "sig"`) are not used for key agreement and vice versa (`"use": "enc"`).
+fn create_did_document(
+    did: &str,
+) -> Result<ssi_core::identity::DidDocument, ssi_core::error::ResolverError> {
+    let parsed_did = ssi_core::did::Did::from_str(did)?;
+    if parsed_did.method() != DID_METHOD {
+        return Err(ssi_core::error::ResolverError::MethodNotSupported(format!(
+            "did:{} is not supported by the did:jwk resolver",
+            parsed_did.method()
+        )));
+    }
+
+    let invalid_jwk = |reason: String| {
+        ssi_core::error::ResolverError::InvalidDid(format!("{did} has an invalid JWK: {reason}"))
+    };
+
+    let jwk = base64::decode_config(parsed_did.method_specific_id(), base64::URL_SAFE_NO_PAD)
+        .map_err(|e| invalid_jwk(e.to_string()))?;
+    let jwk: std::collections::HashMap<String, serde_json::Value> =
+        serde_json::from_slice(&jwk).map_err(|e| invalid_jwk(e.to_string()))?;
+
+    if !jwk.get("kty").is_some_and(serde_json::Value::is_string) {
+        return Err(invalid_jwk("kty is required".to_string()));
+    }
+    if jwk.contains_key("d") {
+        return Err(invalid_jwk("private keys can not be used".to_string()));
+    }
+
+    let key_use = jwk.get("use").and_then(serde_json::Value::as_str);
+    let verification_method = ssi_core::identity::KeyMaterial {
+        id: format!("{did}#0"),
+        proof_type: JSON_WEB_KEY_2020.to_string(),
+        controller: did.to_string(),
+        public_key_multibase: None,
+        public_key_jwk: Some(jwk.clone()),
+        property_set: std::collections::HashMap::new(),
+    };
+    let reference = || {
+        vec![ssi_core::identity::VerificationMethodEntry::Reference(
+            verification_method.id.clone(),
+        )]
+    };
+
+    let (signing, key_agreement) = match key_use {
+        Some("sig") => (reference(), vec![]),
+        Some("enc") => (vec![], reference()),
+        _ => (reference(), reference()),
+    };
+
+    Ok(ssi_core::identity::DidDocument {
+        context: ssi_core::identity::DidContext::Set(vec![
+            ssi_core::credential::ContextValue::String(ssi_core::identity::DID_CONTEXT.to_string()),
+            ssi_core::credential::ContextValue::String(
+                ssi_core::identity::JWS_2020_CONTEXT.to_string(),
+            ),
+        ]),
+        id: did.to_string(),
+        authentication: signing.clone(),
+        assertion_method: signing.clone(),
+        capability_invocation: signing.clone(),
+        capability_delegation: signing,
+        key_agreement,
+        verification_method: vec![verification_method],
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::JwkResolver;
+    use ssi_core::DIDResolver;
+
+    macro_rules! aw {
+        ($e:expr) => {
+            tokio_test::block_on($e)
+        };
+    }
+
+    // Example from https://github.com/quartzjer/did-jwk/blob/main/spec.md#examples
+    fn get_p256_did() -> String {
+        String::from("did:jwk:eyJjcnYiOiJQLTI1NiIsImt0eSI6IkVDIiwieCI6ImFjYklRaXVNczNpOF91c3pFakoydHBUdFJNNEVVM3l6OTFQSDZDZEgyVjAiLCJ5IjoiX0tjeUxqOXZXTXB0bm1LdG00NkdxRHo4d2Y3NEk1TEtncmwyR3pIM25TRSJ9")
+    }
+
+    fn get_p256_did_doc() -> serde_json::Value {
+        let did = get_p256_did();
+        serde_json::json!({
+            "@context": [
+                "https://www.w3.org/ns/did/v1",
+                "https://w3id.org/security/suites/jws-2020/v1"
+            ],
+            "id": did,
+            "verificationMethod": [{
+                "id": format!("{did}#0"),
+                "type": "JsonWebKey2020",
+                "controller": did,
+                "publicKeyJwk": {
+                    "crv": "P-256",
+                    "kty": "EC",
+                    "x": "acbIQiuMs3i8_uszEjJ2tpTtRM4EU3yz91PH6CdH2V0",
+                    "y": "_KcyLj9vWMptnmKtm46GqDz8wf74I5LKgrl2GzH3nSE"
+                }
+            }],
+            "assertionMethod": [format!("{did}#0")],
+            "authentication": [format!("{did}#0")],
+            "capabilityInvocation": [format!("{did}#0")],
+            "capabilityDelegation": [format!("{did}#0")],
+            "keyAgreement": [format!("{did}#0")]
+        })
+    }
+
+    #[test]
+    fn test_resolve() {
+        let resolver = JwkResolver::new();
+
+        let rsp = aw!(resolver.resolve(get_p256_did())).unwrap();
+        assert_eq!(rsp.did_document, get_p256_did_doc());
+    }
+
+    #[test]
+    fn test_resolve_key_pair_did() {
+        let kp =
+            signature::suite::ed25519_2020::Ed25519KeyPair::new("jwk".to_string(), None).unwrap();
+        let did = kp.get_did_jwk();
+        let resolver = JwkResolver::new();
+
+        let rsp = aw!(resolver.resolve(did.clone())).unwrap();
+        let doc = ssi_core::identity::DidDocument::try_from(rsp.did_document).unwrap();
+
+        assert_eq!(doc.id, did);
+        assert_eq!(
+            doc.verification_method[0].public_key_jwk,
+            Some(
+                serde_json::from_value(serde_json::to_value(kp.get_public_key_jwk()).unwrap())
+                    .unwrap()
+            )
+        );
+    }
+
+    #[rstest::rstest]
+    #[case::signing_key(serde_json::json!({"kty": "OKP", "crv": "Ed25519", "use": "sig", "x": "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"}), true, false)]
+    #[case::encryption_key(serde_json::json!({"kty": "OKP", "crv": "X25519", "use": "enc", "x": "3p7bfXt9wbTTW2HC7OQ1Nz-DQ8hbeGdNrfx-FG-IK08"}), false, true)]
+    fn test_resolve_key_use(
+        #[case] jwk: serde_json::Value,
+        #[case] expect_signing: bool,
+        #[case] expect_key_agreement: bool,
+    ) {
+        let did = format!(
+            "did:jwk:{}",
+            base64::encode_config(jwk.to_string(), base64::URL_SAFE_NO_PAD)
+        );
+        let resolver = JwkResolver::new();
+
+        let rsp = aw!(resolver.resolve(did)).unwrap();
+        let doc = ssi_core::identity::DidDocument::try_from(rsp.did_document).unwrap();
+
+        assert_eq!(!doc.assertion_method.is_empty(), expect_signing);
+        assert_eq!(!doc.key_agreement.is_empty(), expect_key_agreement);
+    }
+
+    #[rstest::rstest]
+    #[case::invalid_base64("did:jwk:e30=", "InvalidDid")]
+    #[case::missing_kty("did:jwk:e30", "InvalidDid")]
+    #[case::private_key(
+        &format!("did:jwk:{}", base64::encode_config(r#"{"kty":"OKP","crv":"Ed25519","x":"11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo","d":"nWGxne_9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A"}"#, base64::URL_SAFE_NO_PAD)),
+        "InvalidDid"
+    )]
+    #[case::method_not_supported(
+        "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK",
+        "MethodNotSupported"
+    )]
+    fn test_resolve_error(#[case] did: &str, #[case] expected_kind: &str) {
+        let resolver = JwkResolver::new();
+
+        match (aw!(resolver.resolve(did.to_string())), expected_kind) {
+            (Err(ssi_core::error::ResolverError::InvalidDid(_)), "InvalidDid") => {}
+            (Err(ssi_core::error::ResolverError::MethodNotSupported(_)), "MethodNotSupported") => {}
+            (res, expected) => panic!("unexpected result {res:?}, expected {expected}"),
+        }
+    }
+}
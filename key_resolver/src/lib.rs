@@ -0,0 +1,300 @@
+use std::str::FromStr;
+
+// Implementation of https://w3c-ccg.github.io/did-method-key/
+// The DID Document is derived from the public key encoded in the DID, so no network access is needed.
+pub const DID_METHOD: &str = "key";
+
+const ED25519_VERIFICATION_KEY_2020: &str = "Ed25519VerificationKey2020";
+const X25519_KEY_AGREEMENT_KEY_2020: &str = "X25519KeyAgreementKey2020";
+
+use signature::suite::ed25519_2020::{did::MULTICODEC_X25519_PUB, MULTICODEC_ED25519_PUB};
+
+#[derive(Debug, Clone, Default)]
+pub struct KeyResolver;
+
+impl KeyResolver {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl ssi_core::DIDResolver for KeyResolver {
+    fn get_method(&self) -> &'static str {
+        DID_METHOD
+    }
+
+    async fn create(
+        &self,
+        _did: String,
+        _document: serde_json::Value,
+    ) -> Result<(), ssi_core::error::ResolverError> {
+        Err(ssi_core::error::ResolverError::MethodNotSupported(
+            "did:key documents are derived from the key and can not be created".to_string(),
+        ))
+    }
+
+    async fn resolve(
+        &self,
+        did: String,
+    ) -> Result<ssi_core::ResolveResponse, ssi_core::error::ResolverError> {
+        let document = create_did_document(&did)?;
+        let document = serde_json::to_value(document)
+            .map_err(|e| ssi_core::error::ResolverError::InvalidData(e.to_string()))?;
+
+        Ok(ssi_core::ResolveResponse {
+            did_document: document,
+            did_document_metadata: ssi_core::DidDocumentMetadata::default(),
+            did_resolution_metadata: ssi_core::ResolutionMetadata {
+                content_type: Some(ssi_core::DID_LD_JSON_CONTENT_TYPE.to_string()),
+                ..Default::default()
+            },
+        })
+    }
+}
+
+// Given a did:key, decode the multicodec-prefixed public key and expand it into its DID Document.
+fn create_did_document(
+    did: &str,
+) -> Result<ssi_core::identity::DidDocument, ssi_core::error::ResolverError> {
+    let parsed_did = ssi_core::did::Did::from_str(did)?;
+    if parsed_did.method() != DID_METHOD {
+        return Err(ssi_core::error::ResolverError::MethodNotSupported(format!(
+            "did:{} is not supported by the did:key resolver",
+            parsed_did.method()
+        )));
+    }
+
+    create_key_did_document(did, parsed_did.method_specific_id())
+}
+
+/// Given a DID and the multibase encoded, multicodec-prefixed public key it was derived from, expand the key into
+/// the DID Document defined by did:key. Also used by methods that embed a did:key, such as did:peer:0.
+pub fn create_key_did_document(
+    did: &str,
+    encoded_key: &str,
+) -> Result<ssi_core::identity::DidDocument, ssi_core::error::ResolverError> {
+    let invalid_key = |reason: &str| {
+        ssi_core::error::ResolverError::InvalidDid(format!("{did} has an invalid key: {reason}"))
+    };
+
+    let (base, key) = multibase::decode(encoded_key).map_err(|e| invalid_key(&e.to_string()))?;
+    if base != multibase::Base::Base58Btc {
+        return Err(invalid_key("key must be base58btc encoded"));
+    }
+
+    if let Some(public_key) = key.strip_prefix(MULTICODEC_ED25519_PUB) {
+        let x25519_public_key =
+            signature::suite::ed25519_2020::did::derive_x25519_public_key(public_key)
+                .map_err(|_| invalid_key("not a valid Ed25519 public key"))?;
+        let verification_method =
+            create_key_material(did, ED25519_VERIFICATION_KEY_2020, encoded_key.to_string());
+        let key_agreement = create_key_material(
+            did,
+            X25519_KEY_AGREEMENT_KEY_2020,
+            multibase::encode(
+                multibase::Base::Base58Btc,
+                [MULTICODEC_X25519_PUB, x25519_public_key.as_slice()].concat(),
+            ),
+        );
+
+        Ok(ssi_core::identity::DidDocument {
+            context: ssi_core::identity::DidContext::Set(vec![
+                ssi_core::credential::ContextValue::String(
+                    ssi_core::identity::DID_CONTEXT.to_string(),
+                ),
+                ssi_core::credential::ContextValue::String(
+                    ssi_core::identity::ED25519_2020_CONTEXT.to_string(),
+                ),
+                ssi_core::credential::ContextValue::String(
+                    ssi_core::identity::X25519_2020_CONTEXT.to_string(),
+                ),
+            ]),
+            id: did.to_string(),
+            authentication: vec![reference(&verification_method)],
+            assertion_method: vec![reference(&verification_method)],
+            capability_invocation: vec![reference(&verification_method)],
+            capability_delegation: vec![reference(&verification_method)],
+            key_agreement: vec![reference(&key_agreement)],
+            verification_method: vec![verification_method, key_agreement],
+            ..Default::default()
+        })
+    } else if let Some(public_key) = key.strip_prefix(MULTICODEC_X25519_PUB) {
+        if public_key.len() != 32 {
+            return Err(invalid_key("not a valid X25519 public key"));
+        }
+        let key_agreement =
+            create_key_material(did, X25519_KEY_AGREEMENT_KEY_2020, encoded_key.to_string());
+
+        Ok(ssi_core::identity::DidDocument {
+            context: ssi_core::identity::DidContext::Set(vec![
+                ssi_core::credential::ContextValue::String(
+                    ssi_core::identity::DID_CONTEXT.to_string(),
+                ),
+                ssi_core::credential::ContextValue::String(
+                    ssi_core::identity::X25519_2020_CONTEXT.to_string(),
+                ),
+            ]),
+            id: did.to_string(),
+            key_agreement: vec![reference(&key_agreement)],
+            verification_method: vec![key_agreement],
+            ..Default::default()
+        })
+    } else {
+        Err(invalid_key("unsupported public key type"))
+    }
+}
+
+fn create_key_material(
+    did: &str,
+    key_type: &str,
+    public_key_multibase: String,
+) -> ssi_core::identity::KeyMaterial {
+    ssi_core::identity::KeyMaterial {
+        id: format!("{did}#{public_key_multibase}"),
+        proof_type: key_type.to_string(),
+        controller: did.to_string(),
+        public_key_multibase: Some(public_key_multibase),
+        public_key_jwk: None,
+        property_set: std::collections::HashMap::new(),
+    }
+}
+
+fn reference(
+    method: &ssi_core::identity::KeyMaterial,
+) -> ssi_core::identity::VerificationMethodEntry {
+    ssi_core::identity::VerificationMethodEntry::Reference(method.id.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::KeyResolver;
+    use ssi_core::DIDResolver;
+
+    macro_rules! aw {
+        ($e:expr) => {
+            tokio_test::block_on($e)
+        };
+    }
+
+    fn get_ed25519_did() -> String {
+        String::from("did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK")
+    }
+
+    fn get_ed25519_did_doc() -> serde_json::Value {
+        serde_json::json!({
+            "@context": [
+                "https://www.w3.org/ns/did/v1",
+                "https://w3id.org/security/suites/ed25519-2020/v1",
+                "https://w3id.org/security/suites/x25519-2020/v1"
+            ],
+            "id": "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK",
+            "verificationMethod": [{
+                "id": "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK#z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK",
+                "type": "Ed25519VerificationKey2020",
+                "controller": "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK",
+                "publicKeyMultibase": "z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK"
+            }, {
+                "id": "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK#z6LSj72tK8brWgZja8NLRwPigth2T9QRiG1uH9oKZuKjdh9p",
+                "type": "X25519KeyAgreementKey2020",
+                "controller": "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK",
+                "publicKeyMultibase": "z6LSj72tK8brWgZja8NLRwPigth2T9QRiG1uH9oKZuKjdh9p"
+            }],
+            "authentication": ["did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK#z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK"],
+            "assertionMethod": ["did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK#z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK"],
+            "capabilityInvocation": ["did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK#z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK"],
+            "capabilityDelegation": ["did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK#z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK"],
+            "keyAgreement": ["did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK#z6LSj72tK8brWgZja8NLRwPigth2T9QRiG1uH9oKZuKjdh9p"]
+        })
+    }
+
+    #[test]
+    fn test_resolve_ed25519() {
+        let resolver = KeyResolver::new();
+
+        let rsp = aw!(resolver.resolve(get_ed25519_did())).unwrap();
+        assert_eq!(rsp.did_document, get_ed25519_did_doc());
+
+        // Resolution is deterministic
+        let rsp = aw!(resolver.resolve(get_ed25519_did())).unwrap();
+        assert_eq!(rsp.did_document, get_ed25519_did_doc());
+
+        let doc = ssi_core::identity::DidDocument::try_from(rsp.did_document).unwrap();
+        assert_eq!(doc.id, get_ed25519_did());
+    }
+
+    #[test]
+    fn test_resolve_x25519() {
+        let did = "did:key:z6LSj72tK8brWgZja8NLRwPigth2T9QRiG1uH9oKZuKjdh9p".to_string();
+        let resolver = KeyResolver::new();
+
+        let rsp = aw!(resolver.resolve(did.clone())).unwrap();
+        let doc = ssi_core::identity::DidDocument::try_from(rsp.did_document).unwrap();
+
+        assert_eq!(doc.verification_method.len(), 1);
+        assert_eq!(
+            doc.verification_method[0].proof_type,
+            "X25519KeyAgreementKey2020"
+        );
+        assert_eq!(doc.key_agreement.len(), 1);
+        assert!(doc.authentication.is_empty());
+    }
+
+    #[test]
+    fn test_resolve_key_pair_did() {
+        let kp =
+            signature::suite::ed25519_2020::Ed25519KeyPair::new("key".to_string(), None).unwrap();
+        let did = signature::suite::KeyPair::get_did(&kp);
+        let resolver = KeyResolver::new();
+
+        let rsp = aw!(resolver.resolve(did.clone())).unwrap();
+        let doc = ssi_core::identity::DidDocument::try_from(rsp.did_document).unwrap();
+
+        assert_eq!(doc.id, did);
+        assert_eq!(
+            doc.verification_method[0].public_key_multibase,
+            Some(signature::suite::KeyPair::get_encoded_master_public_key(
+                &kp
+            ))
+        );
+    }
+
+    #[rstest::rstest]
+    #[case::invalid_did(
+        "did:key".to_string(),
+        ssi_core::error::ResolverError::InvalidDid("did:key is not a valid DID".to_string())
+    )]
+    #[case::method_not_supported(
+        "did:knox:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK".to_string(),
+        ssi_core::error::ResolverError::MethodNotSupported("did:knox is not supported by the did:key resolver".to_string())
+    )]
+    #[case::not_base58btc(
+        "did:key:f6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK".to_string(),
+        ssi_core::error::ResolverError::InvalidDid("did:key:f6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK has an invalid key: Invalid base string".to_string())
+    )]
+    #[case::unsupported_key_type(
+        "did:key:zQ3shokFTS3brHcDQrn82RUDfCZESWL1ZdCEJwekUDPQiYBme".to_string(),
+        ssi_core::error::ResolverError::InvalidDid("did:key:zQ3shokFTS3brHcDQrn82RUDfCZESWL1ZdCEJwekUDPQiYBme has an invalid key: unsupported public key type".to_string())
+    )]
+    fn test_resolve_error(#[case] did: String, #[case] expected: ssi_core::error::ResolverError) {
+        let resolver = KeyResolver::new();
+
+        assert_eq!(aw!(resolver.resolve(did)).unwrap_err(), expected);
+    }
+
+    #[test]
+    fn test_create() {
+        let resolver = KeyResolver::new();
+
+        let res = aw!(resolver.create(get_ed25519_did(), get_ed25519_did_doc()));
+        assert!(matches!(
+            res,
+            Err(ssi_core::error::ResolverError::MethodNotSupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_get_method() {
+        assert_eq!(KeyResolver::new().get_method(), "key");
+    }
+}
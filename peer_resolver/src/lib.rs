@@ -0,0 +1,419 @@
+use std::str::FromStr;
+
+use signature::suite::ed25519_2020::{
+    did::{get_peer_4_hash, MULTICODEC_JSON, MULTICODEC_X25519_PUB},
+    MULTICODEC_ED25519_PUB,
+};
+
+// Implementation of https://identity.foundation/peer-did-method-spec/ for numalgo 0, 2 and 4.
+// The DID Document is decoded from the DID itself, so nothing is registered or fetched.
+pub const DID_METHOD: &str = "peer";
+
+const ED25519_VERIFICATION_KEY_2020: &str = "Ed25519VerificationKey2020";
+const X25519_KEY_AGREEMENT_KEY_2020: &str = "X25519KeyAgreementKey2020";
+
+#[derive(Debug, Clone, Default)]
+pub struct PeerResolver;
+
+impl PeerResolver {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl ssi_core::DIDResolver for PeerResolver {
+    fn get_method(&self) -> &'static str {
+        DID_METHOD
+    }
+
+    async fn create(
+        &self,
+        _did: String,
+        _document: serde_json::Value,
+    ) -> Result<(), ssi_core::error::ResolverError> {
+        Err(ssi_core::error::ResolverError::MethodNotSupported(
+            "did:peer documents are encoded in the DID and can not be created".to_string(),
+        ))
+    }
+
+    async fn resolve(
+        &self,
+        did: String,
+    ) -> Result<ssi_core::ResolveResponse, ssi_core::error::ResolverError> {
+        let document = create_did_document(&did)?;
+        let document = serde_json::to_value(document)
+            .map_err(|e| ssi_core::error::ResolverError::InvalidData(e.to_string()))?;
+
+        Ok(ssi_core::ResolveResponse {
+            did_document: document,
+            did_document_metadata: ssi_core::DidDocumentMetadata::default(),
+            did_resolution_metadata: ssi_core::ResolutionMetadata {
+                content_type: Some(ssi_core::DID_LD_JSON_CONTENT_TYPE.to_string()),
+                ..Default::default()
+            },
+        })
+    }
+}
+
+fn create_did_document(
+    did: &str,
+) -> Result<ssi_core::identity::DidDocument, ssi_core::error::ResolverError> {
+    let parsed_did = ssi_core::did::Did::from_str(did)?;
+    if parsed_did.method() != DID_METHOD {
+        return Err(ssi_core::error::ResolverError::MethodNotSupported(format!(
+            "did:{} is not supported by the did:peer resolver",
+            parsed_did.method()
+        )));
+    }
+
+    let method_specific_id = parsed_did.method_specific_id();
+    let (numalgo, rest) = method_specific_id.split_at(1);
+    match numalgo {
+        "0" => key_resolver::create_key_did_document(did, rest),
+        "2" => create_numalgo_2_document(did, rest),
+        "4" => create_numalgo_4_document(did, rest),
+        _ => Err(invalid_did(did, "unsupported numalgo")),
+    }
+}
+
+// did:peer:2 is a list of purpose-prefixed keys and services, e.g. did:peer:2.Ez6LS...Vz6Mk...SeyJ0Ijo...
+fn create_numalgo_2_document(
+    did: &str,
+    elements: &str,
+) -> Result<ssi_core::identity::DidDocument, ssi_core::error::ResolverError> {
+    let mut document = ssi_core::identity::DidDocument {
+        context: ssi_core::identity::DidContext::Set(vec![
+            ssi_core::credential::ContextValue::String(ssi_core::identity::DID_CONTEXT.to_string()),
+            ssi_core::credential::ContextValue::String(
+                ssi_core::identity::ED25519_2020_CONTEXT.to_string(),
+            ),
+            ssi_core::credential::ContextValue::String(
+                ssi_core::identity::X25519_2020_CONTEXT.to_string(),
+            ),
+        ]),
+        id: did.to_string(),
+        ..Default::default()
+    };
+
+    for element in elements.split('.').skip(1) {
+        let (purpose, value) = element.split_at(element.len().min(1));
+        if purpose == "S" {
+            let services = decode_services(did, value)?;
+            for service in services {
+                let id = match document.service.len() {
+                    0 => "#service".to_string(),
+                    n => format!("#service-{n}"),
+                };
+                document.service.push(expand_service(did, id, service)?);
+            }
+            continue;
+        }
+
+        let relationship = match purpose {
+            "A" => &mut document.assertion_method,
+            "E" => &mut document.key_agreement,
+            "V" => &mut document.authentication,
+            "I" => &mut document.capability_invocation,
+            "D" => &mut document.capability_delegation,
+            _ => return Err(invalid_did(did, "unsupported purpose")),
+        };
+
+        let id = format!("#key-{}", document.verification_method.len() + 1);
+        relationship.push(ssi_core::identity::VerificationMethodEntry::Reference(
+            id.clone(),
+        ));
+        document
+            .verification_method
+            .push(create_key_material(did, id, value)?);
+    }
+
+    Ok(document)
+}
+
+// did:peer:4<hash>:<multibase encoded JSON document>, the short form did:peer:4<hash> can not be resolved by itself.
+fn create_numalgo_4_document(
+    did: &str,
+    rest: &str,
+) -> Result<ssi_core::identity::DidDocument, ssi_core::error::ResolverError> {
+    let Some((hash, encoded_document)) = rest.split_once(':') else {
+        return Err(ssi_core::error::ResolverError::DocumentNotFound(format!(
+            "{did} is a short form did:peer:4 and can only be resolved from its long form"
+        )));
+    };
+    if get_peer_4_hash(encoded_document) != hash {
+        return Err(invalid_did(did, "hash does not match the document"));
+    }
+
+    let (base, document) =
+        multibase::decode(encoded_document).map_err(|e| invalid_did(did, &e.to_string()))?;
+    let document = match document.strip_prefix(MULTICODEC_JSON) {
+        Some(document) if base == multibase::Base::Base58Btc => document,
+        _ => return Err(invalid_did(did, "document must be base58btc encoded JSON")),
+    };
+
+    let mut document: serde_json::Value =
+        serde_json::from_slice(document).map_err(|e| invalid_did(did, &e.to_string()))?;
+    let Some(properties) = document.as_object_mut() else {
+        return Err(invalid_did(did, "document must be a JSON object"));
+    };
+    properties.insert("id".to_string(), serde_json::json!(did));
+    properties.insert(
+        "alsoKnownAs".to_string(),
+        serde_json::json!([format!("did:peer:4{hash}")]),
+    );
+    // Verification methods of the input document are controlled by the DID itself
+    if let Some(methods) = properties
+        .get_mut("verificationMethod")
+        .and_then(serde_json::Value::as_array_mut)
+    {
+        for method in methods
+            .iter_mut()
+            .filter_map(serde_json::Value::as_object_mut)
+        {
+            method
+                .entry("controller")
+                .or_insert_with(|| serde_json::json!(did));
+        }
+    }
+
+    ssi_core::identity::DidDocument::try_from(document)
+        .map_err(|e| ssi_core::error::ResolverError::InvalidData(e.to_string()))
+}
+
+fn create_key_material(
+    did: &str,
+    id: String,
+    encoded_key: &str,
+) -> Result<ssi_core::identity::KeyMaterial, ssi_core::error::ResolverError> {
+    let (base, key) =
+        multibase::decode(encoded_key).map_err(|e| invalid_did(did, &e.to_string()))?;
+    if base != multibase::Base::Base58Btc {
+        return Err(invalid_did(did, "keys must be base58btc encoded"));
+    }
+
+    let key_type = if key.starts_with(MULTICODEC_ED25519_PUB) {
+        ED25519_VERIFICATION_KEY_2020
+    } else if key.starts_with(MULTICODEC_X25519_PUB) {
+        X25519_KEY_AGREEMENT_KEY_2020
+    } else {
+        return Err(invalid_did(did, "unsupported public key type"));
+    };
+
+    Ok(ssi_core::identity::KeyMaterial {
+        id,
+        proof_type: key_type.to_string(),
+        controller: did.to_string(),
+        public_key_multibase: Some(encoded_key.to_string()),
+        public_key_jwk: None,
+        property_set: std::collections::HashMap::new(),
+    })
+}
+
+fn decode_services(
+    did: &str,
+    encoded_services: &str,
+) -> Result<Vec<serde_json::Value>, ssi_core::error::ResolverError> {
+    let services = base64::decode_config(encoded_services, base64::URL_SAFE_NO_PAD)
+        .map_err(|e| invalid_did(did, &e.to_string()))?;
+    let services: serde_json::Value =
+        serde_json::from_slice(&services).map_err(|e| invalid_did(did, &e.to_string()))?;
+
+    Ok(match services {
+        serde_json::Value::Array(services) => services,
+        service => vec![service],
+    })
+}
+
+// https://identity.foundation/peer-did-method-spec/#resolving-a-didpeer2
+fn expand_service(
+    did: &str,
+    id: String,
+    service: serde_json::Value,
+) -> Result<ssi_core::identity::Service, ssi_core::error::ResolverError> {
+    let mut service = expand_abbreviations(service);
+    if let Some(properties) = service.as_object_mut() {
+        properties
+            .entry("id")
+            .or_insert_with(|| serde_json::json!(id));
+    }
+
+    serde_json::from_value(service).map_err(|e| invalid_did(did, &e.to_string()))
+}
+
+fn expand_abbreviations(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let key = match key.as_str() {
+                        "t" => "type".to_string(),
+                        "s" => "serviceEndpoint".to_string(),
+                        "r" => "routingKeys".to_string(),
+                        "a" => "accept".to_string(),
+                        _ => key,
+                    };
+                    let value = match value.as_str() {
+                        Some("dm") => {
+                            serde_json::json!(ssi_core::identity::DIDCOMM_MESSAGING_SERVICE_TYPE)
+                        }
+                        _ => expand_abbreviations(value),
+                    };
+                    (key, value)
+                })
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+fn invalid_did(did: &str, reason: &str) -> ssi_core::error::ResolverError {
+    ssi_core::error::ResolverError::InvalidDid(format!("{did} is not a valid did:peer: {reason}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::PeerResolver;
+    use ssi_core::DIDResolver;
+
+    macro_rules! aw {
+        ($e:expr) => {
+            tokio_test::block_on($e)
+        };
+    }
+
+    fn get_key_pair() -> signature::suite::ed25519_2020::Ed25519KeyPair {
+        signature::suite::ed25519_2020::Ed25519KeyPair::from_private_key(
+            "peer".to_string(),
+            "z3u2en7t5LR2WtQH5PfFqMqwVHBeXouLzo6haApm8XHqvjxq".to_string(),
+        )
+        .unwrap()
+    }
+
+    fn resolve(did: String) -> ssi_core::identity::DidDocument {
+        let rsp = aw!(PeerResolver::new().resolve(did)).unwrap();
+        ssi_core::identity::DidDocument::try_from(rsp.did_document).unwrap()
+    }
+
+    #[test]
+    fn test_resolve_numalgo_0() {
+        let did = get_key_pair().get_did_peer_0();
+
+        let doc = resolve(did.clone());
+        assert_eq!(doc.id, did);
+        assert_eq!(
+            doc.verification_method[0].public_key_multibase.as_deref(),
+            Some("z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2")
+        );
+        assert_eq!(doc.key_agreement.len(), 1);
+    }
+
+    #[test]
+    fn test_resolve_numalgo_2() {
+        let did = get_key_pair()
+            .get_did_peer_2(&[
+                serde_json::json!({
+                    "type": "DIDCommMessaging",
+                    "serviceEndpoint": {"uri": "https://example.com/endpoint", "accept": ["didcomm/v2"]}
+                }),
+                serde_json::json!({
+                    "type": "LinkedDomains",
+                    "serviceEndpoint": "https://example.com"
+                }),
+            ])
+            .unwrap();
+
+        let doc = resolve(did.clone());
+        assert_eq!(doc.id, did);
+        assert_eq!(doc.verification_method.len(), 2);
+        assert_eq!(
+            doc.get_verification_method("#key-1").unwrap().proof_type,
+            "X25519KeyAgreementKey2020"
+        );
+        assert_eq!(
+            doc.get_verification_method("#key-2").unwrap().proof_type,
+            "Ed25519VerificationKey2020"
+        );
+        assert_eq!(doc.key_agreement[0].id(), "#key-1");
+        assert_eq!(doc.authentication[0].id(), "#key-2");
+
+        let service = doc.get_service("#service").unwrap();
+        assert!(service
+            .service_type
+            .contains(ssi_core::identity::DIDCOMM_MESSAGING_SERVICE_TYPE));
+        assert_eq!(
+            service.service_endpoint.uris(),
+            vec!["https://example.com/endpoint"]
+        );
+        assert!(doc.get_service("#service-1").is_some());
+    }
+
+    #[test]
+    fn test_resolve_numalgo_4() {
+        let did = get_key_pair().get_did_peer_4().unwrap();
+        let short_did = did.rsplit_once(':').unwrap().0.to_string();
+
+        let doc = resolve(did.clone());
+        assert_eq!(doc.id, did);
+        assert_eq!(doc.also_known_as, vec![short_did.clone()]);
+        assert_eq!(doc.verification_method[0].controller, did);
+        assert_eq!(doc.assertion_method[0].id(), "#key-1");
+
+        assert!(matches!(
+            aw!(PeerResolver::new().resolve(short_did)),
+            Err(ssi_core::error::ResolverError::DocumentNotFound(_))
+        ));
+    }
+
+    #[rstest::rstest]
+    #[case::unsupported_numalgo(
+        "did:peer:1zQmZMygzYqNwU6Uhmewx5Xepf2VLp5S4HLSwwgf2aiKZuwa",
+        "InvalidDid"
+    )]
+    #[case::unsupported_purpose(
+        "did:peer:2.Xz6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2",
+        "InvalidDid"
+    )]
+    #[case::unsupported_key(
+        "did:peer:2.Vz6DtYZ1ZvFsWTPnRBvCP6ZDhoBjNvS2Bqx5whJ1Ev8b3hfa",
+        "InvalidDid"
+    )]
+    #[case::invalid_service(
+        "did:peer:2.Vz6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2.Se30",
+        "InvalidDid"
+    )]
+    #[case::method_not_supported(
+        "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK",
+        "MethodNotSupported"
+    )]
+    fn test_resolve_error(#[case] did: &str, #[case] expected_kind: &str) {
+        let resolver = PeerResolver::new();
+
+        match (aw!(resolver.resolve(did.to_string())), expected_kind) {
+            (Err(ssi_core::error::ResolverError::InvalidDid(_)), "InvalidDid") => {}
+            (Err(ssi_core::error::ResolverError::MethodNotSupported(_)), "MethodNotSupported") => {}
+            (res, expected) => panic!("unexpected result {res:?}, expected {expected}"),
+        }
+    }
+
+    #[test]
+    fn test_resolve_tampered_numalgo_4() {
+        let did = get_key_pair().get_did_peer_4().unwrap();
+        let other_did =
+            signature::suite::ed25519_2020::Ed25519KeyPair::new("peer".to_string(), None)
+                .unwrap()
+                .get_did_peer_4()
+                .unwrap();
+        // Hash of the first document with the content of the second
+        let tampered_did = format!(
+            "{}:{}",
+            did.rsplit_once(':').unwrap().0,
+            other_did.rsplit_once(':').unwrap().1
+        );
+
+        assert!(matches!(
+            aw!(PeerResolver::new().resolve(tampered_did)),
+            Err(ssi_core::error::ResolverError::InvalidDid(_))
+        ));
+    }
+}
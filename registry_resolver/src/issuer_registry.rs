@@ -0,0 +1,351 @@
+use crate::registry_client::registry::{
+    credential_issuer_registry_service_client::CredentialIssuerRegistryServiceClient,
+    CreateCredentialIssuerRequest, GetCredentialIssuerRequest, UpdateCredentialIssuerRequest,
+};
+pub use crate::registry_client::registry::{
+    CreateCredentialIssuerResponse, CredentialIssuer, GetCredentialIssuerResponse, IssuerStatus,
+    PostalAddress, UpdateCredentialIssuerResponse,
+};
+
+#[derive(Clone, Debug)]
+pub struct GrpcIssuerClient {
+    inner: CredentialIssuerRegistryServiceClient<tonic::transport::Channel>,
+}
+
+impl GrpcIssuerClient {
+    pub async fn new(url: String) -> Self {
+        let inner = CredentialIssuerRegistryServiceClient::connect(url)
+            .await
+            .unwrap();
+        Self { inner }
+    }
+}
+
+#[async_trait::async_trait]
+impl CredentialIssuerClient for GrpcIssuerClient {
+    async fn create_credential_issuer(
+        &self,
+        credential_issuer: CredentialIssuer,
+    ) -> Result<tonic::Response<CreateCredentialIssuerResponse>, tonic::Status> {
+        let mut client = self.inner.to_owned();
+        return client
+            .create_credential_issuer(CreateCredentialIssuerRequest {
+                credential_issuer: Some(credential_issuer),
+            })
+            .await;
+    }
+
+    async fn get_credential_issuer(
+        &self,
+        issuer_did: String,
+    ) -> Result<tonic::Response<GetCredentialIssuerResponse>, tonic::Status> {
+        let mut client = self.inner.to_owned();
+        return client
+            .get_credential_issuer(GetCredentialIssuerRequest { issuer_did })
+            .await;
+    }
+
+    async fn update_credential_issuer(
+        &self,
+        credential_issuer: CredentialIssuer,
+    ) -> Result<tonic::Response<UpdateCredentialIssuerResponse>, tonic::Status> {
+        let mut client = self.inner.to_owned();
+        return client
+            .update_credential_issuer(UpdateCredentialIssuerRequest {
+                credential_issuer: Some(credential_issuer),
+            })
+            .await;
+    }
+}
+
+#[mockall::automock]
+#[async_trait::async_trait]
+pub trait CredentialIssuerClient: Send + Sync + std::fmt::Debug {
+    async fn create_credential_issuer(
+        &self,
+        credential_issuer: CredentialIssuer,
+    ) -> Result<tonic::Response<CreateCredentialIssuerResponse>, tonic::Status>;
+
+    async fn get_credential_issuer(
+        &self,
+        issuer_did: String,
+    ) -> Result<tonic::Response<GetCredentialIssuerResponse>, tonic::Status>;
+
+    async fn update_credential_issuer(
+        &self,
+        credential_issuer: CredentialIssuer,
+    ) -> Result<tonic::Response<UpdateCredentialIssuerResponse>, tonic::Status>;
+}
+
+/// Typed access to the `CredentialIssuerRegistryService` of the registry, which keeps the metadata and status of the
+/// credential issuers. Verifiers use `check_credential_issuer` to refuse credentials issued by suspended issuers.
+#[derive(Clone, Debug)]
+pub struct CredentialIssuerRegistry<T = GrpcIssuerClient>
+where
+    T: CredentialIssuerClient + 'static,
+{
+    client: T,
+}
+
+impl CredentialIssuerRegistry<GrpcIssuerClient> {
+    pub async fn new(url: impl Into<String>) -> Self {
+        let client = GrpcIssuerClient::new(url.into()).await;
+        CredentialIssuerRegistry { client }
+    }
+}
+
+impl<T> CredentialIssuerRegistry<T>
+where
+    T: CredentialIssuerClient,
+{
+    pub fn with_client(client: T) -> Self {
+        CredentialIssuerRegistry { client }
+    }
+
+    pub async fn create_issuer(
+        &self,
+        credential_issuer: CredentialIssuer,
+    ) -> Result<(), ssi_core::error::ResolverError> {
+        let issuer_did = credential_issuer.issuer_did.clone();
+        let res = self
+            .client
+            .create_credential_issuer(credential_issuer)
+            .await
+            .map_err(crate::status_to_resolver_error)?
+            .into_inner();
+
+        match res.ok {
+            true => Ok(()),
+            false => Err(ssi_core::error::ResolverError::Unknown(format!(
+                "Credential issuer {issuer_did} was not created by the registry"
+            ))),
+        }
+    }
+
+    pub async fn get_issuer(
+        &self,
+        issuer_did: &str,
+    ) -> Result<CredentialIssuer, ssi_core::error::ResolverError> {
+        self.client
+            .get_credential_issuer(issuer_did.to_string())
+            .await
+            .map_err(crate::status_to_resolver_error)?
+            .into_inner()
+            .credential_issuer
+            .ok_or_else(|| {
+                ssi_core::error::ResolverError::InvalidData(
+                    "No credential issuer found in registry response".to_string(),
+                )
+            })
+    }
+
+    pub async fn update_issuer(
+        &self,
+        credential_issuer: CredentialIssuer,
+    ) -> Result<(), ssi_core::error::ResolverError> {
+        let issuer_did = credential_issuer.issuer_did.clone();
+        let res = self
+            .client
+            .update_credential_issuer(credential_issuer)
+            .await
+            .map_err(crate::status_to_resolver_error)?
+            .into_inner();
+
+        match res.ok {
+            true => Ok(()),
+            false => Err(ssi_core::error::ResolverError::Unknown(format!(
+                "Credential issuer {issuer_did} was not updated by the registry"
+            ))),
+        }
+    }
+
+    pub async fn set_issuer_status(
+        &self,
+        issuer_did: &str,
+        status: IssuerStatus,
+    ) -> Result<(), ssi_core::error::ResolverError> {
+        let mut credential_issuer = self.get_issuer(issuer_did).await?;
+        credential_issuer.set_status(status);
+        self.update_issuer(credential_issuer).await
+    }
+
+    /// Look up the issuer of a credential, failing with `Unauthorized` when the issuer is suspended.
+    pub async fn check_issuer(
+        &self,
+        issuer_did: &str,
+    ) -> Result<CredentialIssuer, ssi_core::error::ResolverError> {
+        let credential_issuer = self.get_issuer(issuer_did).await?;
+        if credential_issuer.status() == IssuerStatus::Suspended {
+            return Err(ssi_core::error::ResolverError::Unauthorized(format!(
+                "Credential issuer {issuer_did} is suspended"
+            )));
+        }
+        Ok(credential_issuer)
+    }
+
+    pub async fn check_credential_issuer(
+        &self,
+        credential: &ssi_core::credential::Credential,
+    ) -> Result<CredentialIssuer, ssi_core::error::ResolverError> {
+        self.check_issuer(credential.issuer.id()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CreateCredentialIssuerResponse, CredentialIssuer, CredentialIssuerRegistry,
+        GetCredentialIssuerResponse, IssuerStatus, MockCredentialIssuerClient, PostalAddress,
+        UpdateCredentialIssuerResponse,
+    };
+
+    macro_rules! aw {
+        ($e:expr) => {
+            tokio_test::block_on($e)
+        };
+    }
+
+    const ISSUER_DID: &str = "did:knox:z6MkfFmsob7fC3MmqU1JVfdBnMbnAw7xm1mrEtPvAoojLcRh";
+
+    fn create_credential_issuer(status: IssuerStatus) -> CredentialIssuer {
+        CredentialIssuer {
+            issuer_did: ISSUER_DID.to_string(),
+            issuer_name: "Example Bank".to_string(),
+            email: "issuer@example.com".to_string(),
+            website: "example.com".to_string(),
+            postal_address: Some(PostalAddress {
+                country: "United States".to_string(),
+                city: "Anytown".to_string(),
+                state: "AL".to_string(),
+                postal_code: "10101".to_string(),
+                street: "123 Sesame Street".to_string(),
+            }),
+            status: status.into(),
+        }
+    }
+
+    fn create_registry(
+        res: Result<tonic::Response<GetCredentialIssuerResponse>, tonic::Status>,
+    ) -> CredentialIssuerRegistry<MockCredentialIssuerClient> {
+        let mut mock_client = MockCredentialIssuerClient::default();
+        mock_client
+            .expect_get_credential_issuer()
+            .with(mockall::predicate::eq(ISSUER_DID.to_string()))
+            .return_once(|_| res);
+        CredentialIssuerRegistry::with_client(mock_client)
+    }
+
+    #[rstest::rstest]
+    #[case::active(
+        Ok(tonic::Response::new(GetCredentialIssuerResponse {
+            credential_issuer: Some(create_credential_issuer(IssuerStatus::Active)),
+        })),
+        Ok(create_credential_issuer(IssuerStatus::Active))
+    )]
+    #[case::suspended(
+        Ok(tonic::Response::new(GetCredentialIssuerResponse {
+            credential_issuer: Some(create_credential_issuer(IssuerStatus::Suspended)),
+        })),
+        Err(ssi_core::error::ResolverError::Unauthorized(format!(
+            "Credential issuer {ISSUER_DID} is suspended"
+        )))
+    )]
+    #[case::not_found(
+        Err(tonic::Status::not_found("message")),
+        Err(ssi_core::error::ResolverError::DocumentNotFound("message".to_string()))
+    )]
+    #[case::missing_issuer(
+        Ok(tonic::Response::new(GetCredentialIssuerResponse {
+            credential_issuer: None,
+        })),
+        Err(ssi_core::error::ResolverError::InvalidData(
+            "No credential issuer found in registry response".to_string()
+        ))
+    )]
+    fn test_check_issuer(
+        #[case] res: Result<tonic::Response<GetCredentialIssuerResponse>, tonic::Status>,
+        #[case] expected: Result<CredentialIssuer, ssi_core::error::ResolverError>,
+    ) {
+        let registry = create_registry(res);
+
+        assert_eq!(aw!(registry.check_issuer(ISSUER_DID)), expected);
+    }
+
+    #[test]
+    fn test_check_credential_issuer() {
+        let registry = create_registry(Ok(tonic::Response::new(GetCredentialIssuerResponse {
+            credential_issuer: Some(create_credential_issuer(IssuerStatus::Suspended)),
+        })));
+        let credential = ssi_core::credential::Credential {
+            context: vec![],
+            id: None,
+            cred_type: vec![],
+            issuance_date: None,
+            expiration_date: None,
+            valid_from: None,
+            valid_until: None,
+            name: None,
+            description: None,
+            issuer: ISSUER_DID.into(),
+            subject: ssi_core::credential::CredentialSubject::Single(Default::default()),
+            credential_status: None,
+            credential_schema: None,
+            property_set: Default::default(),
+        };
+
+        let res = aw!(registry.check_credential_issuer(&credential));
+        assert!(matches!(
+            res,
+            Err(ssi_core::error::ResolverError::Unauthorized(_))
+        ));
+    }
+
+    #[rstest::rstest]
+    #[case::created(true, Ok(()))]
+    #[case::rejected(
+        false,
+        Err(ssi_core::error::ResolverError::Unknown(format!(
+            "Credential issuer {ISSUER_DID} was not created by the registry"
+        )))
+    )]
+    fn test_create_issuer(
+        #[case] ok: bool,
+        #[case] expected: Result<(), ssi_core::error::ResolverError>,
+    ) {
+        let mut mock_client = MockCredentialIssuerClient::default();
+        mock_client
+            .expect_create_credential_issuer()
+            .with(mockall::predicate::eq(create_credential_issuer(
+                IssuerStatus::Active,
+            )))
+            .return_once(move |_| Ok(tonic::Response::new(CreateCredentialIssuerResponse { ok })));
+        let registry = CredentialIssuerRegistry::with_client(mock_client);
+
+        let res = aw!(registry.create_issuer(create_credential_issuer(IssuerStatus::Active)));
+        assert_eq!(res, expected);
+    }
+
+    #[test]
+    fn test_set_issuer_status() {
+        let mut mock_client = MockCredentialIssuerClient::default();
+        mock_client.expect_get_credential_issuer().return_once(|_| {
+            Ok(tonic::Response::new(GetCredentialIssuerResponse {
+                credential_issuer: Some(create_credential_issuer(IssuerStatus::Active)),
+            }))
+        });
+        mock_client
+            .expect_update_credential_issuer()
+            .with(mockall::predicate::eq(create_credential_issuer(
+                IssuerStatus::Suspended,
+            )))
+            .return_once(|_| {
+                Ok(tonic::Response::new(UpdateCredentialIssuerResponse {
+                    ok: true,
+                }))
+            });
+        let registry = CredentialIssuerRegistry::with_client(mock_client);
+
+        let res = aw!(registry.set_issuer_status(ISSUER_DID, IssuerStatus::Suspended));
+        assert_eq!(res, Ok(()));
+    }
+}
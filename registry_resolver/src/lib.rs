@@ -1,8 +1,11 @@
 use std::num::TryFromIntError;
+use std::str::FromStr;
 
 use chrono::TimeZone;
 
+pub mod issuer_registry;
 mod registry_client;
+pub mod status_list_registry;
 pub const DID_METHOD: &str = "knox";
 
 #[derive(Clone, Debug)]
@@ -17,6 +20,36 @@ const fn get_method_helper() -> &'static str {
     DID_METHOD
 }
 
+// `Unavailable` and `DeadlineExceeded` are transient and can be retried, see ssi_core::resolver::retry::RetryResolver.
+fn status_to_resolver_error(status: tonic::Status) -> ssi_core::error::ResolverError {
+    match status.code() {
+        tonic::Code::NotFound => {
+            ssi_core::error::ResolverError::DocumentNotFound(status.message().to_string())
+        }
+        tonic::Code::Unavailable => {
+            ssi_core::error::ResolverError::NetworkFailure(status.to_string())
+        }
+        tonic::Code::DeadlineExceeded => {
+            ssi_core::error::ResolverError::Timeout(status.to_string())
+        }
+        tonic::Code::PermissionDenied | tonic::Code::Unauthenticated => {
+            ssi_core::error::ResolverError::Unauthorized(status.to_string())
+        }
+        _ => ssi_core::error::ResolverError::Unknown(status.to_string()),
+    }
+}
+
+fn check_did(did: &str) -> Result<(), ssi_core::error::ResolverError> {
+    let parsed_did = ssi_core::did::Did::from_str(did)?;
+    if parsed_did.method() != get_method_helper() {
+        return Err(ssi_core::error::ResolverError::MethodNotSupported(format!(
+            "did:{} is not supported by the registry",
+            parsed_did.method()
+        )));
+    }
+    Ok(())
+}
+
 impl RegistryResolver<registry_client::GrpcClient> {
     pub async fn new(url: impl Into<String>) -> Self {
         let client = registry_client::GrpcClient::new(url.into()).await;
@@ -41,7 +74,37 @@ where
         self.client
             .create(did, document.to_string())
             .await
-            .map_err(|e| ssi_core::error::ResolverError::NetworkFailure(e.to_string()))?;
+            .map_err(status_to_resolver_error)?;
+
+        Ok(())
+    }
+
+    // The registry checks the capabilityInvocation proof of `signed_doc` against the current document of `did`.
+    async fn update(
+        &self,
+        did: String,
+        signed_doc: serde_json::Value,
+    ) -> Result<(), ssi_core::error::ResolverError> {
+        check_did(&did)?;
+        self.client
+            .update(did, signed_doc.to_string())
+            .await
+            .map_err(status_to_resolver_error)?;
+
+        Ok(())
+    }
+
+    // Revoking a DID in the registry deactivates it, `signed_doc` proves the ownership of the DID.
+    async fn deactivate(
+        &self,
+        did: String,
+        signed_doc: serde_json::Value,
+    ) -> Result<(), ssi_core::error::ResolverError> {
+        check_did(&did)?;
+        self.client
+            .revoke(did, signed_doc.to_string())
+            .await
+            .map_err(status_to_resolver_error)?;
 
         Ok(())
     }
@@ -50,11 +113,33 @@ where
         &self,
         did: String,
     ) -> Result<ssi_core::ResolveResponse, ssi_core::error::ResolverError> {
+        self.resolve_with_options(did, ssi_core::ResolutionOptions::default())
+            .await
+    }
+
+    // The registry only keeps the latest version of a DID document, so `versionId` can not be resolved and
+    // `versionTime` only resolves when it is after the last update.
+    async fn resolve_with_options(
+        &self,
+        did: String,
+        options: ssi_core::ResolutionOptions,
+    ) -> Result<ssi_core::ResolveResponse, ssi_core::error::ResolverError> {
+        check_resolution(&did, &options)?;
+
+        let resolution_option = match options.accept.as_deref() {
+            Some(ssi_core::DID_LD_JSON_CONTENT_TYPE) => {
+                Some(registry_client::registry::ResolutionOption {
+                    accept: registry_client::registry::ResolutionOptionAccept::JsonLd.into(),
+                })
+            }
+            _ => None,
+        };
+
         let res = self
             .client
-            .resolve(did.clone())
+            .resolve(did.clone(), resolution_option)
             .await
-            .map_err(|e| ssi_core::error::ResolverError::NetworkFailure(e.to_string()))?
+            .map_err(status_to_resolver_error)?
             .into_inner();
 
         let document = res.did_document.ok_or({
@@ -62,64 +147,12 @@ where
                 "No document found in registry response".to_string(),
             )
         })?;
-        let document_metadata = res.did_document_metadata.ok_or({
-            ssi_core::error::ResolverError::InvalidData(
-                "No document metadata found in registry response".to_string(),
-            )
-        })?;
-
-        let resolution_metadata = res.did_resolution_metadata.ok_or({
-            ssi_core::error::ResolverError::InvalidData(
-                "No resolution metadata found in registry response".to_string(),
-            )
-        })?;
-
-        //timestamp to date
-        let created = document_metadata.created.ok_or({
-            ssi_core::error::ResolverError::InvalidData(
-                "No created property found in document metadata in response".to_string(),
-            )
-        })?;
-        let updated = document_metadata.updated.ok_or({
-            ssi_core::error::ResolverError::InvalidData(
-                "No updated property found in document metadata in response".to_string(),
-            )
-        })?;
-
-        let created = chrono::Utc
-            .timestamp_opt(
-                created.seconds,
-                created.nanos.try_into().map_err(|e: TryFromIntError| {
-                    ssi_core::error::ResolverError::Unknown(e.to_string())
-                })?,
-            )
-            .unwrap();
-
-        let updated = chrono::Utc
-            .timestamp_opt(
-                updated.seconds,
-                updated.nanos.try_into().map_err(|e: TryFromIntError| {
-                    ssi_core::error::ResolverError::Unknown(e.to_string())
-                })?,
-            )
-            .unwrap();
-
-        let document_metadata = ssi_core::DidDocumentMetadata { created, updated };
-
-        let did_url = resolution_metadata
-            .did_url
-            .map(|url| ssi_core::DidResolutionURL {
-                did,
-                method_specific_id: url.method_specific_id,
-                method_name: url.method_name,
-            });
-
-        let resolution_metadata = ssi_core::ResolutionMetadata {
-            duration: resolution_metadata.duration,
-            error: resolution_metadata.error,
-            content_type: resolution_metadata.content_type,
-            did_url,
-        };
+        let (document_metadata, resolution_metadata) = convert_metadata(
+            did,
+            &options,
+            res.did_document_metadata,
+            res.did_resolution_metadata,
+        )?;
 
         let document = serde_json::to_value(document).map_err(|e: serde_json::Error| {
             ssi_core::error::ResolverError::InvalidData(e.to_string())
@@ -131,6 +164,174 @@ where
             did_resolution_metadata: resolution_metadata,
         })
     }
+
+    // The document is returned as serialized by the registry, the content type defaults to the requested one when the
+    // registry does not report it.
+    async fn resolve_representation(
+        &self,
+        did: String,
+        options: ssi_core::ResolutionOptions,
+    ) -> Result<ssi_core::ResolveRepresentationResponse, ssi_core::error::ResolverError> {
+        check_resolution(&did, &options)?;
+
+        let (accept, content_type) = match options.accept.as_deref() {
+            Some(ssi_core::DID_JSON_CONTENT_TYPE) => (
+                registry_client::registry::ResolutionOptionAccept::Unspecified,
+                ssi_core::DID_JSON_CONTENT_TYPE,
+            ),
+            _ => (
+                registry_client::registry::ResolutionOptionAccept::JsonLd,
+                ssi_core::DID_LD_JSON_CONTENT_TYPE,
+            ),
+        };
+
+        let res = self
+            .client
+            .resolve_representation(
+                did.clone(),
+                Some(registry_client::registry::ResolutionOption {
+                    accept: accept.into(),
+                }),
+            )
+            .await
+            .map_err(status_to_resolver_error)?
+            .into_inner();
+
+        let (document_metadata, mut resolution_metadata) = convert_metadata(
+            did,
+            &options,
+            res.did_document_metadata,
+            res.did_resolution_metadata,
+        )?;
+        resolution_metadata
+            .content_type
+            .get_or_insert_with(|| content_type.to_string());
+
+        Ok(ssi_core::ResolveRepresentationResponse {
+            did_document_stream: res.did_document_stream.into_bytes(),
+            did_document_metadata: document_metadata,
+            did_resolution_metadata: resolution_metadata,
+        })
+    }
+}
+
+fn check_resolution(
+    did: &str,
+    options: &ssi_core::ResolutionOptions,
+) -> Result<(), ssi_core::error::ResolverError> {
+    check_did(did)?;
+    options.check_accept()?;
+    if options.version_id.is_some() {
+        return Err(ssi_core::error::ResolverError::DocumentNotFound(
+            "versionId is not supported by the registry".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+// Converts the metadata of a registry resolution, failing with the error reported by the registry unless the DID is
+// deactivated.
+fn convert_metadata(
+    did: String,
+    options: &ssi_core::ResolutionOptions,
+    document_metadata: Option<registry_client::registry::DidDocumentMetadata>,
+    resolution_metadata: Option<registry_client::registry::ResolutionMetadata>,
+) -> Result<
+    (ssi_core::DidDocumentMetadata, ssi_core::ResolutionMetadata),
+    ssi_core::error::ResolverError,
+> {
+    let document_metadata = document_metadata.ok_or({
+        ssi_core::error::ResolverError::InvalidData(
+            "No document metadata found in registry response".to_string(),
+        )
+    })?;
+
+    let resolution_metadata = resolution_metadata.ok_or({
+        ssi_core::error::ResolverError::InvalidData(
+            "No resolution metadata found in registry response".to_string(),
+        )
+    })?;
+
+    let error = resolution_metadata
+        .error
+        .map(|error| {
+            serde_json::from_value::<ssi_core::ResolutionErrorCode>(serde_json::Value::String(
+                error.clone(),
+            ))
+            .map(|code| (code, error))
+            .map_err(|e| ssi_core::error::ResolverError::InvalidData(e.to_string()))
+        })
+        .transpose()?;
+    let deactivated = match error {
+        None => None,
+        Some((ssi_core::ResolutionErrorCode::Deactivated, _)) => Some(true),
+        Some((code, error)) => return Err(code.into_resolver_error(error)),
+    };
+
+    //timestamp to date
+    let created = document_metadata.created.ok_or({
+        ssi_core::error::ResolverError::InvalidData(
+            "No created property found in document metadata in response".to_string(),
+        )
+    })?;
+    let updated = document_metadata.updated.ok_or({
+        ssi_core::error::ResolverError::InvalidData(
+            "No updated property found in document metadata in response".to_string(),
+        )
+    })?;
+
+    let created = chrono::Utc
+        .timestamp_opt(
+            created.seconds,
+            created.nanos.try_into().map_err(|e: TryFromIntError| {
+                ssi_core::error::ResolverError::Unknown(e.to_string())
+            })?,
+        )
+        .unwrap();
+
+    let updated = chrono::Utc
+        .timestamp_opt(
+            updated.seconds,
+            updated.nanos.try_into().map_err(|e: TryFromIntError| {
+                ssi_core::error::ResolverError::Unknown(e.to_string())
+            })?,
+        )
+        .unwrap();
+
+    if options
+        .version_time
+        .is_some_and(|version_time| version_time < updated)
+    {
+        return Err(ssi_core::error::ResolverError::DocumentNotFound(
+            "Only the latest version of the document is available in the registry".to_string(),
+        ));
+    }
+
+    let document_metadata = ssi_core::DidDocumentMetadata {
+        created,
+        updated,
+        deactivated,
+        version_id: None,
+        next_update: None,
+        equivalent_id: vec![],
+    };
+
+    let did_url = resolution_metadata
+        .did_url
+        .map(|url| ssi_core::DidResolutionURL {
+            did,
+            method_specific_id: url.method_specific_id,
+            method_name: url.method_name,
+        });
+
+    let resolution_metadata = ssi_core::ResolutionMetadata {
+        duration: resolution_metadata.duration,
+        error: error.map(|(code, _)| code),
+        content_type: resolution_metadata.content_type,
+        did_url,
+    };
+
+    Ok((document_metadata, resolution_metadata))
 }
 
 #[cfg(test)]
@@ -138,12 +339,16 @@ mod tests {
 
     use crate::{
         registry_client::{
-            registry::{CreateResponse, DidDocumentMetadata},
-            registry::{ResolutionMetadata, ResolveResponse},
+            registry::{CreateResponse, DidDocumentMetadata, RevokeResponse, UpdateResponse},
+            registry::{
+                ResolutionMetadata, ResolutionOption, ResolutionOptionAccept,
+                ResolveRepresentationResponse, ResolveResponse,
+            },
             MockRegistryClient,
         },
         RegistryResolver,
     };
+    use chrono::TimeZone;
     use ssi_core::DIDResolver;
 
     macro_rules! aw {
@@ -197,11 +402,18 @@ mod tests {
     }
 
     #[rstest::rstest]
-    #[case::network_failure(
+    #[case::invalid_argument(
         create_did(),
         create_did_doc(create_did()),
         Some(Err(tonic::Status::invalid_argument("message"))),
-        Some(ssi_core::error::ResolverError::NetworkFailure(r#"status: InvalidArgument, message: "message", details: [], metadata: MetadataMap { headers: {} }"#.to_string())),
+        Some(ssi_core::error::ResolverError::Unknown(r#"status: InvalidArgument, message: "message", details: [], metadata: MetadataMap { headers: {} }"#.to_string())),
+        false
+    )]
+    #[case::network_failure(
+        create_did(),
+        create_did_doc(create_did()),
+        Some(Err(tonic::Status::unavailable("message"))),
+        Some(ssi_core::error::ResolverError::NetworkFailure(r#"status: Unavailable, message: "message", details: [], metadata: MetadataMap { headers: {} }"#.to_string())),
         false
     )]
     #[case::success(
@@ -246,10 +458,105 @@ mod tests {
     }
 
     #[rstest::rstest]
-    #[case::network_failure(
+    #[case::success(create_did(), Some(Ok(tonic::Response::new(UpdateResponse {}))), None)]
+    #[case::permission_denied(
+        create_did(),
+        Some(Err(tonic::Status::permission_denied("message"))),
+        Some(ssi_core::error::ResolverError::Unauthorized(r#"status: PermissionDenied, message: "message", details: [], metadata: MetadataMap { headers: {} }"#.to_string()))
+    )]
+    #[case::not_found(
+        create_did(),
+        Some(Err(tonic::Status::not_found("message"))),
+        Some(ssi_core::error::ResolverError::DocumentNotFound("message".to_string()))
+    )]
+    #[case::method_not_supported(
+        "did:web:example.com".to_string(),
+        None,
+        Some(ssi_core::error::ResolverError::MethodNotSupported("did:web is not supported by the registry".to_string()))
+    )]
+    fn test_update(
+        #[case] did: String,
+        #[case] mock_update_response: Option<
+            Result<tonic::Response<UpdateResponse>, tonic::Status>,
+        >,
+        #[case] expect_error_kind: Option<ssi_core::error::ResolverError>,
+    ) {
+        let doc = create_did_doc(did.clone());
+        let mut mock_client = MockRegistryClient::default();
+        if let Some(res) = mock_update_response {
+            mock_client
+                .expect_update()
+                .with(
+                    mockall::predicate::eq(did.clone()),
+                    mockall::predicate::eq(doc.to_string()),
+                )
+                .return_once(|_, _| res);
+        }
+
+        let resolver = RegistryResolver {
+            client: mock_client,
+        };
+
+        let res = aw!(resolver.update(did, doc));
+        assert_eq!(res.err(), expect_error_kind);
+    }
+
+    #[rstest::rstest]
+    #[case::success(create_did(), Some(Ok(tonic::Response::new(RevokeResponse {}))), None)]
+    #[case::unauthenticated(
+        create_did(),
+        Some(Err(tonic::Status::unauthenticated("message"))),
+        Some(ssi_core::error::ResolverError::Unauthorized(r#"status: Unauthenticated, message: "message", details: [], metadata: MetadataMap { headers: {} }"#.to_string()))
+    )]
+    #[case::invalid_did(
+        "did:knox".to_string(),
+        None,
+        Some(ssi_core::error::ResolverError::InvalidDid("did:knox is not a valid DID".to_string()))
+    )]
+    fn test_deactivate(
+        #[case] did: String,
+        #[case] mock_revoke_response: Option<
+            Result<tonic::Response<RevokeResponse>, tonic::Status>,
+        >,
+        #[case] expect_error_kind: Option<ssi_core::error::ResolverError>,
+    ) {
+        let doc = create_did_doc(did.clone());
+        let mut mock_client = MockRegistryClient::default();
+        if let Some(res) = mock_revoke_response {
+            mock_client
+                .expect_revoke()
+                .with(
+                    mockall::predicate::eq(did.clone()),
+                    mockall::predicate::eq(doc.to_string()),
+                )
+                .return_once(|_, _| res);
+        }
+
+        let resolver = RegistryResolver {
+            client: mock_client,
+        };
+
+        let res = aw!(resolver.deactivate(did, doc));
+        assert_eq!(res.err(), expect_error_kind);
+    }
+
+    #[rstest::rstest]
+    #[case::invalid_argument(
         create_did(),
         Some(Err(tonic::Status::invalid_argument("message"))),
-        Some(ssi_core::error::ResolverError::NetworkFailure(r#"status: InvalidArgument, message: "message", details: [], metadata: MetadataMap { headers: {} }"#.to_string())),
+        Some(ssi_core::error::ResolverError::Unknown(r#"status: InvalidArgument, message: "message", details: [], metadata: MetadataMap { headers: {} }"#.to_string())),
+        false
+    )]
+    #[case::network_failure(
+        create_did(),
+        Some(Err(tonic::Status::unavailable("message"))),
+        Some(ssi_core::error::ResolverError::NetworkFailure(r#"status: Unavailable, message: "message", details: [], metadata: MetadataMap { headers: {} }"#.to_string())),
+        false
+    )]
+    #[case::deadline_exceeded(
+        create_did(),
+        Some(Err(tonic::Status::deadline_exceeded("message"))),
+        Some(ssi_core::error::ResolverError::Timeout(r#"status: DeadlineExceeded, message: "message", details: [], metadata: MetadataMap { headers: {} }"#.to_string())),
         false
     )]
     #[case::success(
@@ -276,6 +583,36 @@ mod tests {
         None,
         true
     )]
+    #[case::not_found(
+        create_did(),
+        Some(Err(tonic::Status::not_found("message"))),
+        Some(ssi_core::error::ResolverError::DocumentNotFound("message".to_string())),
+        false
+    )]
+    #[case::registry_error(
+        create_did(),
+        Some(Ok(tonic::Response::new(ResolveResponse {
+            did_resolution_metadata: Some(ResolutionMetadata {
+                error: Some("notFound".to_string()),
+                ..create_resolve_response().did_resolution_metadata.unwrap()
+            }),
+            ..create_resolve_response()
+        }))),
+        Some(ssi_core::error::ResolverError::DocumentNotFound("notFound".to_string())),
+        false
+    )]
+    #[case::invalid_did(
+        "did:knox".to_string(),
+        None,
+        Some(ssi_core::error::ResolverError::InvalidDid("did:knox is not a valid DID".to_string())),
+        false
+    )]
+    #[case::method_not_supported(
+        "did:web:example.com".to_string(),
+        None,
+        Some(ssi_core::error::ResolverError::MethodNotSupported("did:web is not supported by the registry".to_string())),
+        false
+    )]
     fn test_read(
         #[case] did: String,
         #[case] mock_read_response: Option<Result<tonic::Response<ResolveResponse>, tonic::Status>>,
@@ -286,8 +623,11 @@ mod tests {
         if let Some(res) = mock_read_response {
             mock_client
                 .expect_resolve()
-                .with(mockall::predicate::eq(did.clone()))
-                .return_once(|_| res);
+                .with(
+                    mockall::predicate::eq(did.clone()),
+                    mockall::predicate::eq(None),
+                )
+                .return_once(|_, _| res);
         }
 
         let resolver = RegistryResolver {
@@ -304,6 +644,266 @@ mod tests {
         }
     }
 
+    fn create_resolve_response() -> ResolveResponse {
+        ResolveResponse {
+            did_document: Some(create_proto_did_doc(create_did())),
+            did_document_metadata: Some(DidDocumentMetadata {
+                created: Some(pbjson_types::Timestamp {
+                    seconds: 0,
+                    nanos: 0,
+                }),
+                updated: Some(pbjson_types::Timestamp {
+                    seconds: 60,
+                    nanos: 0,
+                }),
+            }),
+            did_resolution_metadata: Some(ResolutionMetadata {
+                content_type: Some(ssi_core::DID_LD_JSON_CONTENT_TYPE.to_string()),
+                duration: None,
+                did_url: None,
+                error: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_resolve_deactivated() {
+        let mut mock_client = MockRegistryClient::default();
+        mock_client.expect_resolve().return_once(|_, _| {
+            Ok(tonic::Response::new(ResolveResponse {
+                did_resolution_metadata: Some(ResolutionMetadata {
+                    error: Some("deactivated".to_string()),
+                    ..create_resolve_response().did_resolution_metadata.unwrap()
+                }),
+                ..create_resolve_response()
+            }))
+        });
+
+        let resolver = RegistryResolver {
+            client: mock_client,
+        };
+
+        let rsp = aw!(resolver.resolve(create_did())).unwrap();
+        assert_eq!(rsp.did_document_metadata.deactivated, Some(true));
+        assert_eq!(
+            rsp.did_resolution_metadata.error,
+            Some(ssi_core::ResolutionErrorCode::Deactivated)
+        );
+    }
+
+    #[rstest::rstest]
+    #[case::json_ld(
+        None,
+        ResolutionOptionAccept::JsonLd,
+        Some(ssi_core::DID_LD_JSON_CONTENT_TYPE)
+    )]
+    #[case::json(
+        Some(ssi_core::DID_JSON_CONTENT_TYPE),
+        ResolutionOptionAccept::Unspecified,
+        Some(ssi_core::DID_JSON_CONTENT_TYPE)
+    )]
+    fn test_resolve_representation(
+        #[case] accept: Option<&str>,
+        #[case] expected_accept: ResolutionOptionAccept,
+        #[case] expected_content_type: Option<&str>,
+    ) {
+        // Signatures are verified over the exact bytes, which must not be normalized by the resolver
+        let stream = r#"{ "id": "did:knox:z6MkfFmsob7fC3MmqU1JVfdBnMbnAw7xm1mrEtPvAoojLcRh" }"#;
+        let resolve_response = create_resolve_response();
+        let mut mock_client = MockRegistryClient::default();
+        mock_client
+            .expect_resolve_representation()
+            .with(
+                mockall::predicate::eq(create_did()),
+                mockall::predicate::eq(Some(ResolutionOption {
+                    accept: expected_accept.into(),
+                })),
+            )
+            .return_once(move |_, _| {
+                Ok(tonic::Response::new(ResolveRepresentationResponse {
+                    did_document_stream: stream.to_string(),
+                    did_document_metadata: resolve_response.did_document_metadata,
+                    // The content type defaults to the requested one
+                    did_resolution_metadata: Some(ResolutionMetadata {
+                        content_type: None,
+                        ..resolve_response.did_resolution_metadata.unwrap()
+                    }),
+                }))
+            });
+
+        let resolver = RegistryResolver {
+            client: mock_client,
+        };
+
+        let rsp = aw!(resolver.resolve_representation(
+            create_did(),
+            ssi_core::ResolutionOptions {
+                accept: accept.map(str::to_string),
+                ..Default::default()
+            }
+        ))
+        .unwrap();
+        assert_eq!(rsp.did_document_stream, stream.as_bytes());
+        assert_eq!(rsp.content_type(), expected_content_type);
+    }
+
+    #[test]
+    fn test_resolve_representation_error() {
+        let mut mock_client = MockRegistryClient::default();
+        mock_client
+            .expect_resolve_representation()
+            .return_once(|_, _| Err(tonic::Status::not_found("message")));
+
+        let resolver = RegistryResolver {
+            client: mock_client,
+        };
+
+        let res =
+            aw!(resolver
+                .resolve_representation(create_did(), ssi_core::ResolutionOptions::default()));
+        assert_eq!(
+            res.unwrap_err(),
+            ssi_core::error::ResolverError::DocumentNotFound("message".to_string())
+        );
+    }
+
+    #[rstest::rstest]
+    #[case::json_ld(
+        ssi_core::ResolutionOptions {
+            accept: Some(ssi_core::DID_LD_JSON_CONTENT_TYPE.to_string()),
+            ..Default::default()
+        },
+        Some(ResolutionOption {
+            accept: ResolutionOptionAccept::JsonLd.into(),
+        }),
+        None
+    )]
+    #[case::version_time_after_update(
+        ssi_core::ResolutionOptions {
+            version_time: Some(chrono::Utc.timestamp_opt(60, 0).unwrap()),
+            ..Default::default()
+        },
+        None,
+        None
+    )]
+    #[case::version_time_before_update(
+        ssi_core::ResolutionOptions {
+            version_time: Some(chrono::Utc.timestamp_opt(30, 0).unwrap()),
+            ..Default::default()
+        },
+        None,
+        Some(ssi_core::error::ResolverError::DocumentNotFound(
+            "Only the latest version of the document is available in the registry".to_string()
+        ))
+    )]
+    #[case::unsupported_representation(
+        ssi_core::ResolutionOptions {
+            accept: Some("text/html".to_string()),
+            ..Default::default()
+        },
+        None,
+        Some(ssi_core::error::ResolverError::RepresentationNotSupported(
+            "text/html is not a supported representation".to_string()
+        ))
+    )]
+    fn test_resolve_with_options(
+        #[case] options: ssi_core::ResolutionOptions,
+        #[case] expected_option: Option<ResolutionOption>,
+        #[case] expect_error_kind: Option<ssi_core::error::ResolverError>,
+    ) {
+        let mut mock_client = MockRegistryClient::default();
+        mock_client
+            .expect_resolve()
+            .with(
+                mockall::predicate::eq(create_did()),
+                mockall::predicate::eq(expected_option),
+            )
+            .returning(|_, _| Ok(tonic::Response::new(create_resolve_response())));
+
+        let resolver = RegistryResolver {
+            client: mock_client,
+        };
+
+        let res = aw!(resolver.resolve_with_options(create_did(), options));
+        match (res, expect_error_kind) {
+            (Ok(rsp), None) => {
+                assert_eq!(rsp.did_document_metadata.updated.timestamp(), 60);
+                assert_eq!(rsp.did_document_metadata.deactivated, None);
+                assert_eq!(rsp.did_document_metadata.version_id, None);
+            }
+            (Err(e), Some(expected)) => assert_eq!(e, expected),
+            (res, expected) => panic!("unexpected result {res:?}, expected {expected:?}"),
+        }
+    }
+
+    // The registry fails `failures` times with `status` before answering.
+    #[rstest::rstest]
+    #[case::recovers_from_unavailable(tonic::Status::unavailable("down"), 2, 3, true)]
+    #[case::recovers_from_deadline_exceeded(tonic::Status::deadline_exceeded("slow"), 1, 2, true)]
+    #[case::retries_exhausted(tonic::Status::unavailable("down"), 5, 4, false)]
+    #[case::not_retried(tonic::Status::invalid_argument("invalid"), 1, 1, false)]
+    fn test_resolve_flaky_registry(
+        #[case] status: tonic::Status,
+        #[case] failures: usize,
+        #[case] expected_calls: usize,
+        #[case] expect_ok: bool,
+    ) {
+        let calls = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let mut mock_client = MockRegistryClient::default();
+        let counter = calls.clone();
+        mock_client.expect_resolve().returning(move |_, _| {
+            if counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst) < failures {
+                Err(tonic::Status::new(status.code(), status.message()))
+            } else {
+                Ok(tonic::Response::new(create_resolve_response()))
+            }
+        });
+
+        let resolver = ssi_core::resolver::retry::RetryResolver::new(RegistryResolver {
+            client: mock_client,
+        })
+        .with_retry_policy(ssi_core::resolver::retry::RetryPolicy {
+            max_retries: 3,
+            initial_backoff: std::time::Duration::from_millis(1),
+            ..Default::default()
+        });
+
+        let res = aw!(resolver.resolve(create_did()));
+        assert_eq!(res.is_ok(), expect_ok);
+        assert_eq!(
+            calls.load(std::sync::atomic::Ordering::SeqCst),
+            expected_calls
+        );
+    }
+
+    #[test]
+    fn test_resolve_circuit_breaker() {
+        let mut mock_client = MockRegistryClient::default();
+        mock_client
+            .expect_resolve()
+            .times(2)
+            .returning(|_, _| Err(tonic::Status::unavailable("down")));
+
+        let resolver = ssi_core::resolver::retry::RetryResolver::new(RegistryResolver {
+            client: mock_client,
+        })
+        .with_retry_policy(ssi_core::resolver::retry::RetryPolicy::none())
+        .with_circuit_breaker(ssi_core::resolver::retry::CircuitBreakerPolicy {
+            failure_threshold: 2,
+            ..Default::default()
+        });
+
+        // The third request is rejected by the open circuit without reaching the registry
+        for _ in 0..3 {
+            let res = aw!(resolver.resolve(create_did()));
+            assert!(matches!(
+                res,
+                Err(ssi_core::error::ResolverError::NetworkFailure(_))
+            ));
+        }
+        assert!(resolver.is_circuit_open());
+    }
+
     #[test]
     fn test_get_method() -> Result<(), String> {
         let mock_client = MockRegistryClient::default();
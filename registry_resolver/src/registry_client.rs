@@ -33,15 +33,52 @@ impl RegistryClient for GrpcClient {
     async fn resolve(
         &self,
         did: String,
+        resolution_option: Option<registry::ResolutionOption>,
     ) -> Result<tonic::Response<registry::ResolveResponse>, tonic::Status> {
         let mut client = self.inner.to_owned();
         return client
             .resolve(registry::ResolveRequest {
                 did,
-                resolution_option: None,
+                resolution_option,
             })
             .await;
     }
+
+    async fn resolve_representation(
+        &self,
+        did: String,
+        resolution_option: Option<registry::ResolutionOption>,
+    ) -> Result<tonic::Response<registry::ResolveRepresentationResponse>, tonic::Status> {
+        let mut client = self.inner.to_owned();
+        return client
+            .resolve_representation(registry::ResolveRepresentationRequest {
+                did,
+                resolution_option,
+            })
+            .await;
+    }
+
+    async fn update(
+        &self,
+        did: String,
+        document: String,
+    ) -> Result<tonic::Response<registry::UpdateResponse>, tonic::Status> {
+        let mut client = self.inner.to_owned();
+        return client
+            .update(registry::UpdateRequest { did, document })
+            .await;
+    }
+
+    async fn revoke(
+        &self,
+        did: String,
+        document: String,
+    ) -> Result<tonic::Response<registry::RevokeResponse>, tonic::Status> {
+        let mut client = self.inner.to_owned();
+        return client
+            .revoke(registry::RevokeRequest { did, document })
+            .await;
+    }
 }
 
 #[mockall::automock]
@@ -56,7 +93,26 @@ pub trait RegistryClient: Send + Sync + std::fmt::Debug {
     async fn resolve(
         &self,
         did: String,
+        resolution_option: Option<registry::ResolutionOption>,
     ) -> Result<tonic::Response<registry::ResolveResponse>, tonic::Status>;
+
+    async fn resolve_representation(
+        &self,
+        did: String,
+        resolution_option: Option<registry::ResolutionOption>,
+    ) -> Result<tonic::Response<registry::ResolveRepresentationResponse>, tonic::Status>;
+
+    async fn update(
+        &self,
+        did: String,
+        document: String,
+    ) -> Result<tonic::Response<registry::UpdateResponse>, tonic::Status>;
+
+    async fn revoke(
+        &self,
+        did: String,
+        document: String,
+    ) -> Result<tonic::Response<registry::RevokeResponse>, tonic::Status>;
 }
 
 impl Clone for MockRegistryClient {
@@ -0,0 +1,644 @@
+use crate::registry_client::registry::{
+    status_list_registry_service_client::StatusListRegistryServiceClient,
+    GetStatusListCredentialRequest,
+};
+pub use crate::registry_client::registry::{
+    CreateStatusListEntryRequest, CreateStatusListEntryResponse, GetStatusListCredentialResponse,
+    StatusListEntry, StatusPurpose, UpdateStatusListEntryRequest, UpdateStatusListEntryResponse,
+};
+
+#[derive(Clone, Debug)]
+pub struct GrpcStatusListClient {
+    inner: StatusListRegistryServiceClient<tonic::transport::Channel>,
+}
+
+impl GrpcStatusListClient {
+    pub async fn new(url: String) -> Self {
+        let inner = StatusListRegistryServiceClient::connect(url).await.unwrap();
+        Self { inner }
+    }
+}
+
+#[async_trait::async_trait]
+impl StatusListClient for GrpcStatusListClient {
+    async fn create_status_list_entry(
+        &self,
+        request: CreateStatusListEntryRequest,
+    ) -> Result<tonic::Response<CreateStatusListEntryResponse>, tonic::Status> {
+        let mut client = self.inner.to_owned();
+        return client.create_status_list_entry(request).await;
+    }
+
+    async fn get_status_list_credential(
+        &self,
+        status_list_credential_id: String,
+    ) -> Result<tonic::Response<GetStatusListCredentialResponse>, tonic::Status> {
+        let mut client = self.inner.to_owned();
+        return client
+            .get_status_list_credential(GetStatusListCredentialRequest {
+                status_list_credential_id,
+            })
+            .await;
+    }
+
+    async fn update_status_list_entry(
+        &self,
+        request: UpdateStatusListEntryRequest,
+    ) -> Result<tonic::Response<UpdateStatusListEntryResponse>, tonic::Status> {
+        let mut client = self.inner.to_owned();
+        return client.update_status_list_entry(request).await;
+    }
+}
+
+#[mockall::automock]
+#[async_trait::async_trait]
+pub trait StatusListClient: Send + Sync + std::fmt::Debug {
+    async fn create_status_list_entry(
+        &self,
+        request: CreateStatusListEntryRequest,
+    ) -> Result<tonic::Response<CreateStatusListEntryResponse>, tonic::Status>;
+
+    async fn get_status_list_credential(
+        &self,
+        status_list_credential_id: String,
+    ) -> Result<tonic::Response<GetStatusListCredentialResponse>, tonic::Status>;
+
+    async fn update_status_list_entry(
+        &self,
+        request: UpdateStatusListEntryRequest,
+    ) -> Result<tonic::Response<UpdateStatusListEntryResponse>, tonic::Status>;
+}
+
+/// Typed access to the `StatusListRegistryService` of the registry, which allocates an entry in a status list
+/// credential for each issued credential.
+/// Issuers allocate an entry with `create_entry` when issuing a credential and set it with `revoke` or `suspend`,
+/// verifiers use `check_status` to refuse revoked and suspended credentials.
+#[derive(Clone, Debug)]
+pub struct StatusListRegistry<T = GrpcStatusListClient>
+where
+    T: StatusListClient + 'static,
+{
+    client: T,
+}
+
+impl StatusListRegistry<GrpcStatusListClient> {
+    pub async fn new(url: impl Into<String>) -> Self {
+        let client = GrpcStatusListClient::new(url.into()).await;
+        StatusListRegistry { client }
+    }
+}
+
+impl<T> StatusListRegistry<T>
+where
+    T: StatusListClient,
+{
+    pub fn with_client(client: T) -> Self {
+        StatusListRegistry { client }
+    }
+
+    /// Allocate an unset entry for the credential in a status list of the given purpose.
+    pub async fn create_entry(
+        &self,
+        credential: &ssi_core::credential::Credential,
+        status_purpose: StatusPurpose,
+    ) -> Result<StatusListEntry, ssi_core::error::ResolverError> {
+        let credential_id = credential.id.clone().ok_or_else(|| {
+            ssi_core::error::ResolverError::InvalidData(
+                "A status list entry can only be created for a credential with an id".to_string(),
+            )
+        })?;
+        // The most specific type of the credential, `VerifiableCredential` being common to all credentials
+        let credential_type = credential
+            .cred_type
+            .iter()
+            .rfind(|cred_type| {
+                **cred_type != ssi_core::credential::CredentialType::VerifiableCredential
+            })
+            .unwrap_or(&ssi_core::credential::CredentialType::VerifiableCredential)
+            .as_str()
+            .to_string();
+
+        self.client
+            .create_status_list_entry(CreateStatusListEntryRequest {
+                credential_id,
+                credential_issuer_id: credential.issuer.id().to_string(),
+                status_purpose: status_purpose.into(),
+                credential_type,
+                status: false,
+            })
+            .await
+            .map_err(crate::status_to_resolver_error)?
+            .into_inner()
+            .status_list_entry
+            .ok_or_else(|| {
+                ssi_core::error::ResolverError::InvalidData(
+                    "No status list entry found in registry response".to_string(),
+                )
+            })
+    }
+
+    pub async fn set_status(
+        &self,
+        entry: &StatusListEntry,
+        status: bool,
+    ) -> Result<(), ssi_core::error::ResolverError> {
+        self.client
+            .update_status_list_entry(UpdateStatusListEntryRequest {
+                credential_id: entry.credential_id.clone(),
+                credential_issuer_id: entry.credential_issuer_id.clone(),
+                status_purpose: entry.status_purpose,
+                credential_type: entry.credential_type.clone(),
+                status,
+            })
+            .await
+            .map_err(crate::status_to_resolver_error)?;
+
+        Ok(())
+    }
+
+    /// Revoke the credential of a revocation entry, revocation can not be undone.
+    pub async fn revoke(
+        &self,
+        entry: &StatusListEntry,
+    ) -> Result<(), ssi_core::error::ResolverError> {
+        check_purpose(entry, StatusPurpose::Revocation)?;
+        self.set_status(entry, true).await
+    }
+
+    pub async fn suspend(
+        &self,
+        entry: &StatusListEntry,
+    ) -> Result<(), ssi_core::error::ResolverError> {
+        check_purpose(entry, StatusPurpose::Suspension)?;
+        self.set_status(entry, true).await
+    }
+
+    pub async fn unsuspend(
+        &self,
+        entry: &StatusListEntry,
+    ) -> Result<(), ssi_core::error::ResolverError> {
+        check_purpose(entry, StatusPurpose::Suspension)?;
+        self.set_status(entry, false).await
+    }
+
+    pub async fn get_status_list_credential(
+        &self,
+        status_list_credential_id: &str,
+    ) -> Result<serde_json::Value, ssi_core::error::ResolverError> {
+        let res = self
+            .client
+            .get_status_list_credential(status_list_credential_id.to_string())
+            .await
+            .map_err(crate::status_to_resolver_error)?
+            .into_inner();
+
+        serde_json::from_str(&res.status_list_credential)
+            .map_err(|e| ssi_core::error::ResolverError::InvalidData(e.to_string()))
+    }
+
+    /// Fetch the status list credential and read the bit at `status_list_index`.
+    /// The list must be issued and signed by `issuer`, the issuer of the credential, whose keys are resolved through
+    /// `did_resolver`, see ssi_core::credential::status::verify_status_list_credential.
+    pub async fn get_status(
+        &self,
+        status_list_credential_id: &str,
+        status_list_index: usize,
+        issuer: &str,
+        did_resolver: &(impl ssi_core::DIDResolver + ?Sized),
+    ) -> Result<bool, ssi_core::error::ResolverError> {
+        let status_list_credential = self
+            .get_status_list_credential(status_list_credential_id)
+            .await?;
+        ssi_core::credential::status::verify_status_list_credential(
+            &status_list_credential,
+            issuer,
+            did_resolver,
+        )
+        .await
+        .map_err(|e| ssi_core::error::ResolverError::InvalidData(e.to_string()))?;
+
+        ssi_core::credential::status::StatusList::from_credential(&status_list_credential)
+            .and_then(|status_list| status_list.get(status_list_index))
+            .map_err(|e| ssi_core::error::ResolverError::InvalidData(e.to_string()))
+    }
+
+    /// Fail with `Unauthorized` when the credential at `status_list_index` is revoked or suspended, depending on the
+    /// purpose of the status list. The list must be issued by `issuer`, see `get_status`.
+    pub async fn check_status(
+        &self,
+        status_list_credential_id: &str,
+        status_list_index: usize,
+        status_purpose: StatusPurpose,
+        issuer: &str,
+        did_resolver: &(impl ssi_core::DIDResolver + ?Sized),
+    ) -> Result<(), ssi_core::error::ResolverError> {
+        if !self
+            .get_status(
+                status_list_credential_id,
+                status_list_index,
+                issuer,
+                did_resolver,
+            )
+            .await?
+        {
+            return Ok(());
+        }
+
+        let status = match status_purpose {
+            StatusPurpose::Suspension => "suspended",
+            _ => "revoked",
+        };
+        Err(ssi_core::error::ResolverError::Unauthorized(format!(
+            "Credential at index {status_list_index} of {status_list_credential_id} is {status}"
+        )))
+    }
+}
+
+#[async_trait::async_trait]
+impl<T> ssi_core::credential::status::StatusListResolver for StatusListRegistry<T>
+where
+    T: StatusListClient,
+{
+    async fn resolve_status_list_credential(
+        &self,
+        status_list_credential: &str,
+    ) -> Result<serde_json::Value, ssi_core::error::ResolverError> {
+        self.get_status_list_credential(status_list_credential)
+            .await
+    }
+}
+
+/// The `credentialStatus` entry to include in the credential for which the registry allocated `entry`.
+pub fn to_credential_status(
+    entry: &StatusListEntry,
+) -> Result<ssi_core::credential::status::StatusListEntry, ssi_core::error::ResolverError> {
+    let status_purpose = match entry.status_purpose() {
+        StatusPurpose::Revocation => ssi_core::credential::status::StatusPurpose::Revocation,
+        StatusPurpose::Suspension => ssi_core::credential::status::StatusPurpose::Suspension,
+        StatusPurpose::Unspecified => {
+            return Err(ssi_core::error::ResolverError::InvalidData(format!(
+                "The status list entry of {} has no purpose",
+                entry.credential_id
+            )))
+        }
+    };
+    let status_list_index = usize::try_from(entry.status_list_index)
+        .map_err(|e| ssi_core::error::ResolverError::InvalidData(e.to_string()))?;
+
+    Ok(ssi_core::credential::status::StatusListEntry {
+        id: Some(format!(
+            "{}#{status_list_index}",
+            entry.status_list_credential_id
+        )),
+        entry_type: ssi_core::credential::status::StatusListEntryType::BitstringStatusListEntry,
+        status_purpose,
+        status_list_index,
+        status_list_credential: entry.status_list_credential_id.clone(),
+    })
+}
+
+fn check_purpose(
+    entry: &StatusListEntry,
+    status_purpose: StatusPurpose,
+) -> Result<(), ssi_core::error::ResolverError> {
+    if entry.status_purpose() != status_purpose {
+        return Err(ssi_core::error::ResolverError::InvalidData(format!(
+            "The status list entry of {} has purpose {}, expected {}",
+            entry.credential_id,
+            entry.status_purpose().as_str_name(),
+            status_purpose.as_str_name()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        to_credential_status, CreateStatusListEntryRequest, CreateStatusListEntryResponse,
+        GetStatusListCredentialResponse, MockStatusListClient, StatusListEntry, StatusListRegistry,
+        StatusPurpose, UpdateStatusListEntryRequest, UpdateStatusListEntryResponse,
+    };
+
+    macro_rules! aw {
+        ($e:expr) => {
+            tokio_test::block_on($e)
+        };
+    }
+
+    const CREDENTIAL_ID: &str = "https://issuer.oidp.uscis.gov/credentials/83627465";
+    const ISSUER_DID: &str = "did:knox:z6MkfFmsob7fC3MmqU1JVfdBnMbnAw7xm1mrEtPvAoojLcRh";
+    const STATUS_LIST_CREDENTIAL_ID: &str = "https://example.com/status/3";
+
+    fn create_credential() -> ssi_core::credential::Credential {
+        ssi_core::credential::Credential {
+            context: vec![],
+            id: Some(CREDENTIAL_ID.to_string()),
+            cred_type: vec![
+                ssi_core::credential::CredentialType::VerifiableCredential,
+                ssi_core::credential::CredentialType::BankAccount,
+            ],
+            issuance_date: None,
+            expiration_date: None,
+            valid_from: None,
+            valid_until: None,
+            name: None,
+            description: None,
+            issuer: ISSUER_DID.into(),
+            subject: ssi_core::credential::CredentialSubject::Single(Default::default()),
+            credential_status: None,
+            credential_schema: None,
+            property_set: Default::default(),
+        }
+    }
+
+    fn create_entry(status_purpose: StatusPurpose) -> StatusListEntry {
+        StatusListEntry {
+            credential_id: CREDENTIAL_ID.to_string(),
+            credential_issuer_id: ISSUER_DID.to_string(),
+            status_purpose: status_purpose.into(),
+            credential_type: "BankAccount".to_string(),
+            status_list_index: 94567,
+            status_list_credential_id: STATUS_LIST_CREDENTIAL_ID.to_string(),
+            status: false,
+        }
+    }
+
+    // The signer of the status list credentials and a DID resolver of its DID Document
+    fn create_issuer() -> (
+        String,
+        signature::suite::ed25519_2020::Ed25519DidSigner,
+        ssi_core::MockDIDResolver,
+    ) {
+        let kp =
+            signature::suite::ed25519_2020::Ed25519KeyPair::new("knox".to_string(), None).unwrap();
+        let issuer = signature::suite::KeyPair::get_did(&kp);
+        let did_document = aw!(ssi_core::identity::create_identity(
+            signature::suite::ed25519_2020::Ed25519DidVerifier::from(kp.clone()),
+            vec![]
+        ))
+        .unwrap();
+        let mut did_resolver = ssi_core::MockDIDResolver::default();
+        did_resolver.expect_resolve().returning(move |_| {
+            Ok(ssi_core::ResolveResponse {
+                did_document: serde_json::to_value(&did_document).unwrap(),
+                did_document_metadata: Default::default(),
+                did_resolution_metadata: Default::default(),
+            })
+        });
+        (issuer, kp.into(), did_resolver)
+    }
+
+    fn create_status_list_credential(
+        set_indexes: &[usize],
+        issuer: &str,
+        signer: &signature::suite::ed25519_2020::Ed25519DidSigner,
+    ) -> String {
+        let mut status_list = ssi_core::credential::status::StatusList::default();
+        for index in set_indexes {
+            status_list.set(*index, true).unwrap();
+        }
+        let mut credential = serde_json::json!({
+            "@context": ["https://www.w3.org/ns/credentials/v2"],
+            "id": STATUS_LIST_CREDENTIAL_ID,
+            "type": ["VerifiableCredential", "BitstringStatusListCredential"],
+            "issuer": issuer,
+            "credentialSubject": {
+                "id": format!("{STATUS_LIST_CREDENTIAL_ID}#list"),
+                "type": "BitstringStatusList",
+                "statusPurpose": "revocation",
+                "encodedList": status_list.encode().unwrap(),
+            }
+        });
+        let proof = ssi_core::proof::create_data_integrity_proof(
+            signer,
+            credential.clone(),
+            signature::suite::VerificationRelation::AssertionMethod,
+            &ssi_core::clock::SystemClock,
+        )
+        .unwrap();
+        credential["proof"] = serde_json::to_value(proof).unwrap();
+        credential.to_string()
+    }
+
+    #[test]
+    fn test_create_entry() {
+        let mut mock_client = MockStatusListClient::default();
+        mock_client
+            .expect_create_status_list_entry()
+            .with(mockall::predicate::eq(CreateStatusListEntryRequest {
+                credential_id: CREDENTIAL_ID.to_string(),
+                credential_issuer_id: ISSUER_DID.to_string(),
+                status_purpose: StatusPurpose::Revocation.into(),
+                credential_type: "BankAccount".to_string(),
+                status: false,
+            }))
+            .return_once(|_| {
+                Ok(tonic::Response::new(CreateStatusListEntryResponse {
+                    status_list_entry: Some(create_entry(StatusPurpose::Revocation)),
+                }))
+            });
+        let registry = StatusListRegistry::with_client(mock_client);
+
+        let entry = aw!(registry.create_entry(&create_credential(), StatusPurpose::Revocation));
+        assert_eq!(entry, Ok(create_entry(StatusPurpose::Revocation)));
+
+        let credential = ssi_core::credential::Credential {
+            id: None,
+            ..create_credential()
+        };
+        let res = aw!(registry.create_entry(&credential, StatusPurpose::Revocation));
+        assert!(matches!(
+            res,
+            Err(ssi_core::error::ResolverError::InvalidData(_))
+        ));
+    }
+
+    #[rstest::rstest]
+    #[case::revoke(StatusPurpose::Revocation, true)]
+    #[case::suspend(StatusPurpose::Suspension, true)]
+    #[case::unsuspend(StatusPurpose::Suspension, false)]
+    fn test_set_status(#[case] status_purpose: StatusPurpose, #[case] status: bool) {
+        let entry = create_entry(status_purpose);
+        let mut mock_client = MockStatusListClient::default();
+        mock_client
+            .expect_update_status_list_entry()
+            .with(mockall::predicate::eq(UpdateStatusListEntryRequest {
+                credential_id: CREDENTIAL_ID.to_string(),
+                credential_issuer_id: ISSUER_DID.to_string(),
+                status_purpose: status_purpose.into(),
+                credential_type: "BankAccount".to_string(),
+                status,
+            }))
+            .return_once(|_| Ok(tonic::Response::new(UpdateStatusListEntryResponse {})));
+        let registry = StatusListRegistry::with_client(mock_client);
+
+        let res = match (status_purpose, status) {
+            (StatusPurpose::Revocation, _) => aw!(registry.revoke(&entry)),
+            (_, true) => aw!(registry.suspend(&entry)),
+            (_, false) => aw!(registry.unsuspend(&entry)),
+        };
+        assert_eq!(res, Ok(()));
+    }
+
+    #[test]
+    fn test_revoke_suspension_entry() {
+        let mut mock_client = MockStatusListClient::default();
+        mock_client.expect_update_status_list_entry().never();
+        let registry = StatusListRegistry::with_client(mock_client);
+
+        let res = aw!(registry.revoke(&create_entry(StatusPurpose::Suspension)));
+        assert_eq!(
+            res,
+            Err(ssi_core::error::ResolverError::InvalidData(format!(
+                "The status list entry of {CREDENTIAL_ID} has purpose STATUS_PURPOSE_SUSPENSION, expected STATUS_PURPOSE_REVOCATION"
+            )))
+        );
+    }
+
+    #[rstest::rstest]
+    #[case::unset(&[1, 94566], StatusPurpose::Revocation, Ok(()))]
+    #[case::revoked(
+        &[94567],
+        StatusPurpose::Revocation,
+        Err(ssi_core::error::ResolverError::Unauthorized(format!(
+            "Credential at index 94567 of {STATUS_LIST_CREDENTIAL_ID} is revoked"
+        )))
+    )]
+    #[case::suspended(
+        &[94567],
+        StatusPurpose::Suspension,
+        Err(ssi_core::error::ResolverError::Unauthorized(format!(
+            "Credential at index 94567 of {STATUS_LIST_CREDENTIAL_ID} is suspended"
+        )))
+    )]
+    fn test_check_status(
+        #[case] set_indexes: &[usize],
+        #[case] status_purpose: StatusPurpose,
+        #[case] expected: Result<(), ssi_core::error::ResolverError>,
+    ) {
+        let (issuer, signer, did_resolver) = create_issuer();
+        let status_list_credential = create_status_list_credential(set_indexes, &issuer, &signer);
+        let mut mock_client = MockStatusListClient::default();
+        mock_client
+            .expect_get_status_list_credential()
+            .with(mockall::predicate::eq(
+                STATUS_LIST_CREDENTIAL_ID.to_string(),
+            ))
+            .return_once(|_| {
+                Ok(tonic::Response::new(GetStatusListCredentialResponse {
+                    status_list_credential,
+                }))
+            });
+        let registry = StatusListRegistry::with_client(mock_client);
+
+        let res = aw!(registry.check_status(
+            STATUS_LIST_CREDENTIAL_ID,
+            94567,
+            status_purpose,
+            &issuer,
+            &did_resolver
+        ));
+        assert_eq!(res, expected);
+    }
+
+    #[test]
+    fn test_to_credential_status() {
+        let credential_status = to_credential_status(&create_entry(StatusPurpose::Suspension));
+        assert_eq!(
+            credential_status,
+            Ok(ssi_core::credential::status::StatusListEntry {
+                id: Some(format!("{STATUS_LIST_CREDENTIAL_ID}#94567")),
+                entry_type:
+                    ssi_core::credential::status::StatusListEntryType::BitstringStatusListEntry,
+                status_purpose: ssi_core::credential::status::StatusPurpose::Suspension,
+                status_list_index: 94567,
+                status_list_credential: STATUS_LIST_CREDENTIAL_ID.to_string(),
+            })
+        );
+
+        let entry = StatusListEntry {
+            status_list_index: -1,
+            ..create_entry(StatusPurpose::Revocation)
+        };
+        assert!(to_credential_status(&entry).is_err());
+    }
+
+    #[test]
+    fn test_verify_credential_status() {
+        let (issuer, signer, did_resolver) = create_issuer();
+        let status_list_credential = create_status_list_credential(&[94567], &issuer, &signer);
+        let mut mock_client = MockStatusListClient::default();
+        mock_client
+            .expect_get_status_list_credential()
+            .return_once(|_| {
+                Ok(tonic::Response::new(GetStatusListCredentialResponse {
+                    status_list_credential,
+                }))
+            });
+        let registry = StatusListRegistry::with_client(mock_client);
+        let credential = ssi_core::credential::Credential {
+            credential_status: Some(
+                to_credential_status(&create_entry(StatusPurpose::Revocation))
+                    .unwrap()
+                    .into(),
+            ),
+            issuer: issuer.into(),
+            ..create_credential()
+        };
+
+        let res = aw!(ssi_core::credential::status::verify_credential_status(
+            &credential,
+            &registry,
+            &did_resolver
+        ));
+        assert!(matches!(
+            res,
+            Err(ssi_core::error::Error::CredentialRevoked(_))
+        ));
+    }
+
+    // Lists that are not issued and signed by the issuer of the credential can not un-revoke it
+    #[rstest::rstest]
+    #[case::empty("empty")]
+    #[case::other_issuer("other_issuer")]
+    #[case::unsigned("unsigned")]
+    fn test_check_status_untrusted_credential(#[case] untrusted: &str) {
+        let (issuer, signer, did_resolver) = create_issuer();
+        let status_list_credential = match untrusted {
+            "empty" => "{}".to_string(),
+            "other_issuer" => {
+                let (other_issuer, other_signer, _) = create_issuer();
+                create_status_list_credential(&[], &other_issuer, &other_signer)
+            }
+            _ => {
+                let mut credential: serde_json::Value =
+                    serde_json::from_str(&create_status_list_credential(&[], &issuer, &signer))
+                        .unwrap();
+                credential.as_object_mut().unwrap().remove("proof");
+                credential.to_string()
+            }
+        };
+        let mut mock_client = MockStatusListClient::default();
+        mock_client
+            .expect_get_status_list_credential()
+            .return_once(|_| {
+                Ok(tonic::Response::new(GetStatusListCredentialResponse {
+                    status_list_credential,
+                }))
+            });
+        let registry = StatusListRegistry::with_client(mock_client);
+
+        let res = aw!(registry.check_status(
+            STATUS_LIST_CREDENTIAL_ID,
+            94567,
+            StatusPurpose::Revocation,
+            &issuer,
+            &did_resolver
+        ));
+        assert!(matches!(
+            res,
+            Err(ssi_core::error::ResolverError::InvalidData(e)) if e.starts_with("Invalid Status List: ")
+        ));
+    }
+}
@@ -0,0 +1,325 @@
+// This module provides typed DIDs and DID URLs following the syntax defined in https://www.w3.org/TR/did-core/#did-syntax
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    #[error("Invalid DID: {0}")]
+    InvalidDid(String),
+}
+
+/// did = "did:" method-name ":" method-specific-id
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Did {
+    method: String,
+    method_specific_id: String,
+}
+
+/// did-url = did path-abempty [ "?" query ] [ "#" fragment ]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct DidUrl {
+    did: Did,
+    path: Option<String>,
+    query: Option<String>,
+    fragment: Option<String>,
+}
+
+impl Did {
+    pub fn new(method: &str, method_specific_id: &str) -> Result<Self, Error> {
+        Did::from_str(&format!("did:{method}:{method_specific_id}"))
+    }
+
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    pub fn method_specific_id(&self) -> &str {
+        &self.method_specific_id
+    }
+
+    /// Create the DID URL pointing to `fragment` in the DID Document of this DID, e.g. a verification method.
+    pub fn with_fragment(&self, fragment: &str) -> Result<DidUrl, Error> {
+        DidUrl::from_relative(self, &format!("#{fragment}"))
+    }
+}
+
+impl DidUrl {
+    pub fn did(&self) -> &Did {
+        &self.did
+    }
+
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    pub fn query(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+
+    pub fn fragment(&self) -> Option<&str> {
+        self.fragment.as_deref()
+    }
+
+    /// Returns the value of the `name` DID parameter in the query, e.g. `service` or `versionId`.
+    pub fn query_param(&self, name: &str) -> Option<&str> {
+        self.query()?
+            .split('&')
+            .filter_map(|param| param.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Given a relative DID URL (e.g. `#key-1` or `/path?query`), resolve it against the `base` DID.
+    pub fn from_relative(base: &Did, relative: &str) -> Result<Self, Error> {
+        if !matches!(relative.chars().next(), Some('#' | '?' | '/')) {
+            return Err(Error::InvalidDid(format!(
+                "{relative} is not a relative DID URL"
+            )));
+        }
+
+        DidUrl::from_str(&format!("{base}{relative}"))
+    }
+}
+
+impl FromStr for Did {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidDid(format!("{s} is not a valid DID"));
+
+        let rest = s.strip_prefix("did:").ok_or_else(invalid)?;
+        let (method, method_specific_id) = rest.split_once(':').ok_or_else(invalid)?;
+
+        // method-name = 1*method-char, method-char = %x61-7A / DIGIT
+        let is_valid_method = !method.is_empty()
+            && method
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit());
+
+        // method-specific-id = *( *idchar ":" ) 1*idchar, idchar = ALPHA / DIGIT / "." / "-" / "_" / pct-encoded
+        let is_valid_method_specific_id = !method_specific_id.is_empty()
+            && !method_specific_id.ends_with(':')
+            && is_pct_encoded_valid(method_specific_id)
+            && method_specific_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '%' | ':'));
+
+        if !is_valid_method || !is_valid_method_specific_id {
+            return Err(invalid());
+        }
+
+        Ok(Did {
+            method: method.to_string(),
+            method_specific_id: method_specific_id.to_string(),
+        })
+    }
+}
+
+impl FromStr for DidUrl {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidDid(format!("{s} is not a valid DID URL"));
+
+        let (rest, fragment) = match s.split_once('#') {
+            Some((rest, fragment)) => (rest, Some(fragment)),
+            None => (s, None),
+        };
+        let (rest, query) = match rest.split_once('?') {
+            Some((rest, query)) => (rest, Some(query)),
+            None => (rest, None),
+        };
+        let (did, path) = match rest.find('/') {
+            Some(index) => (&rest[..index], Some(&rest[index..])),
+            None => (rest, None),
+        };
+
+        let did = Did::from_str(did).map_err(|_| invalid())?;
+
+        let is_valid_path = path.into_iter().all(|path| {
+            path.chars().all(|c| is_pchar(c) || c == '/') && is_pct_encoded_valid(path)
+        });
+        let is_valid_query = query.into_iter().all(is_valid_fragment);
+        let is_valid_fragment = fragment
+            .into_iter()
+            .all(|fragment| !fragment.is_empty() && is_valid_fragment(fragment));
+
+        if !is_valid_path || !is_valid_query || !is_valid_fragment {
+            return Err(invalid());
+        }
+
+        Ok(DidUrl {
+            did,
+            path: path.map(str::to_string),
+            query: query.map(str::to_string),
+            fragment: fragment.map(str::to_string),
+        })
+    }
+}
+
+impl std::fmt::Display for Did {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "did:{}:{}", self.method, self.method_specific_id)
+    }
+}
+
+impl std::fmt::Display for DidUrl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.did)?;
+        if let Some(path) = &self.path {
+            write!(f, "{path}")?;
+        }
+        if let Some(query) = &self.query {
+            write!(f, "?{query}")?;
+        }
+        if let Some(fragment) = &self.fragment {
+            write!(f, "#{fragment}")?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<String> for Did {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Did::from_str(&value)
+    }
+}
+
+impl TryFrom<String> for DidUrl {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        DidUrl::from_str(&value)
+    }
+}
+
+impl From<Did> for String {
+    fn from(did: Did) -> Self {
+        did.to_string()
+    }
+}
+
+impl From<DidUrl> for String {
+    fn from(did_url: DidUrl) -> Self {
+        did_url.to_string()
+    }
+}
+
+impl From<Did> for DidUrl {
+    fn from(did: Did) -> Self {
+        DidUrl {
+            did,
+            path: None,
+            query: None,
+            fragment: None,
+        }
+    }
+}
+
+// A relative DID URL that only carries a fragment, e.g. `#key-1`
+pub fn is_relative_did_url(val: &str) -> bool {
+    match val.strip_prefix('#') {
+        Some(fragment) => !fragment.is_empty() && is_valid_fragment(fragment),
+        None => false,
+    }
+}
+
+// fragment = *( pchar / "/" / "?" )
+// https://www.rfc-editor.org/rfc/rfc3986#section-3.5
+fn is_valid_fragment(val: &str) -> bool {
+    val.chars().all(|c| is_pchar(c) || c == '/' || c == '?') && is_pct_encoded_valid(val)
+}
+
+fn is_pchar(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "-._~%!$&'()*+,;=:@".contains(c)
+}
+
+fn is_pct_encoded_valid(val: &str) -> bool {
+    let bytes = val.as_bytes();
+    bytes.iter().enumerate().all(|(i, b)| {
+        *b != b'%'
+            || (bytes.get(i + 1).is_some_and(u8::is_ascii_hexdigit)
+                && bytes.get(i + 2).is_some_and(u8::is_ascii_hexdigit))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rstest::rstest]
+    #[case::did("did:example:123456789abcdefghi", Some(("example", "123456789abcdefghi")))]
+    #[case::did_with_colons("did:web:example.com:user:alice", Some(("web", "example.com:user:alice")))]
+    #[case::did_pct_encoded("did:web:localhost%3A8443", Some(("web", "localhost%3A8443")))]
+    #[case::missing_scheme("example:123", None)]
+    #[case::uppercase_method("did:Example:123", None)]
+    #[case::empty_method_specific_id("did:example:", None)]
+    #[case::trailing_colon("did:example:123:", None)]
+    #[case::invalid_pct_encoding("did:example:abc%zz", None)]
+    #[case::did_url("did:example:123#key-1", None)]
+    fn test_parse_did(#[case] did: &str, #[case] expected: Option<(&str, &str)>) {
+        match (Did::from_str(did), expected) {
+            (Ok(parsed), Some((method, method_specific_id))) => {
+                assert_eq!(parsed.method(), method);
+                assert_eq!(parsed.method_specific_id(), method_specific_id);
+                assert_eq!(parsed.to_string(), did);
+            }
+            (Err(_), None) => {}
+            (res, expected) => panic!("unexpected result {res:?}, expected {expected:?}"),
+        }
+    }
+
+    #[rstest::rstest]
+    #[case::fragment("did:example:123#key-1", Some((None, None, Some("key-1"))))]
+    #[case::path_query_fragment(
+        "did:example:123/path/to?service=files&relativeRef=%2Fdoc#key-1",
+        Some((Some("/path/to"), Some("service=files&relativeRef=%2Fdoc"), Some("key-1")))
+    )]
+    #[case::no_fragment("did:example:123", Some((None, None, None)))]
+    #[case::empty_fragment("did:example:123#", None)]
+    #[case::invalid_fragment("did:example:123#key 1", None)]
+    #[case::multiple_fragments("did:example:123#key#1", None)]
+    #[case::invalid_did("did:example#key-1", None)]
+    fn test_parse_did_url(
+        #[case] did_url: &str,
+        #[case] expected: Option<(Option<&str>, Option<&str>, Option<&str>)>,
+    ) {
+        match (DidUrl::from_str(did_url), expected) {
+            (Ok(parsed), Some((path, query, fragment))) => {
+                assert_eq!(parsed.did().to_string(), "did:example:123");
+                assert_eq!(parsed.path(), path);
+                assert_eq!(parsed.query(), query);
+                assert_eq!(parsed.fragment(), fragment);
+                assert_eq!(parsed.to_string(), did_url);
+            }
+            (Err(_), None) => {}
+            (res, expected) => panic!("unexpected result {res:?}, expected {expected:?}"),
+        }
+    }
+
+    #[test]
+    fn test_did_url_helpers() {
+        let did = Did::from_str("did:example:123").unwrap();
+
+        let did_url = did.with_fragment("key-1").unwrap();
+        assert_eq!(did_url.to_string(), "did:example:123#key-1");
+
+        let did_url = DidUrl::from_relative(&did, "?service=agent&versionId=2").unwrap();
+        assert_eq!(did_url.query_param("service"), Some("agent"));
+        assert_eq!(did_url.query_param("versionId"), Some("2"));
+        assert_eq!(did_url.query_param("versionTime"), None);
+
+        assert!(DidUrl::from_relative(&did, "key-1").is_err());
+
+        let encoded = serde_json::to_value(&did_url).unwrap();
+        assert_eq!(
+            encoded,
+            serde_json::json!("did:example:123?service=agent&versionId=2")
+        );
+        assert_eq!(serde_json::from_value::<DidUrl>(encoded).unwrap(), did_url);
+        assert!(serde_json::from_value::<Did>(serde_json::json!("did:example")).is_err());
+    }
+}
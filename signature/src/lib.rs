@@ -1 +1,2 @@
+pub mod did;
 pub mod suite;
@@ -7,6 +7,7 @@ const ED25519_VERIFICATION_KEY_2020: &str = "Ed25519VerificationKey2020";
 /// Ed25519 Multicodec constant
 pub const MULTICODEC_ED25519_PUB: &[u8] = &[0xed, 0x01];
 
+pub mod did;
 pub mod error;
 
 // Implementation of https://www.w3.org/community/reports/credentials/CG-FINAL-di-eddsa-2020-20220724/
@@ -107,6 +108,31 @@ fn get_prefixed_public_key(pk: &ed25519_zebra::VerificationKey) -> Vec<u8> {
     [MULTICODEC_ED25519_PUB, pk.as_ref()].concat()
 }
 
+// The DID of a key is its multibase encoded public key under the DID method, e.g. did:knox:z6Mk...
+// The DID method is validated when the key pair is created and base58btc only uses DID idchars.
+fn get_key_did(did_method: &str, encoded_public_key: &str) -> crate::did::Did {
+    crate::did::Did::new(did_method, encoded_public_key)
+        .expect("the DID method of the key pair is valid")
+}
+
+// The verification method of a key is the key itself in the DID Document, e.g. did:knox:z6Mk...#z6Mk...
+fn get_key_verification_method(did_method: &str, encoded_public_key: &str) -> crate::did::DidUrl {
+    get_key_did(did_method, encoded_public_key)
+        .with_fragment(encoded_public_key)
+        .expect("a base58btc encoded key is a valid fragment")
+}
+
+fn validate_did_method(
+    did_method: &str,
+    public_key: &ed25519_zebra::VerificationKey,
+) -> Result<(), error::Error> {
+    crate::did::Did::new(
+        did_method,
+        &super::PublicKey::get_encoded_public_key(public_key),
+    )?;
+    Ok(())
+}
+
 impl super::KeyPair<ed25519_zebra::SigningKey, ed25519_zebra::VerificationKey> for Ed25519KeyPair {
     fn get_did_method(&self) -> String {
         self.did_method.clone()
@@ -114,7 +140,7 @@ impl super::KeyPair<ed25519_zebra::SigningKey, ed25519_zebra::VerificationKey> f
 
     fn get_did(&self) -> String {
         let encoded_pk = super::PublicKey::get_encoded_public_key(&self.master_public_key);
-        format!("did:{0}:{1}", self.did_method, encoded_pk)
+        get_key_did(&self.did_method, &encoded_pk).to_string()
     }
 
     fn get_public_key_encoded(&self, relation: crate::suite::VerificationRelation) -> String {
@@ -194,6 +220,7 @@ impl Ed25519KeyPair {
             .map_err(|e| error::Error::SigningKeyConversion(e.to_string()))?;
 
         let vk = ed25519_zebra::VerificationKey::from(&sk);
+        validate_did_method(&did_method, &vk)?;
 
         Ok(Self {
             master_public_key: vk,
@@ -241,6 +268,7 @@ impl Ed25519KeyPair {
             .map_err(|e| error::Error::SigningKeyConversion(e.to_string()))?;
 
         let vk = ed25519_zebra::VerificationKey::from(&sk);
+        validate_did_method(&did_method, &vk)?;
 
         Ok(Self {
             master_public_key: vk,
@@ -289,8 +317,7 @@ impl super::DIDSigner<Ed25519Signature> for Ed25519DidSigner {
 
     fn get_verification_method(&self, _relation: super::VerificationRelation) -> String {
         let encoded_pk = super::PublicKey::get_encoded_public_key(&self.public_key);
-
-        format!("did:{0}:{1}#{1}", self.did_method, encoded_pk)
+        get_key_verification_method(&self.did_method, &encoded_pk).to_string()
     }
 
     fn encode(&self, sig: Ed25519Signature) -> String {
@@ -405,7 +432,7 @@ impl super::DIDVerifier<Ed25519Signature> for Ed25519DidVerifier {
 
     fn get_verification_method(&self, relation: super::VerificationRelation) -> String {
         let encoded_pk = self.get_encoded_public_key_by_relation(relation);
-        format!("did:{0}:{1}#{1}", self.did_method, encoded_pk)
+        get_key_verification_method(&self.did_method, &encoded_pk).to_string()
     }
 
     fn get_encoded_public_key_by_relation(&self, relation: super::VerificationRelation) -> String {
@@ -418,7 +445,7 @@ impl super::DIDVerifier<Ed25519Signature> for Ed25519DidVerifier {
 
     fn get_did(&self) -> String {
         let encoded_pk = super::PublicKey::get_encoded_public_key(&self.public_key);
-        format!("did:{0}:{1}", self.did_method, encoded_pk)
+        get_key_did(&self.did_method, &encoded_pk).to_string()
     }
 
     fn decoded_verify(&self, msg: &[u8], data: String) -> Result<(), super::error::Error> {
@@ -428,6 +455,31 @@ impl super::DIDVerifier<Ed25519Signature> for Ed25519DidVerifier {
 }
 
 impl Ed25519DidVerifier {
+    /// Given a multibase encoded Ed25519 public key, e.g. the `publicKeyMultibase` of a verification method,
+    /// create a verifier for the signatures of that key.
+    pub fn from_public_key_multibase(
+        did_method: String,
+        public_key_multibase: &str,
+    ) -> Result<Self, error::Error> {
+        let (base, public_key) = multibase::decode(public_key_multibase)?;
+        let public_key = match public_key.strip_prefix(MULTICODEC_ED25519_PUB) {
+            Some(public_key) if base == multibase::Base::Base58Btc => public_key,
+            _ => {
+                return Err(error::Error::KeyGeneration(
+                    "Invalid Ed25519 public key encoding".to_string(),
+                ))
+            }
+        };
+        let public_key = ed25519_zebra::VerificationKey::try_from(public_key)
+            .map_err(|e| error::Error::KeyGeneration(e.to_string()))?;
+        validate_did_method(&did_method, &public_key)?;
+
+        Ok(Self {
+            public_key,
+            did_method,
+        })
+    }
+
     fn get_public_key_by_relation(
         &self,
         relation: super::VerificationRelation,
@@ -459,6 +511,37 @@ impl Ed25519DidSigner {
 mod tests {
     use crate::suite::KeyPair;
 
+    #[test]
+    fn test_create_verifier_from_multibase() {
+        let public_key = "z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2";
+        let verifier =
+            super::Ed25519DidVerifier::from_public_key_multibase("key".to_string(), public_key)
+                .unwrap();
+        assert_eq!(
+            crate::suite::DIDVerifier::get_did(&verifier),
+            format!("did:key:{public_key}")
+        );
+        assert_eq!(
+            crate::suite::DIDVerifier::get_verification_method(
+                &verifier,
+                crate::suite::VerificationRelation::AssertionMethod
+            ),
+            format!("did:key:{public_key}#{public_key}")
+        );
+
+        // X25519 key and invalid multibase
+        for public_key in [
+            "z6LSj72tK8brWgZja8NLRwPigth2T9QRiG1uH9oKZuKjdh9p",
+            "not-multibase",
+        ] {
+            assert!(super::Ed25519DidVerifier::from_public_key_multibase(
+                "key".to_string(),
+                public_key
+            )
+            .is_err());
+        }
+    }
+
     #[test]
     fn test_create_keypair_from_multibase() {
         let public_key = "z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2".to_string();
@@ -467,4 +550,12 @@ mod tests {
         let kp = super::Ed25519KeyPair::from_private_key(did_method, private_key).unwrap();
         assert_eq!(kp.get_encoded_master_public_key(), public_key);
     }
+
+    #[test]
+    fn test_invalid_did_method() {
+        assert!(matches!(
+            super::Ed25519KeyPair::new("Key".to_string(), None),
+            Err(super::error::Error::Did(_))
+        ));
+    }
 }
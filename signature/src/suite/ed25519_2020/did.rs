@@ -0,0 +1,252 @@
+use super::{Ed25519KeyPair, ED25519_VERIFICATION_KEY_2020};
+
+// Helpers producing DIDs whose DID Document is encoded in the identifier itself, see
+// https://github.com/quartzjer/did-jwk/blob/main/spec.md and https://identity.foundation/peer-did-method-spec/
+
+/// X25519 Multicodec constant
+pub const MULTICODEC_X25519_PUB: &[u8] = &[0xec, 0x01];
+/// JSON Multicodec constant (0x0200) encoded as a varint
+pub const MULTICODEC_JSON: &[u8] = &[0x80, 0x04];
+/// SHA2-256 Multihash prefix
+pub const MULTIHASH_SHA2_256: &[u8] = &[0x12, 0x20];
+
+const X25519_KEY_AGREEMENT_KEY_2020: &str = "X25519KeyAgreementKey2020";
+
+/// An Ed25519 public key as a JSON Web Key (https://www.rfc-editor.org/rfc/rfc8037)
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Ed25519PublicJwk {
+    pub kty: String,
+    pub crv: String,
+    pub x: String,
+}
+
+/// Given an Ed25519 public key, return its Montgomery form to be used as an X25519 key agreement key.
+pub fn derive_x25519_public_key(
+    ed25519_public_key: &[u8],
+) -> Result<[u8; 32], super::error::Error> {
+    let compressed = curve25519_dalek::edwards::CompressedEdwardsY::from_slice(ed25519_public_key)
+        .map_err(|e| super::error::Error::KeyGeneration(e.to_string()))?;
+    let point = compressed.decompress().ok_or_else(|| {
+        super::error::Error::KeyGeneration("Invalid Ed25519 public key".to_string())
+    })?;
+
+    Ok(point.to_montgomery().to_bytes())
+}
+
+impl Ed25519KeyPair {
+    pub fn get_public_key_jwk(&self) -> Ed25519PublicJwk {
+        Ed25519PublicJwk {
+            kty: "OKP".to_string(),
+            crv: "Ed25519".to_string(),
+            x: base64::encode_config(self.master_public_key.as_ref(), base64::URL_SAFE_NO_PAD),
+        }
+    }
+
+    /// did:jwk:<base64url encoded JWK>
+    pub fn get_did_jwk(&self) -> String {
+        let jwk = serde_json::to_vec(&self.get_public_key_jwk()).expect("JWK is serializable");
+
+        format!(
+            "did:jwk:{}",
+            base64::encode_config(jwk, base64::URL_SAFE_NO_PAD)
+        )
+    }
+
+    /// did:peer:0<multibase encoded public key>, the inception key is the only key of the document.
+    pub fn get_did_peer_0(&self) -> String {
+        format!(
+            "did:peer:0{}",
+            super::super::PublicKey::get_encoded_public_key(&self.master_public_key)
+        )
+    }
+
+    /// did:peer:2 with the Ed25519 key for verification and its derived X25519 key for key agreement.
+    /// The `services` are abbreviated and appended to the DID.
+    pub fn get_did_peer_2(
+        &self,
+        services: &[serde_json::Value],
+    ) -> Result<String, super::error::Error> {
+        let mut did = format!(
+            "did:peer:2.E{}.V{}",
+            self.get_encoded_x25519_public_key()?,
+            super::super::PublicKey::get_encoded_public_key(&self.master_public_key)
+        );
+
+        for service in services {
+            let service = serde_json::to_vec(&abbreviate_service(service))
+                .map_err(|e| super::error::Error::KeyGeneration(e.to_string()))?;
+            did.push_str(".S");
+            did.push_str(&base64::encode_config(service, base64::URL_SAFE_NO_PAD));
+        }
+
+        Ok(did)
+    }
+
+    /// Long form did:peer:4<hash>:<encoded document>, the short form is did:peer:4<hash>.
+    pub fn get_did_peer_4(&self) -> Result<String, super::error::Error> {
+        let ed25519_key = super::super::PublicKey::get_encoded_public_key(&self.master_public_key);
+        let x25519_key = self.get_encoded_x25519_public_key()?;
+
+        let document = serde_json::json!({
+            "@context": [
+                "https://www.w3.org/ns/did/v1",
+                "https://w3id.org/security/suites/ed25519-2020/v1",
+                "https://w3id.org/security/suites/x25519-2020/v1"
+            ],
+            "verificationMethod": [{
+                "id": "#key-1",
+                "type": ED25519_VERIFICATION_KEY_2020,
+                "publicKeyMultibase": ed25519_key
+            }, {
+                "id": "#key-2",
+                "type": X25519_KEY_AGREEMENT_KEY_2020,
+                "publicKeyMultibase": x25519_key
+            }],
+            "authentication": ["#key-1"],
+            "assertionMethod": ["#key-1"],
+            "capabilityInvocation": ["#key-1"],
+            "capabilityDelegation": ["#key-1"],
+            "keyAgreement": ["#key-2"]
+        });
+        let document = serde_json::to_vec(&document)
+            .map_err(|e| super::error::Error::KeyGeneration(e.to_string()))?;
+
+        let encoded_document = multibase::encode(
+            multibase::Base::Base58Btc,
+            [MULTICODEC_JSON, document.as_slice()].concat(),
+        );
+
+        Ok(format!(
+            "did:peer:4{}:{encoded_document}",
+            get_peer_4_hash(&encoded_document)
+        ))
+    }
+
+    fn get_encoded_x25519_public_key(&self) -> Result<String, super::error::Error> {
+        let x25519_public_key = derive_x25519_public_key(self.master_public_key.as_ref())?;
+
+        Ok(multibase::encode(
+            multibase::Base::Base58Btc,
+            [MULTICODEC_X25519_PUB, x25519_public_key.as_slice()].concat(),
+        ))
+    }
+}
+
+/// The hash of a did:peer:4 is the multibase encoded SHA2-256 multihash of the encoded document.
+pub fn get_peer_4_hash(encoded_document: &str) -> String {
+    use sha2::Digest;
+
+    let digest = sha2::Sha256::digest(encoded_document.as_bytes());
+    multibase::encode(
+        multibase::Base::Base58Btc,
+        [MULTIHASH_SHA2_256, digest.as_slice()].concat(),
+    )
+}
+
+// https://identity.foundation/peer-did-method-spec/#generating-a-didpeer2
+fn abbreviate_service(service: &serde_json::Value) -> serde_json::Value {
+    match service {
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    let key = match key.as_str() {
+                        "type" => "t",
+                        "serviceEndpoint" => "s",
+                        "routingKeys" => "r",
+                        "accept" => "a",
+                        key => key,
+                    };
+                    let value = match value.as_str() {
+                        Some("DIDCommMessaging") => serde_json::json!("dm"),
+                        _ => abbreviate_service(value),
+                    };
+                    (key.to_string(), value)
+                })
+                .collect(),
+        ),
+        value => value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Ed25519KeyPair;
+
+    fn get_key_pair() -> Ed25519KeyPair {
+        Ed25519KeyPair::from_private_key(
+            "key".to_string(),
+            "z3u2en7t5LR2WtQH5PfFqMqwVHBeXouLzo6haApm8XHqvjxq".to_string(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_get_did_jwk() {
+        let did = get_key_pair().get_did_jwk();
+        let encoded_jwk = did.strip_prefix("did:jwk:").unwrap();
+        let jwk: super::Ed25519PublicJwk = serde_json::from_slice(
+            &base64::decode_config(encoded_jwk, base64::URL_SAFE_NO_PAD).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(jwk, get_key_pair().get_public_key_jwk());
+        assert_eq!(jwk.kty, "OKP");
+        assert_eq!(jwk.crv, "Ed25519");
+    }
+
+    #[test]
+    fn test_get_did_peer() {
+        let kp = get_key_pair();
+
+        assert_eq!(
+            kp.get_did_peer_0(),
+            "did:peer:0z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2"
+        );
+
+        let did = kp
+            .get_did_peer_2(&[serde_json::json!({
+                "type": "DIDCommMessaging",
+                "serviceEndpoint": {"uri": "https://example.com/endpoint"}
+            })])
+            .unwrap();
+        let segments = did.split('.').collect::<Vec<&str>>();
+        assert_eq!(segments[0], "did:peer:2");
+        assert!(segments[1].starts_with("Ez6LS"));
+        assert_eq!(
+            segments[2],
+            "Vz6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2"
+        );
+        let service: serde_json::Value = serde_json::from_slice(
+            &base64::decode_config(&segments[3][1..], base64::URL_SAFE_NO_PAD).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            service,
+            serde_json::json!({"t": "dm", "s": {"uri": "https://example.com/endpoint"}})
+        );
+
+        let did = kp.get_did_peer_4().unwrap();
+        let (hash, encoded_document) = did
+            .strip_prefix("did:peer:4")
+            .unwrap()
+            .split_once(':')
+            .unwrap();
+        assert_eq!(hash, super::get_peer_4_hash(encoded_document));
+    }
+
+    #[test]
+    fn test_derive_x25519_public_key() {
+        // Test vector from https://w3c-ccg.github.io/did-method-key/#ed25519-x25519
+        let (_, ed25519_key) =
+            multibase::decode("z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK").unwrap();
+        let x25519_key = super::derive_x25519_public_key(&ed25519_key[2..]).unwrap();
+
+        assert_eq!(
+            multibase::encode(
+                multibase::Base::Base58Btc,
+                [super::MULTICODEC_X25519_PUB, x25519_key.as_slice()].concat()
+            ),
+            "z6LSj72tK8brWgZja8NLRwPigth2T9QRiG1uH9oKZuKjdh9p"
+        );
+    }
+}
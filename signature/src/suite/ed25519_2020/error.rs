@@ -14,4 +14,7 @@ pub enum Error {
 
     #[error("Error decoding multibase key: {0}")]
     MultibaseDecode(#[from] multibase::Error),
+
+    #[error(transparent)]
+    Did(#[from] crate::did::Error),
 }
@@ -2,5 +2,10 @@ pub use signature;
 pub use ssi_core::*;
 pub mod resolver {
     pub use ephemeral_resolver;
+    pub use jwk_resolver;
+    pub use key_resolver;
+    pub use peer_resolver;
     pub use registry_resolver;
+    pub use ssi_core::resolver::UniversalResolver;
+    pub use web_resolver;
 }
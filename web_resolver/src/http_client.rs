@@ -0,0 +1,45 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ReqwestClient {
+    inner: reqwest::Client,
+}
+
+impl ReqwestClient {
+    pub fn new() -> Self {
+        Self {
+            inner: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpClient for ReqwestClient {
+    async fn get(&self, url: String) -> Result<HttpResponse, String> {
+        let res = self
+            .inner
+            .get(url)
+            .header(
+                reqwest::header::ACCEPT,
+                "application/did+json, application/json",
+            )
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let status = res.status().as_u16();
+        let body = res.bytes().await.map_err(|e| e.to_string())?.to_vec();
+
+        Ok(HttpResponse { status, body })
+    }
+}
+
+/// HTTP client used to fetch `did:web` documents, implement it to customize the transport (proxies, TLS, caching, etc.).
+#[async_trait::async_trait]
+pub trait HttpClient: Send + Sync + std::fmt::Debug {
+    async fn get(&self, url: String) -> Result<HttpResponse, String>;
+}
@@ -0,0 +1,334 @@
+use std::str::FromStr;
+
+pub mod http_client;
+
+// Implementation of https://w3c-ccg.github.io/did-method-web/
+pub const DID_METHOD: &str = "web";
+
+const WELL_KNOWN_PATH: &str = ".well-known";
+const DID_DOCUMENT_FILE: &str = "did.json";
+
+#[derive(Clone, Debug)]
+pub struct WebResolver<T = http_client::ReqwestClient>
+where
+    T: http_client::HttpClient + 'static,
+{
+    client: T,
+}
+
+impl WebResolver<http_client::ReqwestClient> {
+    pub fn new() -> Self {
+        WebResolver {
+            client: http_client::ReqwestClient::new(),
+        }
+    }
+}
+
+impl Default for WebResolver<http_client::ReqwestClient> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> WebResolver<T>
+where
+    T: http_client::HttpClient,
+{
+    pub fn with_client(client: T) -> Self {
+        WebResolver { client }
+    }
+}
+
+/// Given a `did:web`, return the URL the DID Document is published at.
+/// `did:web:example.com` maps to `https://example.com/.well-known/did.json`
+/// and `did:web:example.com:user:alice` maps to `https://example.com/user/alice/did.json`.
+pub fn get_document_url(did: &str) -> Result<String, ssi_core::error::ResolverError> {
+    let parsed_did = ssi_core::did::Did::from_str(did)?;
+    if parsed_did.method() != DID_METHOD {
+        return Err(ssi_core::error::ResolverError::MethodNotSupported(format!(
+            "did:{} is not supported by the did:web resolver",
+            parsed_did.method()
+        )));
+    }
+
+    let mut segments = parsed_did.method_specific_id().split(':');
+    // The port of the domain is percent encoded, e.g. did:web:localhost%3A8443
+    let domain = segments
+        .next()
+        .map(|domain| percent_encoding::percent_decode_str(domain).decode_utf8_lossy())
+        .unwrap_or_default();
+    if domain.is_empty() || domain.contains(['/', '?', '#']) {
+        return Err(ssi_core::error::ResolverError::InvalidDid(format!(
+            "{did} does not contain a valid domain"
+        )));
+    }
+
+    let path = segments.collect::<Vec<&str>>();
+    if path.is_empty() {
+        Ok(format!(
+            "https://{domain}/{WELL_KNOWN_PATH}/{DID_DOCUMENT_FILE}"
+        ))
+    } else {
+        Ok(format!(
+            "https://{domain}/{}/{DID_DOCUMENT_FILE}",
+            path.join("/")
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl<T> ssi_core::DIDResolver for WebResolver<T>
+where
+    T: http_client::HttpClient,
+{
+    fn get_method(&self) -> &'static str {
+        DID_METHOD
+    }
+
+    async fn create(
+        &self,
+        _did: String,
+        _document: serde_json::Value,
+    ) -> Result<(), ssi_core::error::ResolverError> {
+        Err(ssi_core::error::ResolverError::MethodNotSupported(
+            "did:web documents are published by hosting them on the domain".to_string(),
+        ))
+    }
+
+    async fn resolve(
+        &self,
+        did: String,
+    ) -> Result<ssi_core::ResolveResponse, ssi_core::error::ResolverError> {
+        let url = get_document_url(&did)?;
+
+        let res = self
+            .client
+            .get(url.clone())
+            .await
+            .map_err(ssi_core::error::ResolverError::NetworkFailure)?;
+
+        match res.status {
+            200..=299 => {}
+            404 | 410 => {
+                return Err(ssi_core::error::ResolverError::DocumentNotFound(format!(
+                    "No document found at {url}"
+                )))
+            }
+            status => {
+                return Err(ssi_core::error::ResolverError::NetworkFailure(format!(
+                    "Unexpected status {status} fetching {url}"
+                )))
+            }
+        }
+
+        let document: serde_json::Value = serde_json::from_slice(&res.body)
+            .map_err(|e| ssi_core::error::ResolverError::InvalidData(e.to_string()))?;
+        let parsed_document = ssi_core::identity::DidDocument::try_from(document.clone())
+            .map_err(|e| ssi_core::error::ResolverError::InvalidData(e.to_string()))?;
+        if parsed_document.id != did {
+            return Err(ssi_core::error::ResolverError::InvalidData(format!(
+                "Document id {} does not match {did}",
+                parsed_document.id
+            )));
+        }
+
+        Ok(ssi_core::ResolveResponse {
+            did_document: document,
+            did_document_metadata: ssi_core::DidDocumentMetadata::default(),
+            did_resolution_metadata: ssi_core::ResolutionMetadata {
+                content_type: Some(ssi_core::DID_JSON_CONTENT_TYPE.to_string()),
+                ..Default::default()
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        get_document_url,
+        http_client::{HttpClient, HttpResponse, ReqwestClient},
+        WebResolver,
+    };
+    use ssi_core::DIDResolver;
+
+    // Serves the DID documents over plain HTTP, the client below downgrades the https URLs built by the resolver.
+    #[derive(Debug)]
+    struct LocalClient {
+        inner: ReqwestClient,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpClient for LocalClient {
+        async fn get(&self, url: String) -> Result<HttpResponse, String> {
+            self.inner.get(url.replacen("https://", "http://", 1)).await
+        }
+    }
+
+    // Binds the server before building the documents, as the DIDs contain the port of the server.
+    async fn start_server(
+        documents: impl FnOnce(std::net::SocketAddr) -> Vec<(&'static str, String)>,
+    ) -> std::net::SocketAddr {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let documents = std::sync::Arc::new(
+            documents(addr)
+                .into_iter()
+                .collect::<std::collections::HashMap<&'static str, String>>(),
+        );
+
+        let make_service = hyper::service::make_service_fn(move |_| {
+            let documents = documents.clone();
+            async move {
+                Ok::<_, std::convert::Infallible>(hyper::service::service_fn(
+                    move |req: hyper::Request<hyper::Body>| {
+                        let res = match documents.get(req.uri().path()) {
+                            Some(document) => {
+                                hyper::Response::new(hyper::Body::from(document.clone()))
+                            }
+                            None => hyper::Response::builder()
+                                .status(404)
+                                .body(hyper::Body::empty())
+                                .unwrap(),
+                        };
+                        async move { Ok::<_, std::convert::Infallible>(res) }
+                    },
+                ))
+            }
+        });
+
+        tokio::spawn(
+            hyper::Server::from_tcp(listener)
+                .unwrap()
+                .serve(make_service),
+        );
+        addr
+    }
+
+    fn create_did(addr: std::net::SocketAddr, path: &str) -> String {
+        format!("did:web:127.0.0.1%3A{}{path}", addr.port())
+    }
+
+    fn create_did_doc(did: &str) -> serde_json::Value {
+        serde_json::json!({
+            "@context": ["https://www.w3.org/ns/did/v1", "https://w3id.org/security/suites/ed25519-2020/v1"],
+            "id": did,
+            "verificationMethod": [{
+                "id": format!("{did}#key-1"),
+                "type": "Ed25519VerificationKey2020",
+                "controller": did,
+                "publicKeyMultibase": "z6MkmgYPyjwqrMyHYBFfEcetAAoW7A9njsC4ToZ1WnjAgRL1"
+            }],
+            "assertionMethod": [format!("{did}#key-1")]
+        })
+    }
+
+    fn create_resolver() -> WebResolver<LocalClient> {
+        WebResolver::with_client(LocalClient {
+            inner: ReqwestClient::new(),
+        })
+    }
+
+    #[rstest::rstest]
+    #[case::domain(
+        "did:web:w3c-ccg.github.io",
+        Ok("https://w3c-ccg.github.io/.well-known/did.json")
+    )]
+    #[case::path(
+        "did:web:w3c-ccg.github.io:user:alice",
+        Ok("https://w3c-ccg.github.io/user/alice/did.json")
+    )]
+    #[case::port(
+        "did:web:example.com%3A3000:user:alice",
+        Ok("https://example.com:3000/user/alice/did.json")
+    )]
+    #[case::invalid_domain(
+        "did:web:example.com%2Fpath",
+        Err(ssi_core::error::ResolverError::InvalidDid(
+            "did:web:example.com%2Fpath does not contain a valid domain".to_string()
+        ))
+    )]
+    #[case::method_not_supported(
+        "did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK",
+        Err(ssi_core::error::ResolverError::MethodNotSupported(
+            "did:key is not supported by the did:web resolver".to_string()
+        ))
+    )]
+    fn test_get_document_url(
+        #[case] did: &str,
+        #[case] expected: Result<&str, ssi_core::error::ResolverError>,
+    ) {
+        assert_eq!(get_document_url(did), expected.map(str::to_string));
+    }
+
+    #[tokio::test]
+    async fn test_resolve() {
+        let addr = start_server(|addr| {
+            vec![
+                (
+                    "/.well-known/did.json",
+                    create_did_doc(&create_did(addr, "")).to_string(),
+                ),
+                (
+                    "/user/alice/did.json",
+                    create_did_doc(&create_did(addr, ":user:alice")).to_string(),
+                ),
+            ]
+        })
+        .await;
+        let resolver = create_resolver();
+
+        for did in [create_did(addr, ""), create_did(addr, ":user:alice")] {
+            let rsp = resolver.resolve(did.clone()).await.unwrap();
+            assert_eq!(rsp.did_document, create_did_doc(&did));
+            assert_eq!(
+                rsp.did_resolution_metadata.content_type.as_deref(),
+                Some(ssi_core::DID_JSON_CONTENT_TYPE)
+            );
+        }
+    }
+
+    #[rstest::rstest]
+    #[case::not_found(":user:bob", "DocumentNotFound")]
+    #[case::id_mismatch(":user:mallory", "InvalidData")]
+    #[case::invalid_json(":user:eve", "InvalidData")]
+    #[case::invalid_document(":user:trudy", "InvalidData")]
+    #[tokio::test]
+    async fn test_resolve_error(#[case] path: &str, #[case] expected_kind: &str) {
+        let addr = start_server(|_| {
+            vec![
+                (
+                    "/user/mallory/did.json",
+                    create_did_doc("did:web:example.com").to_string(),
+                ),
+                ("/user/eve/did.json", "{".to_string()),
+                (
+                    "/user/trudy/did.json",
+                    serde_json::json!({"id": "not-a-did"}).to_string(),
+                ),
+            ]
+        })
+        .await;
+        let resolver = create_resolver();
+
+        let err = resolver.resolve(create_did(addr, path)).await.unwrap_err();
+        match (err, expected_kind) {
+            (ssi_core::error::ResolverError::DocumentNotFound(_), "DocumentNotFound") => {}
+            (ssi_core::error::ResolverError::InvalidData(_), "InvalidData") => {}
+            (err, expected) => panic!("unexpected error {err:?}, expected {expected}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create() {
+        let resolver = create_resolver();
+        let did = "did:web:example.com".to_string();
+
+        let res = resolver.create(did.clone(), create_did_doc(&did)).await;
+        assert!(matches!(
+            res,
+            Err(ssi_core::error::ResolverError::MethodNotSupported(_))
+        ));
+    }
+}